@@ -1,3 +1,5 @@
 fn main() {
+    // Migrations are embedded by `sqlx::migrate!`; rebuild when one is added
+    println!("cargo:rerun-if-changed=migrations");
     tauri_build::build()
 }
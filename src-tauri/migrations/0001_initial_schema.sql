@@ -0,0 +1,112 @@
+-- Baseline: the schema as created by the last build before versioned migrations.
+-- Databases from those builds are brought up to this layout before it runs
+-- (see `schema::adopt_legacy`), so every statement must stay idempotent.
+
+CREATE TABLE IF NOT EXISTS deliberations (
+    id TEXT PRIMARY KEY,
+    question TEXT NOT NULL,
+    consensus TEXT,
+    created_at INTEGER NOT NULL,
+    completed BOOLEAN NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS topics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    topic TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    created_by TEXT
+);
+
+CREATE TABLE IF NOT EXISTS rounds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    deliberation_id TEXT NOT NULL,
+    round_number INTEGER NOT NULL,
+    FOREIGN KEY (deliberation_id) REFERENCES deliberations(id)
+);
+
+CREATE TABLE IF NOT EXISTS responses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    round_id INTEGER NOT NULL,
+    member_name TEXT NOT NULL,
+    model TEXT NOT NULL,
+    response TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    reasoning TEXT,
+    FOREIGN KEY (round_id) REFERENCES rounds(id)
+);
+
+CREATE TABLE IF NOT EXISTS text_chunks (
+    id TEXT PRIMARY KEY,
+    deliberation_id TEXT NOT NULL,
+    text TEXT NOT NULL,
+    chunk_type TEXT NOT NULL,
+    chunk_metadata TEXT,
+    FOREIGN KEY (deliberation_id) REFERENCES deliberations(id)
+);
+
+CREATE TABLE IF NOT EXISTS embeddings (
+    chunk_id TEXT PRIMARY KEY,
+    embedding BLOB NOT NULL,
+    dimension INTEGER NOT NULL,
+    model TEXT,
+    FOREIGN KEY (chunk_id) REFERENCES text_chunks(id)
+);
+
+CREATE TABLE IF NOT EXISTS reputation (
+    agent_id TEXT PRIMARY KEY,
+    tier TEXT NOT NULL,
+    accuracy REAL NOT NULL,
+    reasoning REAL NOT NULL,
+    contribution REAL NOT NULL,
+    total_votes INTEGER NOT NULL,
+    successful_consensus INTEGER NOT NULL,
+    last_updated INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS chat_logs (
+    id TEXT PRIMARY KEY,
+    channel TEXT NOT NULL,
+    author TEXT NOT NULL,
+    author_type TEXT NOT NULL,
+    content TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    signature TEXT,
+    reply_to TEXT,
+    reasoning TEXT,
+    context_cleared INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS chat_embeddings (
+    message_id TEXT PRIMARY KEY,
+    embedding BLOB NOT NULL,
+    dimension INTEGER NOT NULL,
+    model TEXT,
+    FOREIGN KEY (message_id) REFERENCES chat_logs(id)
+);
+
+CREATE TABLE IF NOT EXISTS documents (
+    id TEXT PRIMARY KEY,
+    source_path TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    format TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    chunk_count INTEGER NOT NULL,
+    ingested_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS document_chunks (
+    id TEXT PRIMARY KEY,
+    document_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    heading TEXT,
+    text TEXT NOT NULL,
+    FOREIGN KEY (document_id) REFERENCES documents(id)
+);
+
+CREATE TABLE IF NOT EXISTS document_embeddings (
+    chunk_id TEXT PRIMARY KEY,
+    embedding BLOB NOT NULL,
+    dimension INTEGER NOT NULL,
+    model TEXT,
+    FOREIGN KEY (chunk_id) REFERENCES document_chunks(id)
+);
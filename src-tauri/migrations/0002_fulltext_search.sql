@@ -0,0 +1,95 @@
+-- Full-text (BM25) indexes for lexical and hybrid search.
+-- External-content FTS5 tables kept in sync by triggers. The BEFORE INSERT
+-- trigger covers INSERT OR REPLACE, whose implicit delete fires no trigger.
+
+-- Deliberation text chunks
+CREATE VIRTUAL TABLE IF NOT EXISTS fts_chunks USING fts5(text, content='text_chunks', content_rowid='rowid');
+
+CREATE TRIGGER IF NOT EXISTS fts_chunks_bi BEFORE INSERT ON text_chunks BEGIN
+    INSERT INTO fts_chunks(fts_chunks, rowid, text) SELECT 'delete', rowid, text FROM text_chunks WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_chunks_ai AFTER INSERT ON text_chunks BEGIN
+    INSERT INTO fts_chunks(rowid, text) VALUES (new.rowid, new.text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_chunks_ad AFTER DELETE ON text_chunks BEGIN
+    INSERT INTO fts_chunks(fts_chunks, rowid, text) VALUES ('delete', old.rowid, old.text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_chunks_au AFTER UPDATE OF text ON text_chunks BEGIN
+    INSERT INTO fts_chunks(fts_chunks, rowid, text) VALUES ('delete', old.rowid, old.text);
+    INSERT INTO fts_chunks(rowid, text) VALUES (new.rowid, new.text);
+END;
+
+-- Index rows that existed before this migration
+INSERT INTO fts_chunks(fts_chunks) VALUES ('rebuild');
+
+-- Questions and verdicts
+CREATE VIRTUAL TABLE IF NOT EXISTS fts_deliberations USING fts5(question, consensus, content='deliberations', content_rowid='rowid');
+
+CREATE TRIGGER IF NOT EXISTS fts_deliberations_bi BEFORE INSERT ON deliberations BEGIN
+    INSERT INTO fts_deliberations(fts_deliberations, rowid, question, consensus) SELECT 'delete', rowid, question, consensus FROM deliberations WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_deliberations_ai AFTER INSERT ON deliberations BEGIN
+    INSERT INTO fts_deliberations(rowid, question, consensus) VALUES (new.rowid, new.question, new.consensus);
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_deliberations_ad AFTER DELETE ON deliberations BEGIN
+    INSERT INTO fts_deliberations(fts_deliberations, rowid, question, consensus) VALUES ('delete', old.rowid, old.question, old.consensus);
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_deliberations_au AFTER UPDATE OF question, consensus ON deliberations BEGIN
+    INSERT INTO fts_deliberations(fts_deliberations, rowid, question, consensus) VALUES ('delete', old.rowid, old.question, old.consensus);
+    INSERT INTO fts_deliberations(rowid, question, consensus) VALUES (new.rowid, new.question, new.consensus);
+END;
+
+-- Index rows that existed before this migration
+INSERT INTO fts_deliberations(fts_deliberations) VALUES ('rebuild');
+
+-- Chat messages
+CREATE VIRTUAL TABLE IF NOT EXISTS fts_chat USING fts5(content, content='chat_logs', content_rowid='rowid');
+
+CREATE TRIGGER IF NOT EXISTS fts_chat_bi BEFORE INSERT ON chat_logs BEGIN
+    INSERT INTO fts_chat(fts_chat, rowid, content) SELECT 'delete', rowid, content FROM chat_logs WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_chat_ai AFTER INSERT ON chat_logs BEGIN
+    INSERT INTO fts_chat(rowid, content) VALUES (new.rowid, new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_chat_ad AFTER DELETE ON chat_logs BEGIN
+    INSERT INTO fts_chat(fts_chat, rowid, content) VALUES ('delete', old.rowid, old.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_chat_au AFTER UPDATE OF content ON chat_logs BEGIN
+    INSERT INTO fts_chat(fts_chat, rowid, content) VALUES ('delete', old.rowid, old.content);
+    INSERT INTO fts_chat(rowid, content) VALUES (new.rowid, new.content);
+END;
+
+-- Index rows that existed before this migration
+INSERT INTO fts_chat(fts_chat) VALUES ('rebuild');
+
+-- Ingested document chunks
+CREATE VIRTUAL TABLE IF NOT EXISTS fts_documents USING fts5(heading, text, content='document_chunks', content_rowid='rowid');
+
+CREATE TRIGGER IF NOT EXISTS fts_documents_bi BEFORE INSERT ON document_chunks BEGIN
+    INSERT INTO fts_documents(fts_documents, rowid, heading, text) SELECT 'delete', rowid, heading, text FROM document_chunks WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_documents_ai AFTER INSERT ON document_chunks BEGIN
+    INSERT INTO fts_documents(rowid, heading, text) VALUES (new.rowid, new.heading, new.text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_documents_ad AFTER DELETE ON document_chunks BEGIN
+    INSERT INTO fts_documents(fts_documents, rowid, heading, text) VALUES ('delete', old.rowid, old.heading, old.text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS fts_documents_au AFTER UPDATE OF heading, text ON document_chunks BEGIN
+    INSERT INTO fts_documents(fts_documents, rowid, heading, text) VALUES ('delete', old.rowid, old.heading, old.text);
+    INSERT INTO fts_documents(rowid, heading, text) VALUES (new.rowid, new.heading, new.text);
+END;
+
+-- Index rows that existed before this migration
+INSERT INTO fts_documents(fts_documents) VALUES ('rebuild');
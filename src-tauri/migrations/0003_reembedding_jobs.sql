@@ -0,0 +1,26 @@
+-- Background re-embedding after the embedding model changes.
+-- New vectors are staged per job and only swapped into the live embedding
+-- tables once every item has been embedded.
+
+CREATE TABLE IF NOT EXISTS reembed_jobs (
+    id TEXT PRIMARY KEY,
+    target_model TEXT NOT NULL,
+    status TEXT NOT NULL,           -- running | completed | cancelled
+    total INTEGER NOT NULL,
+    done INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    started_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    finished_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS staged_embeddings (
+    job_id TEXT NOT NULL,
+    source TEXT NOT NULL,           -- chunks | chat | documents
+    item_id TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    dimension INTEGER NOT NULL,
+    model TEXT NOT NULL,
+    PRIMARY KEY (job_id, source, item_id),
+    FOREIGN KEY (job_id) REFERENCES reembed_jobs(id)
+);
@@ -0,0 +1,28 @@
+-- Durable queue of items awaiting an embedding, drained in batches by a
+-- background worker. Text is read from the content table when the item is
+-- processed, so the queue only holds references.
+
+CREATE TABLE IF NOT EXISTS embedding_queue (
+    source TEXT NOT NULL,           -- chunks | chat
+    item_id TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at INTEGER NOT NULL,
+    enqueued_at INTEGER NOT NULL,
+    PRIMARY KEY (source, item_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_embedding_queue_due ON embedding_queue(next_attempt_at);
+
+-- Items whose inline embedding failed before the queue existed
+INSERT OR IGNORE INTO embedding_queue (source, item_id, next_attempt_at, enqueued_at)
+SELECT 'chunks', t.id, 0, strftime('%s', 'now')
+FROM text_chunks t
+WHERE NOT EXISTS (SELECT 1 FROM embeddings e WHERE e.chunk_id = t.id);
+
+INSERT OR IGNORE INTO embedding_queue (source, item_id, next_attempt_at, enqueued_at)
+SELECT 'chat', c.id, 0, strftime('%s', 'now')
+FROM chat_logs c
+WHERE c.author_type != 'System'
+  AND c.context_cleared = 0
+  AND NOT EXISTS (SELECT 1 FROM chat_embeddings e WHERE e.message_id = c.id);
@@ -0,0 +1,40 @@
+-- Append-only, hash-chained log of finalised verdicts with signed checkpoints.
+-- entry_hash = sha256(prev_hash || payload_hash); see `ledger.rs`. The triggers
+-- stop accidental edits; the chain and checkpoints expose deliberate ones.
+
+CREATE TABLE IF NOT EXISTS verdict_ledger (
+    seq INTEGER PRIMARY KEY,        -- 1-based position in the chain
+    deliberation_id TEXT NOT NULL,
+    question TEXT NOT NULL,
+    verdict TEXT NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    prev_hash TEXT NOT NULL,
+    payload_hash TEXT NOT NULL,
+    entry_hash TEXT NOT NULL UNIQUE
+);
+
+CREATE INDEX IF NOT EXISTS idx_verdict_ledger_deliberation ON verdict_ledger(deliberation_id);
+
+CREATE TABLE IF NOT EXISTS ledger_checkpoints (
+    seq INTEGER PRIMARY KEY,        -- number of entries covered
+    head_hash TEXT NOT NULL,
+    signed_at INTEGER NOT NULL,
+    public_key TEXT NOT NULL,
+    signature TEXT NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS verdict_ledger_no_update BEFORE UPDATE ON verdict_ledger BEGIN
+    SELECT RAISE(ABORT, 'verdict ledger is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS verdict_ledger_no_delete BEFORE DELETE ON verdict_ledger BEGIN
+    SELECT RAISE(ABORT, 'verdict ledger is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS ledger_checkpoints_no_update BEFORE UPDATE ON ledger_checkpoints BEGIN
+    SELECT RAISE(ABORT, 'ledger checkpoints are append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS ledger_checkpoints_no_delete BEFORE DELETE ON ledger_checkpoints BEGIN
+    SELECT RAISE(ABORT, 'ledger checkpoints are append-only');
+END;
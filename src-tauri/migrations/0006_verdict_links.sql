@@ -0,0 +1,15 @@
+-- Typed links between a new verdict and the similar earlier verdicts a judge
+-- agent compared it with (see `contradiction.rs`).
+
+CREATE TABLE IF NOT EXISTS verdict_links (
+    deliberation_id TEXT NOT NULL,  -- the newer verdict
+    related_id TEXT NOT NULL,       -- the earlier verdict it was compared with
+    relation TEXT NOT NULL CHECK (relation IN ('consistent', 'refining', 'contradicting')),
+    reason TEXT NOT NULL,
+    similarity REAL NOT NULL,
+    judge TEXT NOT NULL,            -- name of the judging agent
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (deliberation_id, related_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_verdict_links_related ON verdict_links(related_id);
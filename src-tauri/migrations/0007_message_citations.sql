@@ -0,0 +1,4 @@
+-- Sources a chat reply cited from its RAG context, as a JSON array of
+-- `citations::Citation` (NULL when nothing was cited).
+
+ALTER TABLE chat_logs ADD COLUMN citations TEXT;
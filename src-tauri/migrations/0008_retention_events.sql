@@ -0,0 +1,13 @@
+-- Log of retention pruning and context resets (see `retention.rs`).
+
+CREATE TABLE IF NOT EXISTS retention_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    channel TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('prune', 'reset')),
+    initiated_by TEXT NOT NULL CHECK (initiated_by IN ('schedule', 'manual')),
+    messages INTEGER NOT NULL,      -- messages deleted (prune) or taken out of context (reset)
+    archive_path TEXT,              -- archive written before a prune, if any
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_retention_events_channel ON retention_events(channel, kind, created_at);
@@ -0,0 +1,5 @@
+-- Revision counter for queued items, bumped on every re-queue. The worker only
+-- dequeues the revision it embedded; `enqueued_at` has one-second resolution,
+-- so an edit made in the same second as the read was lost.
+
+ALTER TABLE embedding_queue ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
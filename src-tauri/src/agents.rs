@@ -1,6 +1,7 @@
 // Agent Pool Management - Add/remove AI models to chat sessions
 
 use crate::prompt;
+use crate::providers::{GenerationParams, GenerationRequest};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -29,6 +30,9 @@ pub struct AgentStats {
     pub last_context_size: usize,
     /// Timestamp of last activity (Unix epoch seconds)
     pub last_activity: u64,
+    /// Total retries spent on rate limits / transient errors
+    #[serde(default)]
+    pub total_retries: u64,
 }
 
 impl AgentStats {
@@ -65,6 +69,11 @@ impl AgentStats {
             .as_secs();
     }
 
+    /// Record retries spent by the provider retry layer
+    pub fn record_retries(&mut self, retries: u32) {
+        self.total_retries += retries as u64;
+    }
+
     /// Get success rate as percentage
     pub fn success_rate(&self) -> f64 {
         if self.total_requests == 0 {
@@ -92,7 +101,7 @@ pub struct Agent {
     #[serde(default)]
     pub handle: String,
 
-    /// Provider type: "ollama", "openai", "openrouter", "google"
+    /// Provider type: "ollama", "openai", "openrouter", "google", "mock"
     #[serde(default = "default_provider")]
     pub provider: String,
 
@@ -116,6 +125,10 @@ pub struct Agent {
     #[serde(default)]
     pub timeout_secs: Option<u64>,
 
+    /// Sampling overrides: seed, top_p, top_k, stop, penalties, JSON mode
+    #[serde(default, flatten)]
+    pub generation: GenerationParams,
+
     /// Metadata for UI/sorting
     pub metadata: HashMap<String, String>,
 }
@@ -139,6 +152,7 @@ impl Agent {
             temperature: 0.7,
             active: true,
             timeout_secs: None, // Use global timeout
+            generation: GenerationParams::default(),
             metadata: HashMap::new(),
         }
     }
@@ -157,6 +171,7 @@ impl Agent {
             temperature: 0.7,
             active: true,
             timeout_secs: None, // Use global timeout
+            generation: GenerationParams::default(),
             metadata: HashMap::new(),
         }
     }
@@ -175,6 +190,21 @@ impl Agent {
 
         prompt
     }
+
+    /// Generation request carrying this agent's model, temperature and sampling overrides
+    pub fn generation_request(&self, prompt: String, system_prompt: Option<String>) -> GenerationRequest {
+        GenerationRequest {
+            model: self.model.clone(),
+            prompt,
+            system_prompt,
+            temperature: self.temperature,
+            max_tokens: None,
+            stream: false,
+            params: self.generation.clone(),
+            bypass_cache: false,
+            attachments: Vec::new(),
+        }
+    }
 }
 
 /// Manages a pool of agents for council sessions
@@ -199,6 +229,10 @@ struct AgentConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_secs: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(flatten)]
+    pub generation: GenerationParams,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, String>>,
 }
 
@@ -238,6 +272,8 @@ impl AgentPool {
                 model: agent.model.clone(),
                 system_prompt: agent.system_prompt.clone(),
                 timeout_secs: agent.timeout_secs,
+                temperature: Some(agent.temperature),
+                generation: agent.generation.clone(),
                 metadata: if agent.metadata.is_empty() { None } else { Some(agent.metadata.clone()) },
             }
         }).collect();
@@ -363,6 +399,16 @@ impl AgentPool {
         agent_stats.record_failure(response_time_ms);
     }
 
+    /// Record retries spent on a request for an agent
+    pub async fn record_retries(&self, agent_id: &str, retries: u32) {
+        if retries == 0 {
+            return;
+        }
+        let mut stats = self.stats.lock().await;
+        let agent_stats = stats.entry(agent_id.to_string()).or_insert_with(AgentStats::new);
+        agent_stats.record_retries(retries);
+    }
+
     /// Start timing a request (returns instant for later measurement)
     pub fn start_request(&self) -> Instant {
         Instant::now()
@@ -480,6 +526,30 @@ mod tests {
         assert_eq!(agent.temperature, 0.7);
     }
 
+    #[test]
+    fn test_agent_generation_params_from_json() {
+        let agent: Agent = serde_json::from_value(serde_json::json!({
+            "id": "a1",
+            "name": "Judge",
+            "model": "qwen2.5",
+            "system_prompt": "Be terse",
+            "enabled_tools": [],
+            "temperature": 0.1,
+            "active": true,
+            "metadata": {},
+            "seed": 42,
+            "stop": ["###"],
+            "response_format": { "type": "json" }
+        }))
+        .unwrap();
+
+        let request = agent.generation_request("Vote".to_string(), None);
+        assert_eq!(request.temperature, 0.1);
+        assert_eq!(request.params.seed, Some(42));
+        assert_eq!(request.params.stop, vec!["###".to_string()]);
+        assert_eq!(request.params.response_format, crate::providers::ResponseFormat::Json);
+    }
+
     #[tokio::test]
     async fn test_agent_pool_add_remove() {
         let pool = AgentPool::new();
@@ -0,0 +1,146 @@
+// Verdict ledger CLI - verify history and prove verdict inclusion
+// Run with: cargo run --bin council-ledger -- <command>
+
+use app_lib::{crypto::SigningIdentity, ledger};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: council-ledger [--db PATH] [--key PATH] <command>
+
+Commands:
+  verify [--trusted-key KEY]              Check the whole ledger and its checkpoints
+  prove <deliberation-id> [--out FILE]    Write an inclusion proof for a verdict
+  check <proof.json> [--trusted-key KEY]  Check a proof offline (no database needed)
+  checkpoint                              Sign the current ledger head
+
+Defaults: --db ./data/knowledge_bank.sqlite, --key ./data/council_identity.key";
+
+struct Options {
+    db: PathBuf,
+    key: PathBuf,
+    trusted_key: Option<String>,
+    out: Option<PathBuf>,
+    args: Vec<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        db: PathBuf::from("./data/knowledge_bank.sqlite"),
+        key: PathBuf::from("./data/council_identity.key"),
+        trusted_key: None,
+        out: None,
+        args: Vec::new(),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--db" => options.db = PathBuf::from(value()?),
+            "--key" => options.key = PathBuf::from(value()?),
+            "--trusted-key" => options.trusted_key = Some(value()?),
+            "--out" => options.out = Some(PathBuf::from(value()?)),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => options.args.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+async fn open_pool(options: &Options) -> Result<SqlitePool, String> {
+    SqlitePool::connect_with(SqliteConnectOptions::new().filename(&options.db))
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", options.db.display(), e))
+}
+
+fn load_identity(options: &Options) -> Result<SigningIdentity, String> {
+    SigningIdentity::load(options.key.clone())
+        .map_err(|e| format!("Failed to load signing key {}: {}", options.key.display(), e))
+}
+
+async fn run(options: Options) -> Result<bool, String> {
+    let args: Vec<&str> = options.args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["verify"] => {
+            let report = ledger::verify(&open_pool(&options).await?, options.trusted_key.as_deref()).await?;
+            println!(
+                "{} entries, {} checkpoints, head {}",
+                report.entries,
+                report.checkpoints,
+                report.head_hash.as_deref().unwrap_or("(empty)")
+            );
+            for problem in &report.problems {
+                println!("❌ {}", problem);
+            }
+            if report.valid {
+                println!("✅ Ledger is intact");
+            }
+            Ok(report.valid)
+        }
+        ["prove", deliberation_id] => {
+            let pool = open_pool(&options).await?;
+            let proof = match ledger::prove(&pool, deliberation_id).await? {
+                Some(proof) => proof,
+                None => {
+                    // Newest entries are not checkpointed yet
+                    ledger::checkpoint(&pool, &load_identity(&options)?).await?;
+                    ledger::prove(&pool, deliberation_id)
+                        .await?
+                        .ok_or_else(|| format!("No checkpoint covers the verdict of {}", deliberation_id))?
+                }
+            };
+            let json = serde_json::to_string_pretty(&proof).map_err(|e| e.to_string())?;
+            match &options.out {
+                Some(path) => {
+                    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                    println!("✅ Proof for entry {} written to {}", proof.entry.seq, path.display());
+                }
+                None => println!("{}", json),
+            }
+            Ok(true)
+        }
+        ["check", path] => {
+            let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let proof: ledger::InclusionProof =
+                serde_json::from_str(&json).map_err(|e| format!("Not an inclusion proof: {}", e))?;
+            match ledger::verify_proof(&proof, options.trusted_key.as_deref()) {
+                Ok(()) => {
+                    println!(
+                        "✅ Verdict of {} (entry {}) is included in checkpoint {}",
+                        proof.entry.deliberation_id, proof.entry.seq, proof.checkpoint.seq
+                    );
+                    Ok(true)
+                }
+                Err(e) => {
+                    println!("❌ {}", e);
+                    Ok(false)
+                }
+            }
+        }
+        ["checkpoint"] => {
+            let pool = open_pool(&options).await?;
+            match ledger::checkpoint(&pool, &load_identity(&options)?).await? {
+                Some(checkpoint) => println!("🔏 Signed checkpoint at entry {}: {}", checkpoint.seq, checkpoint.head_hash),
+                None => println!("Ledger head is already checkpointed"),
+            }
+            Ok(true)
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let result = match parse_args() {
+        Ok(options) => run(options).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        }
+    }
+}
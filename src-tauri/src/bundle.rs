@@ -0,0 +1,712 @@
+// Knowledge bundles - Signed, portable export/import of the council's memory
+//
+// A bundle is a gzipped tarball of newline-delimited JSON files plus a
+// manifest listing each file's SHA-256. The manifest hash is signed with the
+// node's `SigningIdentity`, so any change to any record breaks verification.
+
+use crate::crypto::{self, SignedMessage, SigningIdentity};
+use crate::embedding_queue;
+use crate::vector_index::VectorSource;
+use base64::{engine::general_purpose, Engine as _};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+pub const BUNDLE_FORMAT: &str = "council-knowledge-bundle";
+pub const BUNDLE_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const SIGNATURE_FILE: &str = "signature.json";
+const DELIBERATIONS_FILE: &str = "deliberations.jsonl";
+const CHUNKS_FILE: &str = "chunks.jsonl";
+const EMBEDDINGS_FILE: &str = "embeddings.jsonl";
+const REPUTATIONS_FILE: &str = "reputations.jsonl";
+
+/// Members read on import; anything else in the archive is skipped unread
+const KNOWN_FILES: [&str; 6] = [
+    MANIFEST_FILE,
+    SIGNATURE_FILE,
+    DELIBERATIONS_FILE,
+    CHUNKS_FILE,
+    EMBEDDINGS_FILE,
+    REPUTATIONS_FILE,
+];
+
+/// Largest bundle member read on import (guards against decompression bombs)
+const MAX_ENTRY_BYTES: u64 = 1 << 30;
+/// Largest total read on import, across all members
+const MAX_BUNDLE_BYTES: u64 = 2 << 30;
+
+/// One data file in the bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFile {
+    pub name: String,
+    pub sha256: String,
+    pub records: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: i64,
+    /// Base64 public key of the exporting node
+    pub node_public_key: String,
+    /// Models of the exported embeddings
+    pub embedding_models: Vec<String>,
+    pub files: Vec<BundleFile>,
+}
+
+/// A deliberation (council session) with its transcript and verdict
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliberationRecord {
+    pub id: String,
+    pub question: String,
+    /// The verdict, once reached
+    pub consensus: Option<String>,
+    pub created_at: i64,
+    pub completed: bool,
+    pub rounds: Vec<RoundRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub round_number: i64,
+    pub responses: Vec<ResponseRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseRecord {
+    pub member_name: String,
+    pub model: String,
+    pub response: String,
+    pub timestamp: i64,
+    pub reasoning: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkRecord {
+    pub id: String,
+    pub deliberation_id: String,
+    pub text: String,
+    pub chunk_type: String,
+    pub chunk_metadata: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingRecord {
+    pub chunk_id: String,
+    pub model: Option<String>,
+    pub dimension: i64,
+    /// Little-endian f32s, base64 encoded
+    pub vector: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReputationRecord {
+    pub agent_id: String,
+    pub tier: String,
+    pub accuracy: f64,
+    pub reasoning: f64,
+    pub contribution: f64,
+    pub total_votes: i64,
+    pub successful_consensus: i64,
+    pub last_updated: i64,
+}
+
+/// What an export wrote
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportReport {
+    pub path: String,
+    pub deliberations: usize,
+    pub chunks: usize,
+    pub embeddings: usize,
+    pub reputations: usize,
+}
+
+/// What an import added (records whose ID already existed are skipped)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Fingerprint of the key that signed the bundle
+    pub signer: String,
+    /// When the exporting node wrote the bundle
+    pub exported_at: i64,
+    pub deliberations: usize,
+    pub chunks: usize,
+    pub embeddings: usize,
+    pub reputations: usize,
+    /// Records skipped because their ID already existed
+    pub duplicates: usize,
+    /// Chunks queued for embedding with the local model
+    pub queued_for_embedding: usize,
+}
+
+fn to_jsonl<T: Serialize>(records: &[T]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut out, record).map_err(|e| format!("Failed to serialize record: {}", e))?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+fn from_jsonl<T: DeserializeOwned>(name: &str, bytes: &[u8]) -> Result<Vec<T>, String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("{} line {}: {}", name, i + 1, e)))
+        .collect()
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Everything that goes into a bundle
+struct Snapshot {
+    deliberations: Vec<DeliberationRecord>,
+    chunks: Vec<ChunkRecord>,
+    embeddings: Vec<EmbeddingRecord>,
+    reputations: Vec<ReputationRecord>,
+}
+
+async fn collect(pool: &SqlitePool) -> Result<Snapshot, String> {
+    let err = |what: &str, e: sqlx::Error| format!("Failed to export {}: {}", what, e);
+
+    let mut deliberations: Vec<DeliberationRecord> =
+        sqlx::query("SELECT id, question, consensus, created_at, completed FROM deliberations ORDER BY created_at, id")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| err("deliberations", e))?
+            .iter()
+            .map(|r| DeliberationRecord {
+                id: r.get("id"),
+                question: r.get("question"),
+                consensus: r.get("consensus"),
+                created_at: r.get("created_at"),
+                completed: r.get("completed"),
+                rounds: Vec::new(),
+            })
+            .collect();
+
+    let responses = sqlx::query(
+        "SELECT r.deliberation_id, r.id AS round_id, r.round_number, \
+                p.member_name, p.model, p.response, p.timestamp, p.reasoning \
+         FROM rounds r LEFT JOIN responses p ON p.round_id = r.id \
+         ORDER BY r.deliberation_id, r.round_number, r.id, p.id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| err("rounds", e))?;
+
+    let index: HashMap<String, usize> = deliberations.iter().enumerate().map(|(i, d)| (d.id.clone(), i)).collect();
+    let mut last_round: Option<i64> = None;
+    for row in &responses {
+        let Some(&i) = index.get(&row.get::<String, _>("deliberation_id")) else {
+            continue;
+        };
+        let round_id: i64 = row.get("round_id");
+        let rounds = &mut deliberations[i].rounds;
+        if last_round != Some(round_id) {
+            rounds.push(RoundRecord {
+                round_number: row.get("round_number"),
+                responses: Vec::new(),
+            });
+            last_round = Some(round_id);
+        }
+        if let Some(member_name) = row.get::<Option<String>, _>("member_name") {
+            rounds.last_mut().unwrap().responses.push(ResponseRecord {
+                member_name,
+                model: row.get("model"),
+                response: row.get("response"),
+                timestamp: row.get("timestamp"),
+                reasoning: row.get("reasoning"),
+            });
+        }
+    }
+
+    let chunks = sqlx::query("SELECT id, deliberation_id, text, chunk_type, chunk_metadata FROM text_chunks ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| err("chunks", e))?
+        .iter()
+        .map(|r| ChunkRecord {
+            id: r.get("id"),
+            deliberation_id: r.get("deliberation_id"),
+            text: r.get("text"),
+            chunk_type: r.get("chunk_type"),
+            chunk_metadata: r.get("chunk_metadata"),
+        })
+        .collect();
+
+    let embeddings = sqlx::query("SELECT chunk_id, embedding, dimension, model FROM embeddings ORDER BY chunk_id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| err("embeddings", e))?
+        .iter()
+        .map(|r| EmbeddingRecord {
+            chunk_id: r.get("chunk_id"),
+            model: r.get("model"),
+            dimension: r.get("dimension"),
+            vector: general_purpose::STANDARD.encode(r.get::<Vec<u8>, _>("embedding")),
+        })
+        .collect();
+
+    let reputations = sqlx::query("SELECT * FROM reputation ORDER BY agent_id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| err("reputations", e))?
+        .iter()
+        .map(|r| ReputationRecord {
+            agent_id: r.get("agent_id"),
+            tier: r.get("tier"),
+            accuracy: r.get("accuracy"),
+            reasoning: r.get("reasoning"),
+            contribution: r.get("contribution"),
+            total_votes: r.get("total_votes"),
+            successful_consensus: r.get("successful_consensus"),
+            last_updated: r.get("last_updated"),
+        })
+        .collect();
+
+    Ok(Snapshot {
+        deliberations,
+        chunks,
+        embeddings,
+        reputations,
+    })
+}
+
+/// Write a signed bundle of the knowledge bank to `path`
+pub async fn export(pool: &SqlitePool, identity: &SigningIdentity, path: &Path) -> Result<ExportReport, String> {
+    let Snapshot {
+        deliberations,
+        chunks,
+        embeddings,
+        reputations,
+    } = collect(pool).await?;
+
+    let mut embedding_models: Vec<String> = embeddings.iter().filter_map(|e| e.model.clone()).collect();
+    embedding_models.sort();
+    embedding_models.dedup();
+
+    let data = [
+        (DELIBERATIONS_FILE, to_jsonl(&deliberations)?, deliberations.len()),
+        (CHUNKS_FILE, to_jsonl(&chunks)?, chunks.len()),
+        (EMBEDDINGS_FILE, to_jsonl(&embeddings)?, embeddings.len()),
+        (REPUTATIONS_FILE, to_jsonl(&reputations)?, reputations.len()),
+    ];
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        created_at: chrono::Utc::now().timestamp(),
+        node_public_key: identity.public_key_base64(),
+        embedding_models,
+        files: data
+            .iter()
+            .map(|(name, bytes, records)| BundleFile {
+                name: name.to_string(),
+                sha256: sha256_hex(bytes),
+                records: *records,
+            })
+            .collect(),
+    };
+    let manifest_bytes =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    let signature = serde_json::to_vec_pretty(&identity.sign(&sha256_hex(&manifest_bytes)))
+        .map_err(|e| format!("Failed to serialize signature: {}", e))?;
+
+    let file = std::fs::File::create(path).map_err(|e| format!("Cannot create {}: {}", path.display(), e))?;
+    let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+    let entries = [(MANIFEST_FILE, &manifest_bytes), (SIGNATURE_FILE, &signature)]
+        .into_iter()
+        .chain(data.iter().map(|(name, bytes, _)| (*name, bytes)));
+    for (name, bytes) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(manifest.created_at.max(0) as u64);
+        header.set_cksum();
+        tar.append_data(&mut header, name, bytes.as_slice())
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+    tar.into_inner()
+        .and_then(|gz| gz.finish())
+        .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+
+    Ok(ExportReport {
+        path: path.to_string_lossy().into_owned(),
+        deliberations: deliberations.len(),
+        chunks: chunks.len(),
+        embeddings: embeddings.len(),
+        reputations: reputations.len(),
+    })
+}
+
+/// A bundle whose signature and file hashes checked out
+pub struct VerifiedBundle {
+    pub manifest: BundleManifest,
+    pub signer: String,
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl VerifiedBundle {
+    fn records<T: DeserializeOwned>(&self, name: &str) -> Result<Vec<T>, String> {
+        match self.files.get(name) {
+            Some(bytes) => from_jsonl(name, bytes),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Read `path` and check the manifest signature and every file hash;
+/// `trusted_key` (base64 public key or fingerprint) pins the signer
+pub fn read_verified(path: &Path, trusted_key: Option<&str>) -> Result<VerifiedBundle, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut files = HashMap::new();
+    let mut remaining = MAX_BUNDLE_BYTES;
+    for entry in archive.entries().map_err(|e| format!("Not a bundle: {}", e))? {
+        let entry = entry.map_err(|e| format!("Corrupt bundle: {}", e))?;
+        let name = entry
+            .path()
+            .map_err(|e| format!("Corrupt bundle: {}", e))?
+            .to_string_lossy()
+            .into_owned();
+        if !KNOWN_FILES.contains(&name.as_str()) || files.contains_key(&name) {
+            continue;
+        }
+        // One byte past the budget tells an oversized bundle from one that fits exactly
+        let mut bytes = Vec::new();
+        entry
+            .take(MAX_ENTRY_BYTES.min(remaining + 1))
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        remaining = remaining
+            .checked_sub(bytes.len() as u64)
+            .ok_or_else(|| format!("Bundle is larger than {} bytes", MAX_BUNDLE_BYTES))?;
+        files.insert(name, bytes);
+    }
+
+    let manifest_bytes = files.get(MANIFEST_FILE).ok_or("Bundle has no manifest")?;
+    let signed: SignedMessage = serde_json::from_slice(files.get(SIGNATURE_FILE).ok_or("Bundle is not signed")?)
+        .map_err(|e| format!("Invalid bundle signature: {}", e))?;
+    if signed.content != sha256_hex(manifest_bytes) || !crypto::verify_signed_message(&signed)? {
+        return Err("Bundle signature does not match its manifest".to_string());
+    }
+
+    let manifest: BundleManifest =
+        serde_json::from_slice(manifest_bytes).map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    if manifest.format != BUNDLE_FORMAT || manifest.version > BUNDLE_VERSION {
+        return Err(format!("Unsupported bundle format {} v{}", manifest.format, manifest.version));
+    }
+    if manifest.node_public_key != signed.public_key {
+        return Err("Bundle was signed by a different key than the node it claims".to_string());
+    }
+    for file in &manifest.files {
+        let bytes = files.get(&file.name).ok_or_else(|| format!("Bundle is missing {}", file.name))?;
+        if sha256_hex(bytes) != file.sha256 {
+            return Err(format!("{} was modified after signing", file.name));
+        }
+    }
+    // Only files covered by the manifest are trusted
+    files.retain(|name, _| manifest.files.iter().any(|f| &f.name == name));
+
+    let signer = crypto::public_key_fingerprint(&signed.public_key)?;
+    if let Some(trusted) = trusted_key {
+        if trusted != signed.public_key && trusted != signer {
+            return Err(format!("Bundle signed by {}, not the trusted key", signer));
+        }
+    }
+
+    Ok(VerifiedBundle { manifest, signer, files })
+}
+
+/// Insert records whose IDs are new. Embeddings from a model other than
+/// `local_model` are dropped and their chunks queued for re-embedding when
+/// `reembed` is set; chunks without any embedding are always queued.
+pub async fn import(pool: &SqlitePool, bundle: &VerifiedBundle, local_model: &str, reembed: bool) -> Result<ImportReport, String> {
+    let deliberations: Vec<DeliberationRecord> = bundle.records(DELIBERATIONS_FILE)?;
+    let chunks: Vec<ChunkRecord> = bundle.records(CHUNKS_FILE)?;
+    let embeddings: Vec<EmbeddingRecord> = bundle.records(EMBEDDINGS_FILE)?;
+    let reputations: Vec<ReputationRecord> = bundle.records(REPUTATIONS_FILE)?;
+
+    let mut report = ImportReport {
+        signer: bundle.signer.clone(),
+        exported_at: bundle.manifest.created_at,
+        ..Default::default()
+    };
+    let err = |what: &str, e: sqlx::Error| format!("Failed to import {}: {}", what, e);
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    for d in &deliberations {
+        let added = sqlx::query(
+            "INSERT OR IGNORE INTO deliberations (id, question, consensus, created_at, completed) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&d.id)
+        .bind(&d.question)
+        .bind(&d.consensus)
+        .bind(d.created_at)
+        .bind(d.completed)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| err("deliberation", e))?
+        .rows_affected()
+            > 0;
+        if !added {
+            report.duplicates += 1;
+            continue;
+        }
+        report.deliberations += 1;
+
+        for round in &d.rounds {
+            let round_id = sqlx::query("INSERT INTO rounds (deliberation_id, round_number) VALUES (?, ?)")
+                .bind(&d.id)
+                .bind(round.round_number)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| err("round", e))?
+                .last_insert_rowid();
+            for r in &round.responses {
+                sqlx::query(
+                    "INSERT INTO responses (round_id, member_name, model, response, timestamp, reasoning) \
+                     VALUES (?, ?, ?, ?, ?, ?)",
+                )
+                .bind(round_id)
+                .bind(&r.member_name)
+                .bind(&r.model)
+                .bind(&r.response)
+                .bind(r.timestamp)
+                .bind(&r.reasoning)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| err("response", e))?;
+            }
+        }
+    }
+
+    let mut new_chunks = std::collections::HashSet::new();
+    for c in &chunks {
+        let added = sqlx::query(
+            "INSERT OR IGNORE INTO text_chunks (id, deliberation_id, text, chunk_type, chunk_metadata) \
+             SELECT ?, ?, ?, ?, ? WHERE EXISTS (SELECT 1 FROM deliberations WHERE id = ?)",
+        )
+        .bind(&c.id)
+        .bind(&c.deliberation_id)
+        .bind(&c.text)
+        .bind(&c.chunk_type)
+        .bind(&c.chunk_metadata)
+        .bind(&c.deliberation_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| err("chunk", e))?
+        .rows_affected()
+            > 0;
+        if added {
+            report.chunks += 1;
+            new_chunks.insert(c.id.as_str());
+        } else {
+            report.duplicates += 1;
+        }
+    }
+
+    let mut embedded = std::collections::HashSet::new();
+    for e in &embeddings {
+        // Only vectors for chunks this import added; existing ones keep theirs
+        if !new_chunks.contains(e.chunk_id.as_str()) {
+            continue;
+        }
+        if reembed && e.model.as_deref() != Some(local_model) {
+            continue;
+        }
+        let bytes = general_purpose::STANDARD
+            .decode(&e.vector)
+            .map_err(|err| format!("Invalid vector for {}: {}", e.chunk_id, err))?;
+        if bytes.len() != e.dimension.max(0) as usize * 4 {
+            return Err(format!("Vector for {} does not match its dimension", e.chunk_id));
+        }
+        sqlx::query("INSERT OR IGNORE INTO embeddings (chunk_id, embedding, dimension, model) VALUES (?, ?, ?, ?)")
+            .bind(&e.chunk_id)
+            .bind(bytes)
+            .bind(e.dimension)
+            .bind(&e.model)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| err("embedding", e))?;
+        embedded.insert(e.chunk_id.as_str());
+        report.embeddings += 1;
+    }
+
+    for r in &reputations {
+        let added = sqlx::query(
+            "INSERT OR IGNORE INTO reputation \
+             (agent_id, tier, accuracy, reasoning, contribution, total_votes, successful_consensus, last_updated) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&r.agent_id)
+        .bind(&r.tier)
+        .bind(r.accuracy)
+        .bind(r.reasoning)
+        .bind(r.contribution)
+        .bind(r.total_votes)
+        .bind(r.successful_consensus)
+        .bind(r.last_updated)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| err("reputation", e))?
+        .rows_affected()
+            > 0;
+        if added {
+            report.reputations += 1;
+        } else {
+            report.duplicates += 1;
+        }
+    }
+
+    tx.commit().await.map_err(|e| format!("Failed to commit import: {}", e))?;
+
+    for id in new_chunks.iter().filter(|id| !embedded.contains(*id)) {
+        embedding_queue::enqueue(pool, VectorSource::Chunks, id).await?;
+        report.queued_for_embedding += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deliberation::{DeliberationResult, DeliberationRound, MemberResponse};
+    use crate::knowledge::{KnowledgeBank, SearchMode};
+    use crate::logger::Logger;
+    use crate::providers::{LocalEmbeddingProvider, MockProvider};
+    use std::sync::Arc;
+
+    async fn source_bank(logger: &Arc<Logger>) -> KnowledgeBank {
+        let kb = KnowledgeBank::new("sqlite::memory:", logger.clone(), Arc::new(LocalEmbeddingProvider::new(logger.clone())))
+            .await
+            .unwrap();
+        kb.store_deliberation(&DeliberationResult {
+            session_id: "s1".to_string(),
+            question: "Should we approve budget line BL-4471?".to_string(),
+            rounds: vec![DeliberationRound {
+                round_number: 1,
+                responses: vec![MemberResponse {
+                    member_name: "Pragmatist".to_string(),
+                    model: "qwen2.5".to_string(),
+                    response: "Yes, the data centre needs it.".to_string(),
+                    timestamp: 1,
+                    reasoning: None,
+                }],
+            }],
+            consensus: Some("Approved".to_string()),
+            completed: true,
+            created_at: 1,
+        })
+        .await
+        .unwrap();
+        kb.process_embedding_queue().await.unwrap();
+        kb
+    }
+
+    /// Rewrite one member of a bundle without re-signing it
+    fn tamper(path: &Path, name: &str, edit: impl Fn(&[u8]) -> Vec<u8>) {
+        rewrite(path, |members| {
+            for (entry_name, bytes) in members.iter_mut() {
+                if entry_name.as_str() == name {
+                    *bytes = edit(bytes);
+                }
+            }
+        });
+    }
+
+    /// Re-pack a bundle after `edit` changed its member list
+    fn rewrite(path: &Path, edit: impl FnOnce(&mut Vec<(String, Vec<u8>)>)) {
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(std::fs::File::open(path).unwrap()));
+        let mut members = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let entry_name = entry.path().unwrap().to_string_lossy().into_owned();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).unwrap();
+            members.push((entry_name, bytes));
+        }
+        edit(&mut members);
+        let file = std::fs::File::create(path).unwrap();
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+        for (entry_name, bytes) in members {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_cksum();
+            tar.append_data(&mut header, entry_name, bytes.as_slice()).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let logger = Arc::new(Logger::new(false));
+        let identity = SigningIdentity::generate();
+        let path = std::env::temp_dir().join(format!("kb-bundle-{}.tar.gz", uuid::Uuid::new_v4()));
+
+        let source = source_bank(&logger).await;
+        let exported = source.export_bundle(&path, &identity).await.unwrap();
+        assert_eq!((exported.deliberations, exported.embeddings), (1, 3));
+
+        let target = KnowledgeBank::new("sqlite::memory:", logger.clone(), Arc::new(LocalEmbeddingProvider::new(logger.clone())))
+            .await
+            .unwrap();
+        let imported = target
+            .import_bundle(&path, Some(&identity.public_key_base64()), true)
+            .await
+            .unwrap();
+        assert_eq!((imported.deliberations, imported.chunks, imported.embeddings), (1, 3, 3));
+        assert_eq!(imported.queued_for_embedding, 0);
+
+        let deliberation = target.get_deliberation("s1").await.unwrap();
+        assert_eq!(deliberation.consensus.as_deref(), Some("Approved"));
+        assert_eq!(deliberation.rounds[0].responses[0].member_name, "Pragmatist");
+        let hits = target.semantic_search("budget data centre", 1, SearchMode::Semantic).await.unwrap();
+        assert_eq!(hits[0].deliberation_id, "s1");
+
+        // Same IDs again: nothing added
+        let again = target.import_bundle(&path, None, true).await.unwrap();
+        assert_eq!((again.deliberations, again.chunks, again.embeddings), (0, 0, 0));
+        assert_eq!(again.duplicates, 4);
+
+        // Different local model: vectors are re-queued instead of imported
+        let mock = Arc::new(MockProvider::from_optional_path(None, logger.clone()));
+        let other = KnowledgeBank::new("sqlite::memory:", logger, mock).await.unwrap();
+        let reembedded = other.import_bundle(&path, None, true).await.unwrap();
+        assert_eq!((reembedded.embeddings, reembedded.queued_for_embedding), (0, 3));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_tampered_or_untrusted_bundles() {
+        let logger = Arc::new(Logger::new(false));
+        let identity = SigningIdentity::generate();
+        let path = std::env::temp_dir().join(format!("kb-bundle-{}.tar.gz", uuid::Uuid::new_v4()));
+        source_bank(&logger).await.export_bundle(&path, &identity).await.unwrap();
+
+        let stranger = SigningIdentity::generate().public_key_base64();
+        let err = read_verified(&path, Some(&stranger)).err().unwrap();
+        assert!(err.contains("not the trusted key"));
+        assert!(read_verified(&path, None).is_ok());
+
+        // Unsigned extras are skipped, not read
+        rewrite(&path, |members| members.push(("notes.txt".to_string(), vec![b'x'; 4096])));
+        assert!(read_verified(&path, None).is_ok());
+
+        tamper(&path, CHUNKS_FILE, |bytes| String::from_utf8_lossy(bytes).replace("Approved", "Rejected").into_bytes());
+        let err = read_verified(&path, None).err().unwrap();
+        assert!(err.contains("modified after signing"));
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -1,4 +1,7 @@
+use crate::citations::Citation;
 use crate::knowledge::KnowledgeBank;
+use crate::namespaces::NamespaceRegistry;
+use crate::providers::Attachment;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -91,6 +94,15 @@ pub struct Message {
     pub reply_to: Option<String>, // Thread support
     #[serde(default)]
     pub reactions: Vec<Reaction>,
+    /// Images shared with the message (kept in memory, not persisted)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Model's `<think>` trace, kept apart from `content` (never part of agent context)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Supplied RAG sources the reply actually cited
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
 }
 
 impl Message {
@@ -113,6 +125,9 @@ impl Message {
             signature: None,
             reply_to: None,
             reactions: Vec::new(),
+            attachments: Vec::new(),
+            reasoning: None,
+            citations: Vec::new(),
         }
     }
 
@@ -130,6 +145,21 @@ impl Message {
         self
     }
 
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    pub fn with_reasoning(mut self, reasoning: Option<String>) -> Self {
+        self.reasoning = reasoning;
+        self
+    }
+
+    pub fn with_citations(mut self, citations: Vec<Citation>) -> Self {
+        self.citations = citations;
+        self
+    }
+
     pub fn add_reaction(&mut self, emoji: String, author: String) {
         // Remove existing reaction from same author with same emoji
         self.reactions
@@ -234,6 +264,8 @@ impl Channel {
 pub struct ChannelManager {
     channels: Arc<Mutex<HashMap<ChannelType, Channel>>>,
     knowledge_bank: Option<Arc<KnowledgeBank>>,
+    /// Routes each channel to the bank of the namespace that owns it
+    namespaces: Option<Arc<NamespaceRegistry>>,
 }
 
 impl ChannelManager {
@@ -248,16 +280,31 @@ impl ChannelManager {
         Self {
             channels: Arc::new(Mutex::new(channels)),
             knowledge_bank,
+            namespaces: None,
+        }
+    }
+
+    /// Store each channel in its namespace's bank instead of the single knowledge bank
+    pub fn with_namespaces(mut self, namespaces: Arc<NamespaceRegistry>) -> Self {
+        self.namespaces = Some(namespaces);
+        self
+    }
+
+    /// Bank holding `channel`'s history
+    fn bank_for(&self, channel: ChannelType) -> Option<Arc<KnowledgeBank>> {
+        match &self.namespaces {
+            Some(namespaces) => namespaces.for_channel(channel),
+            None => self.knowledge_bank.clone(),
         }
     }
 
     /// Load history from Knowledge Bank
     pub async fn load_history(&self) {
-        if let Some(kb) = &self.knowledge_bank {
-            let mut channels = self.channels.lock().unwrap();
-            
-            // Load for each channel type
-            for (channel_type, channel) in channels.iter_mut() {
+        let mut channels = self.channels.lock().unwrap();
+
+        // Load for each channel type
+        for (channel_type, channel) in channels.iter_mut() {
+            if let Some(kb) = self.bank_for(*channel_type) {
                 if let Ok(messages) = kb.get_chat_history(*channel_type, 50).await {
                     channel.messages = messages;
                 }
@@ -278,7 +325,7 @@ impl ChannelManager {
         // Save to DB if available (fire and forget for now, or block?)
         // Since this is sync, we can't await. We should spawn a task or use a blocking call if critical.
         // For now, we'll just add to memory. Ideally, we should spawn a tokio task.
-        if let Some(kb) = &self.knowledge_bank {
+        if let Some(kb) = self.bank_for(message.channel) {
             let kb_clone = kb.clone();
             let msg_clone = message.clone();
             tokio::spawn(async move {
@@ -57,10 +57,10 @@ impl DuplicateFilter {
 
     /// Check if a question is a duplicate
     pub async fn check_duplicate(&self, question: &str) -> Result<DuplicateCheckResult, String> {
-        // Search knowledge bank for similar questions
+        // Search past deliberations for similar questions (ingested documents are not questions)
         let results = self
             .knowledge_bank
-            .semantic_search(question, 1)
+            .search_deliberations(question, 5)
             .await
             .map_err(|e| format!("Failed to search knowledge bank: {}", e))?;
 
@@ -7,7 +7,11 @@ use serde::{Deserialize, Serialize};
 use crate::{
     agents::{Agent, AgentPool},
     chat::{AuthorType, ChannelType, Message},
-    provider_dispatch, prompt, AppState,
+    citations::{self, Citation},
+    knowledge::SearchMode,
+    provider_dispatch, prompt,
+    providers::RequestPriority,
+    AppState,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -24,7 +28,8 @@ pub struct ChatBot {
     enabled: bool,
     max_agents_per_message: usize,
     next_agent_index: usize,
-    pending_responses: VecDeque<(Agent, Message, String)>,
+    /// Agent, message, context and the sources labelled in it (for citation checks)
+    pending_responses: VecDeque<(Agent, Message, String, Vec<Citation>)>,
 }
 
 impl ChatBot {
@@ -128,22 +133,16 @@ impl ChatBot {
 
     async fn queue_response(&mut self, agent: Agent, message: Message) {
         // Build context based on channel type
+        let mut sources = Vec::new();
         let context = if message.channel == ChannelType::Knowledge {
-            // For #knowledge, ONLY use Consensus results (Global Knowledge)
-            if let Some(kb) = &self.app_state.knowledge_bank {
+            // For #knowledge, ONLY use Consensus results (Global Knowledge), labelled for citation
+            if let Some(kb) = self.app_state.namespaces.for_channel(message.channel) {
                 self.app_state.log_debug("chat_bot", "🔍 Searching Knowledge Bank for consensus");
-                match kb.semantic_search(&message.content, 3).await {
-                    Ok(results) => {
-                        if results.is_empty() {
-                            "No relevant past decisions found.".to_string()
-                        } else {
-                            let mut ctx = String::from("### Relevant Past Decisions (Consensus):\n\n");
-                            for result in results {
-                                ctx.push_str(&format!("- **Question:** {}\n  **Verdict:** {}\n\n", 
-                                    result.question, result.text_snippet));
-                            }
-                            ctx
-                        }
+                match kb.build_rag_context(&message.content, 3, SearchMode::Hybrid).await {
+                    Ok(rag) if rag.relevant_decisions.is_empty() => "No relevant past decisions found.".to_string(),
+                    Ok(rag) => {
+                        sources = rag.sources;
+                        rag.context_text
                     }
                     Err(_) => "Error retrieving knowledge.".to_string()
                 }
@@ -164,7 +163,7 @@ impl ChatBot {
             ctx.push_str(&self.build_context(&recent_messages));
 
             // 2. Get relevant older messages from this channel (Long-term channel memory)
-            if let Some(kb) = &self.app_state.knowledge_bank {
+            if let Some(kb) = self.app_state.namespaces.for_channel(message.channel) {
                 if let Ok(rag_results) = kb.search_channel_context(message.channel, &message.content, 3).await {
                     if !rag_results.is_empty() {
                         ctx.push_str("\n\n### Relevant Context from this discussion:\n");
@@ -184,7 +183,7 @@ impl ChatBot {
         );
 
         // Add to internal queue
-        self.pending_responses.push_back((agent.clone(), message, context));
+        self.pending_responses.push_back((agent.clone(), message, context, sources));
         
         // Update public status
         let mut status = self.app_state.chat_bot_status.lock().unwrap();
@@ -201,6 +200,9 @@ impl ChatBot {
         }
 
         let active_agents: Vec<Agent> = agents.into_iter().filter(|a| a.active).collect();
+        // Only the council of the namespace that owns this channel
+        let namespace = self.app_state.namespaces.namespace_of_channel(message.channel);
+        let active_agents = self.app_state.namespaces.members(Some(namespace.as_str()), active_agents);
         if active_agents.is_empty() {
             self.app_state.log_warn("chat_bot", "⚠️ No active agents found");
             return;
@@ -235,7 +237,7 @@ impl ChatBot {
         }
 
         // Pop next response
-        if let Some((agent, msg, context)) = self.pending_responses.pop_front() {
+        if let Some((agent, msg, context, sources)) = self.pending_responses.pop_front() {
             // Update status
             {
                 let mut status = self.app_state.chat_bot_status.lock().unwrap();
@@ -270,7 +272,7 @@ impl ChatBot {
             }
 
             // Execute response
-            if let Err(e) = self.respond_with_agent(&agent, &msg, &context, &config).await {
+            if let Err(e) = self.respond_with_agent(&agent, &msg, &context, &sources, &config).await {
                 self.app_state.log_error("chat_bot", &format!("Agent {} error: {}", agent.name, e));
             }
 
@@ -313,19 +315,18 @@ Answer ONLY "YES" or "NO" (nothing else).
             msg.content
         );
 
-        // Use a smaller/faster model for the check if available, otherwise use agent's model
-        let check_model = &agent.model;
-        
-        match provider_dispatch::generate(
-            &agent.provider,
-            check_model,
+        let outcome = provider_dispatch::generate_for_agent(
+            agent,
             check_prompt,
             None,
             config,
             Some(self.app_state.logger.clone()),
+            self.dispatch_options(msg),
         )
-        .await
-        {
+        .await;
+        self.app_state.agent_pool.record_retries(&agent.id, outcome.retries).await;
+
+        match outcome.result {
             Ok(response) => {
                 let answer = response.trim().to_uppercase();
                 let should = answer.starts_with("YES");
@@ -342,11 +343,31 @@ Answer ONLY "YES" or "NO" (nothing else).
         }
     }
 
+    /// Replies to humans jump ahead of council and topic-loop traffic
+    fn dispatch_options<'a>(&'a self, msg: &'a Message) -> provider_dispatch::DispatchOptions<'a> {
+        let priority = if msg.author_type == AuthorType::Human {
+            RequestPriority::Interactive
+        } else {
+            RequestPriority::Background
+        };
+
+        provider_dispatch::DispatchOptions {
+            cache: self.app_state.response_cache.as_deref(),
+            scheduler: Some(&self.app_state.scheduler),
+            priority,
+            user: Some(&msg.author),
+            attachments: &msg.attachments,
+            catalog: Some(&self.app_state.model_catalog),
+            ollama_pool: Some(&self.app_state.ollama_pool),
+        }
+    }
+
     async fn respond_with_agent(
         &self,
         agent: &Agent,
         msg: &Message,
         context: &str,
+        sources: &[Citation],
         config: &crate::config::AppConfig,
     ) -> Result<(), String> {
         let system_prompt = prompt::compose_system_prompt(&agent.system_prompt);
@@ -375,16 +396,18 @@ Answer ONLY "YES" or "NO" (nothing else).
         let start_time = std::time::Instant::now();
         let context_size = prompt.len() + system_prompt.len();
 
-        match provider_dispatch::generate(
-            &agent.provider,
-            &agent.model,
+        let outcome = provider_dispatch::generate_for_agent(
+            agent,
             prompt,
             Some(system_prompt),
             config,
             Some(self.app_state.logger.clone()),
+            self.dispatch_options(msg),
         )
-        .await
-        {
+        .await;
+        self.app_state.agent_pool.record_retries(&agent.id, outcome.retries).await;
+
+        match outcome.result {
             Ok(response) => {
                 let elapsed_ms = start_time.elapsed().as_secs_f64() * 1000.0;
                 
@@ -409,12 +432,23 @@ Answer ONLY "YES" or "NO" (nothing else).
                 self.app_state
                     .log_success("chat_bot", &format!("← Response: {} chars in {:.0}ms", response.len(), elapsed_ms));
 
+                // Keep only citations of sources the agent was actually given
+                let cited = citations::check(&response, sources);
+                if !cited.unresolved.is_empty() {
+                    self.app_state.log_warn(
+                        "chat_bot",
+                        &format!("Agent {} cited unknown sources: {}", agent.name, cited.unresolved.join(", ")),
+                    );
+                }
+
                 let reply = Message::new(
                     ChannelType::General,
                     agent.name.clone(),
                     AuthorType::AI,
                     response,
-                );
+                )
+                .with_reasoning(outcome.reasoning)
+                .with_citations(cited.resolved);
 
                 match self.app_state.channel_manager.send_message(reply.clone()) {
                     Ok(_) => {
@@ -0,0 +1,178 @@
+// Citations - Stable IDs for RAG sources and validation of what agents cite
+//
+// Every past verdict or document excerpt placed in a prompt is labelled with an
+// ID derived from its record (`V-…` for verdicts, `DOC-…` for documents), so
+// the same source always has the same ID. Agents are asked to cite those IDs;
+// the IDs found in a reply are checked against the sources actually supplied.
+
+use crate::knowledge::SearchResult;
+use serde::{Deserialize, Serialize};
+
+/// Record characters kept in a citation ID
+const ID_LENGTH: usize = 8;
+
+/// Appended to prompts that carry RAG sources
+pub const CITATION_INSTRUCTIONS: &str = "When you rely on one of the sources above, cite its ID in square \
+brackets right after the claim, e.g. [V-3f2a9c1d]. Only cite IDs listed above and never invent past decisions.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    /// A past deliberation and its verdict
+    Verdict,
+    /// An ingested document excerpt
+    Document,
+}
+
+/// A source supplied to an agent, and the form stored with replies that cite it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// ID shown in the prompt, e.g. "V-3f2a9c1d"
+    pub id: String,
+    pub kind: SourceKind,
+    /// Deliberation or document ID
+    pub record_id: String,
+    /// Question or document title
+    pub title: String,
+    /// "path § heading" for documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+impl Citation {
+    pub fn from_result(result: &SearchResult) -> Self {
+        let (kind, prefix) = match result.document {
+            Some(_) => (SourceKind::Document, "DOC"),
+            None => (SourceKind::Verdict, "V"),
+        };
+        let suffix: String = result
+            .deliberation_id
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .take(ID_LENGTH)
+            .collect::<String>()
+            .to_lowercase();
+
+        Self {
+            id: format!("{}-{}", prefix, suffix),
+            kind,
+            record_id: result.deliberation_id.clone(),
+            title: result.question.clone(),
+            location: result.document.as_ref().map(|doc| match &doc.heading {
+                Some(heading) => format!("{} § {}", doc.source_path, heading),
+                None => doc.source_path.clone(),
+            }),
+        }
+    }
+}
+
+/// Citations found in a reply
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CitationCheck {
+    /// Cited sources that were supplied, in order of first mention
+    pub resolved: Vec<Citation>,
+    /// Cited IDs that match no supplied source (invented or mistyped)
+    pub unresolved: Vec<String>,
+}
+
+fn looks_like_id(token: &str) -> bool {
+    let Some((prefix, rest)) = token.split_once('-') else {
+        return false;
+    };
+    (prefix.eq_ignore_ascii_case("V") || prefix.eq_ignore_ascii_case("DOC"))
+        && !rest.is_empty()
+        && rest.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Citation IDs in `text` (`[V-…]`, `[DOC-…]`, also several per bracket), first mention first
+pub fn extract_ids(text: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for part in text.split('[').skip(1) {
+        let Some((inside, _)) = part.split_once(']') else {
+            continue;
+        };
+        for token in inside.split(|c: char| c == ',' || c == ';' || c.is_whitespace()) {
+            if looks_like_id(token) && !ids.iter().any(|id| id.eq_ignore_ascii_case(token)) {
+                ids.push(token.to_string());
+            }
+        }
+    }
+    ids
+}
+
+/// Check the IDs cited in `reply` against the sources that were supplied
+pub fn check(reply: &str, supplied: &[Citation]) -> CitationCheck {
+    let mut result = CitationCheck::default();
+    for id in extract_ids(reply) {
+        match supplied.iter().find(|s| s.id.eq_ignore_ascii_case(&id)) {
+            Some(source) => result.resolved.push(source.clone()),
+            None => result.unresolved.push(id),
+        }
+    }
+    result
+}
+
+/// JSON for the `chat_logs.citations` column (NULL when empty)
+pub fn to_column(citations: &[Citation]) -> Option<String> {
+    if citations.is_empty() {
+        None
+    } else {
+        serde_json::to_string(citations).ok()
+    }
+}
+
+/// Parse the `chat_logs.citations` column; unreadable values are treated as no citations
+pub fn from_column(value: Option<String>) -> Vec<Citation> {
+    value
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge::DocumentCitation;
+
+    fn result(id: &str, title: &str, document: Option<DocumentCitation>) -> SearchResult {
+        SearchResult {
+            deliberation_id: id.to_string(),
+            question: title.to_string(),
+            relevance_score: 1.0,
+            text_snippet: String::new(),
+            document,
+            namespace: None,
+        }
+    }
+
+    #[test]
+    fn test_ids_are_stable_per_record() {
+        let verdict = Citation::from_result(&result("3F2A9C1D77E0B4A1", "Adopt P-17?", None));
+        assert_eq!((verdict.id.as_str(), verdict.kind), ("V-3f2a9c1d", SourceKind::Verdict));
+
+        let document = Citation::from_result(&result(
+            "5b0e-41aa-9c",
+            "Travel Policy",
+            Some(DocumentCitation {
+                source_path: "docs/travel.md".to_string(),
+                heading: Some("Approvals".to_string()),
+            }),
+        ));
+        assert_eq!(document.id, "DOC-5b0e41aa");
+        assert_eq!(document.location.as_deref(), Some("docs/travel.md § Approvals"));
+    }
+
+    #[test]
+    fn test_check_separates_supplied_from_invented() {
+        let supplied = vec![
+            Citation::from_result(&result("3f2a9c1d77", "Adopt P-17?", None)),
+            Citation::from_result(&result("aa11bb22cc", "Budget cap?", None)),
+        ];
+        let reply = "We adopted P-17 before [v-3F2A9C1D]. As decided [V-3f2a9c1d, V-deadbeef], \
+                     see also [1] and [DOC-99].";
+
+        let checked = check(reply, &supplied);
+        assert_eq!(checked.resolved, vec![supplied[0].clone()]);
+        assert_eq!(checked.unresolved, vec!["V-deadbeef".to_string(), "DOC-99".to_string()]);
+        assert!(check("No sources used.", &supplied).resolved.is_empty());
+    }
+}
@@ -1,7 +1,12 @@
+use crate::secrets::{is_secret_ref, redact, SecretStore};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+/// Providers whose API key lives in `AppConfig`
+const API_KEY_PROVIDERS: [&str; 3] = ["openai", "openrouter", "google"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub ollama_url: String,
@@ -15,13 +20,148 @@ pub struct AppConfig {
     pub bootstrap_peers: Vec<String>,
     pub user_handle: String,
     pub question_generation_prompt: String,
-    // Provider API keys
+    // Provider API keys: "secret:<name>" references into config/secrets.enc.json
+    // (plaintext values are still read, and moved into the store once it is unlocked)
     #[serde(default)]
     pub openai_api_key: Option<String>,
     #[serde(default)]
     pub openrouter_api_key: Option<String>,
     #[serde(default)]
     pub google_api_key: Option<String>,
+    /// Key file that unlocks the secret store at startup (alternative to a passphrase)
+    #[serde(default)]
+    pub secrets_key_file: Option<String>,
+    // Response cache (data/response_cache.sqlite)
+    #[serde(default)]
+    pub response_cache_enabled: bool,
+    /// Cache entry lifetime in seconds (None = keep until cleared)
+    #[serde(default)]
+    pub response_cache_ttl_secs: Option<u64>,
+    // Offline embeddings (hashed n-gram vectoriser)
+    #[serde(default)]
+    pub local_embeddings_mode: LocalEmbeddingsMode,
+    /// Optional JSON weights file for the local vectoriser
+    #[serde(default)]
+    pub local_embeddings_model_path: Option<String>,
+    // Scripted provider (agents with provider "mock")
+    /// Mock script file (built-in script when unset)
+    #[serde(default)]
+    pub mock_script_path: Option<String>,
+    /// Append every live generation to this JSONL file for later replay
+    #[serde(default)]
+    pub record_fixtures_path: Option<String>,
+    // Request scheduler (per provider / Ollama host)
+    /// Max concurrent requests keyed by provider ("ollama") or lane ("ollama@http://host:11434")
+    #[serde(default)]
+    pub provider_max_in_flight: HashMap<String, usize>,
+    /// Limit for lanes not listed in `provider_max_in_flight`
+    #[serde(default = "default_max_in_flight")]
+    pub default_max_in_flight: usize,
+    // Ollama host pool (ollama_url is always the first host)
+    /// Extra Ollama servers; requests go to the least-loaded healthy host serving the model
+    #[serde(default)]
+    pub ollama_hosts: Vec<OllamaHostConfig>,
+    /// Seconds between /api/tags health polls of every host
+    #[serde(default = "default_ollama_health_interval")]
+    pub ollama_health_interval_secs: u64,
+    // Model warm-up
+    /// How long Ollama keeps warmed models loaded (Ollama duration, e.g. "30m", "-1" = forever)
+    #[serde(default = "default_ollama_keep_alive")]
+    pub ollama_keep_alive: String,
+    /// Seconds before a topic round to pre-load active agents' models (0 = off)
+    #[serde(default = "default_model_warmup_lead")]
+    pub model_warmup_lead_secs: u64,
+    // Contradiction detection
+    /// Agent (id or name) that compares new verdicts with similar past ones (first active agent when unset)
+    #[serde(default)]
+    pub contradiction_judge_agent: Option<String>,
+    // Chat retention and periodic context resets
+    /// Limits per channel name ("general", "topic", ...); channels not listed are kept forever
+    #[serde(default)]
+    pub channel_retention: HashMap<String, RetentionPolicy>,
+    /// Directory for archives of pruned messages (data/archive when unset)
+    #[serde(default)]
+    pub retention_archive_dir: Option<String>,
+    /// Seconds between retention passes
+    #[serde(default = "default_retention_interval")]
+    pub retention_interval_secs: u64,
+    // Namespaces (separate councils)
+    /// Councils with their own knowledge bank, keyed by name; "default" is data/knowledge_bank.sqlite
+    #[serde(default)]
+    pub namespaces: HashMap<String, NamespaceConfig>,
+}
+
+/// Agents and chat channels belonging to one namespace
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NamespaceConfig {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Agent ids or names on this council (empty = every active agent not claimed elsewhere)
+    #[serde(default)]
+    pub agents: Vec<String>,
+    /// Channel names whose history, RAG and retention live in this namespace
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+fn default_retention_interval() -> u64 {
+    3600
+}
+
+/// Retention limits and reset schedule for one chat channel
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Delete messages older than this many days
+    #[serde(default)]
+    pub max_age_days: Option<u64>,
+    /// Keep only this many newest messages
+    #[serde(default)]
+    pub max_messages: Option<usize>,
+    /// Write messages to a gzipped NDJSON archive before deleting them
+    #[serde(default)]
+    pub archive: bool,
+    /// Clear the channel's RAG context this often (the periodic "fresh start")
+    #[serde(default)]
+    pub reset_interval_hours: Option<u64>,
+}
+
+fn default_ollama_health_interval() -> u64 {
+    30
+}
+
+fn default_ollama_keep_alive() -> String {
+    "30m".to_string()
+}
+
+fn default_model_warmup_lead() -> u64 {
+    120
+}
+
+/// An additional Ollama server with its own credentials
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OllamaHostConfig {
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+fn default_max_in_flight() -> usize {
+    crate::providers::scheduler::DEFAULT_MAX_IN_FLIGHT
+}
+
+/// How the in-process embedding provider is used by the knowledge bank
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocalEmbeddingsMode {
+    /// Ollama only
+    Off,
+    /// Ollama first, local vectoriser when Ollama is unreachable
+    #[default]
+    Fallback,
+    /// Local vectoriser only (fully offline)
+    Primary,
 }
 
 impl Default for AppConfig {
@@ -41,6 +181,24 @@ impl Default for AppConfig {
             openai_api_key: None,
             openrouter_api_key: None,
             google_api_key: None,
+            secrets_key_file: None,
+            response_cache_enabled: false,
+            response_cache_ttl_secs: None,
+            local_embeddings_mode: LocalEmbeddingsMode::default(),
+            local_embeddings_model_path: None,
+            mock_script_path: None,
+            record_fixtures_path: None,
+            provider_max_in_flight: HashMap::new(),
+            default_max_in_flight: default_max_in_flight(),
+            ollama_hosts: Vec::new(),
+            ollama_health_interval_secs: default_ollama_health_interval(),
+            ollama_keep_alive: default_ollama_keep_alive(),
+            model_warmup_lead_secs: default_model_warmup_lead(),
+            contradiction_judge_agent: None,
+            channel_retention: HashMap::new(),
+            retention_archive_dir: None,
+            retention_interval_secs: default_retention_interval(),
+            namespaces: HashMap::new(),
         }
     }
 }
@@ -136,6 +294,124 @@ impl AppConfig {
         }
     }
 
+    fn api_key_slot(&mut self, provider: &str) -> Option<&mut Option<String>> {
+        match provider.to_lowercase().as_str() {
+            "openai" => Some(&mut self.openai_api_key),
+            "openrouter" => Some(&mut self.openrouter_api_key),
+            "google" => Some(&mut self.google_api_key),
+            _ => None,
+        }
+    }
+
+    /// API key for a provider, with `secret:` references resolved through the secret store
+    pub fn api_key(&self, provider: &str) -> Result<Option<String>, String> {
+        let value = match provider.to_lowercase().as_str() {
+            "openai" => &self.openai_api_key,
+            "openrouter" => &self.openrouter_api_key,
+            "google" => &self.google_api_key,
+            _ => return Ok(None),
+        };
+        value
+            .as_deref()
+            .map(|v| crate::secrets::global().resolve(v))
+            .transpose()
+    }
+
+    /// Store a provider's API key (new or rotated) and keep only its reference
+    pub fn set_api_key(
+        &mut self,
+        provider: &str,
+        value: &str,
+        store: &SecretStore,
+    ) -> Result<String, String> {
+        let name = format!("{}_api_key", provider.to_lowercase());
+        let slot = self
+            .api_key_slot(provider)
+            .ok_or_else(|| format!("Provider {} has no API key", provider))?;
+        let reference = store.set(&name, value)?;
+        *slot = Some(reference.clone());
+        Ok(reference)
+    }
+
+    /// Move plaintext API keys into the (unlocked) store; returns how many moved
+    pub fn seal_api_keys(&mut self, store: &SecretStore) -> Result<usize, String> {
+        let mut sealed = 0;
+        for provider in API_KEY_PROVIDERS {
+            let plaintext = match self.api_key_slot(provider) {
+                Some(Some(value)) if !is_secret_ref(value) => value.clone(),
+                _ => continue,
+            };
+            self.set_api_key(provider, &plaintext, store)?;
+            sealed += 1;
+        }
+        Ok(sealed)
+    }
+
+    /// Copy for API responses: plaintext keys and the Ollama password become previews
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        for provider in API_KEY_PROVIDERS {
+            if let Some(Some(value)) = config.api_key_slot(provider) {
+                *value = redact(value);
+            }
+        }
+        if let Some(password) = &mut config.ollama_password {
+            *password = redact(password);
+        }
+        for host in &mut config.ollama_hosts {
+            if let Some(password) = &mut host.password {
+                *password = redact(password);
+            }
+        }
+        config
+    }
+
+    /// Undo `redacted()` for a config posted back by the UI (unchanged previews keep the real value)
+    pub fn restore_redacted(&mut self, current: &AppConfig) {
+        let mut current = current.clone();
+        for provider in API_KEY_PROVIDERS {
+            let previous = current.api_key_slot(provider).and_then(|v| v.take());
+            if let (Some(Some(value)), Some(previous)) = (self.api_key_slot(provider), previous) {
+                if *value == redact(&previous) {
+                    *value = previous;
+                }
+            }
+        }
+        if let (Some(password), Some(previous)) = (&mut self.ollama_password, &current.ollama_password) {
+            if *password == redact(previous) {
+                *password = previous.clone();
+            }
+        }
+        for host in &mut self.ollama_hosts {
+            let previous = current
+                .ollama_hosts
+                .iter()
+                .find(|h| h.url == host.url)
+                .and_then(|h| h.password.as_ref());
+            if let (Some(password), Some(previous)) = (&mut host.password, previous) {
+                if *password == redact(previous) {
+                    *password = previous.clone();
+                }
+            }
+        }
+    }
+
+    /// All Ollama hosts: `ollama_url` with its credentials first, then `ollama_hosts` (deduplicated)
+    pub fn ollama_host_list(&self) -> Vec<OllamaHostConfig> {
+        let mut hosts = vec![OllamaHostConfig {
+            url: self.ollama_url.clone(),
+            username: self.ollama_username.clone(),
+            password: self.ollama_password.clone(),
+        }];
+        for host in &self.ollama_hosts {
+            let url = host.url.trim_end_matches('/');
+            if !hosts.iter().any(|h| h.url.trim_end_matches('/') == url) {
+                hosts.push(host.clone());
+            }
+        }
+        hosts
+    }
+
     /// Get configured provider names (for display)
     pub fn available_providers(&self) -> Vec<&str> {
         let mut providers = vec!["ollama"]; // Always available
@@ -148,6 +424,7 @@ impl AppConfig {
         if self.openrouter_api_key.is_some() {
             providers.push("openrouter");
         }
+        providers.push("mock"); // Scripted, never needs a server
         providers
     }
 }
@@ -0,0 +1,448 @@
+// Contradiction detection - Compare each new verdict with similar earlier ones
+//
+// After consensus, the knowledge bank is searched for past verdicts on
+// semantically close questions. A judge agent classifies each pair as
+// consistent, refining or contradicting; the result is stored as a typed link
+// (migration 0006) and contradictions are announced in #knowledge.
+
+use crate::agents::{Agent, AgentPool};
+use crate::chat::{ChannelManager, ChannelType};
+use crate::config::AppConfig;
+use crate::knowledge::KnowledgeBank;
+use crate::logger::{LogLevel, Logger};
+use crate::namespaces::{NamespaceRegistry, DEFAULT_NAMESPACE};
+use crate::provider_dispatch::{self, DispatchOptions};
+use crate::providers::{OllamaPool, RequestPriority, RequestScheduler, ResponseFormat};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Past verdicts sent to the judge per review
+pub const MAX_CANDIDATES: usize = 3;
+
+/// Minimum cosine similarity between questions to compare their verdicts
+pub const MIN_SIMILARITY: f32 = 0.6;
+
+const JUDGE_SYSTEM_PROMPT: &str = "You are the judge of an AI council. You check whether the council's \
+verdicts agree with each other. Do not re-open the questions; only compare what was decided.";
+
+/// How a new verdict relates to an earlier one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerdictRelation {
+    /// Agrees with the earlier verdict
+    Consistent,
+    /// Agrees, but narrows, extends or adds conditions
+    Refining,
+    /// Both cannot hold
+    Contradicting,
+}
+
+impl VerdictRelation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerdictRelation::Consistent => "consistent",
+            VerdictRelation::Refining => "refining",
+            VerdictRelation::Contradicting => "contradicting",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "consistent" => Some(VerdictRelation::Consistent),
+            "refining" => Some(VerdictRelation::Refining),
+            "contradicting" => Some(VerdictRelation::Contradicting),
+            _ => None,
+        }
+    }
+}
+
+/// An earlier verdict on a question close to the one under review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PastVerdict {
+    pub deliberation_id: String,
+    pub question: String,
+    pub verdict: String,
+    /// Cosine similarity of the questions
+    pub similarity: f32,
+}
+
+/// A judged relation between a verdict and an earlier one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerdictLink {
+    /// The newer verdict
+    pub deliberation_id: String,
+    /// The earlier verdict it was compared with
+    pub related_id: String,
+    pub relation: VerdictRelation,
+    pub reason: String,
+    pub similarity: f32,
+    /// Name of the judging agent
+    pub judge: String,
+    pub created_at: i64,
+}
+
+/// Prompt asking the judge to relate `verdict` to `past`
+pub fn judge_prompt(question: &str, verdict: &str, past: &PastVerdict) -> String {
+    format!(
+        "Earlier verdict\nQuestion: {}\nVerdict: {}\n\n\
+         New verdict\nQuestion: {}\nVerdict: {}\n\n\
+         Classify how the new verdict relates to the earlier one:\n\
+         - consistent: it agrees with the earlier verdict\n\
+         - refining: it agrees, but narrows, extends or adds conditions\n\
+         - contradicting: both verdicts cannot hold at the same time\n\n\
+         Reply with JSON only: {{\"relation\": \"consistent|refining|contradicting\", \"reason\": \"<one sentence>\"}}",
+        past.question, past.verdict, question, verdict
+    )
+}
+
+/// Words that turn a following "consistent" into its opposite
+const NEGATIONS: &[&str] = &["not", "isn't", "aren't", "never", "no", "hardly"];
+
+/// Relation and reason from the judge's reply (JSON, or prose naming the relation)
+pub fn parse_judgement(reply: &str) -> Result<(VerdictRelation, String), String> {
+    let json = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str::<serde_json::Value>(&reply[start..=end]).ok(),
+        _ => None,
+    };
+    if let Some(value) = json {
+        let relation = value
+            .get("relation")
+            .and_then(|v| v.as_str())
+            .and_then(VerdictRelation::parse)
+            .ok_or_else(|| format!("Judge reply has no valid relation: {}", value))?;
+        let reason = value.get("reason").and_then(|v| v.as_str()).unwrap_or_default();
+        return Ok((relation, reason.trim().to_string()));
+    }
+
+    // Models without JSON mode: take whichever relation is named first, by whole word.
+    // "inconsistent" and a negated "consistent" ("not consistent") count as contradictions.
+    let lower = reply.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .collect();
+    words
+        .iter()
+        .enumerate()
+        .find_map(|(i, word)| {
+            let negated = i > 0 && NEGATIONS.contains(&words[i - 1]);
+            if word.starts_with("contradict") || word.starts_with("inconsistent") {
+                Some(VerdictRelation::Contradicting)
+            } else if word.starts_with("refin") {
+                Some(VerdictRelation::Refining)
+            } else if *word == "consistent" {
+                Some(if negated { VerdictRelation::Contradicting } else { VerdictRelation::Consistent })
+            } else {
+                None
+            }
+        })
+        .map(|relation| (relation, reply.trim().to_string()))
+        .ok_or_else(|| format!("Judge reply names no relation: {}", reply.trim()))
+}
+
+/// #knowledge message listing the earlier verdicts a new one contradicts
+pub fn announcement(question: &str, verdict: &str, contradictions: &[(PastVerdict, VerdictLink)]) -> String {
+    let mut message = format!(
+        "⚖️ **Contradiction Detected**\n\n**Question:** {}\n**Verdict:** {}\n\nContradicts earlier consensus:\n",
+        question, verdict
+    );
+    for (past, link) in contradictions {
+        message.push_str(&format!(
+            "- **{}** → {}\n  _{} (judged by {})_\n",
+            past.question, past.verdict, link.reason, link.judge
+        ));
+    }
+    message
+}
+
+/// Runs the post-consensus review of new verdicts
+pub struct ContradictionDetector {
+    knowledge_bank: Arc<KnowledgeBank>,
+    agent_pool: Arc<AgentPool>,
+    channel_manager: Arc<ChannelManager>,
+    scheduler: Option<Arc<RequestScheduler>>,
+    ollama_pool: Option<Arc<OllamaPool>>,
+    namespaces: Option<Arc<NamespaceRegistry>>,
+    logger: Arc<Logger>,
+}
+
+impl ContradictionDetector {
+    pub fn new(
+        knowledge_bank: Arc<KnowledgeBank>,
+        agent_pool: Arc<AgentPool>,
+        channel_manager: Arc<ChannelManager>,
+        logger: Arc<Logger>,
+    ) -> Self {
+        Self {
+            knowledge_bank,
+            agent_pool,
+            channel_manager,
+            scheduler: None,
+            ollama_pool: None,
+            namespaces: None,
+            logger,
+        }
+    }
+
+    /// Queue judge requests as background work
+    pub fn with_scheduler(mut self, scheduler: Arc<RequestScheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    /// Route an Ollama judge over the host pool instead of the single `ollama_url`
+    pub fn with_ollama_pool(mut self, ollama_pool: Arc<OllamaPool>) -> Self {
+        self.ollama_pool = Some(ollama_pool);
+        self
+    }
+
+    /// Judge with the namespace's own council and announce only where its #knowledge lives
+    pub fn with_namespaces(mut self, namespaces: Arc<NamespaceRegistry>) -> Self {
+        self.namespaces = Some(namespaces);
+        self
+    }
+
+    /// The configured judge, else the first active agent by name (among `namespace`'s members)
+    async fn judge(&self, config: &AppConfig, namespace: Option<&str>) -> Option<Agent> {
+        let mut agents = self.agent_pool.list_active_agents().await;
+        if let Some(namespaces) = &self.namespaces {
+            agents = namespaces.members(namespace, agents);
+        }
+        match &config.contradiction_judge_agent {
+            Some(wanted) => agents
+                .into_iter()
+                .find(|a| a.id == *wanted || a.name.eq_ignore_ascii_case(wanted)),
+            None => {
+                agents.sort_by(|a, b| a.name.cmp(&b.name));
+                agents.into_iter().next()
+            }
+        }
+    }
+
+    /// Compare a new verdict with similar past verdicts, store the links and
+    /// announce contradictions in #knowledge
+    pub async fn review(
+        &self,
+        config: &AppConfig,
+        deliberation_id: &str,
+        question: &str,
+        verdict: &str,
+    ) -> Result<Vec<VerdictLink>, String> {
+        self.review_in(&self.knowledge_bank, None, config, deliberation_id, question, verdict)
+            .await
+    }
+
+    /// `review` against another namespace's bank (verdicts are only compared within a namespace)
+    pub async fn review_in(
+        &self,
+        knowledge_bank: &KnowledgeBank,
+        namespace: Option<&str>,
+        config: &AppConfig,
+        deliberation_id: &str,
+        question: &str,
+        verdict: &str,
+    ) -> Result<Vec<VerdictLink>, String> {
+        let candidates = knowledge_bank
+            .similar_verdicts(deliberation_id, question, MAX_CANDIDATES, MIN_SIMILARITY)
+            .await?;
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut judge = self.judge(config, namespace).await.ok_or("No judge agent available")?;
+        judge.generation.response_format = ResponseFormat::Json;
+        judge.temperature = judge.temperature.min(0.2);
+
+        let mut links = Vec::new();
+        let mut contradictions = Vec::new();
+        for past in candidates {
+            let outcome = provider_dispatch::generate_for_agent(
+                &judge,
+                judge_prompt(question, verdict, &past),
+                Some(JUDGE_SYSTEM_PROMPT.to_string()),
+                config,
+                Some(self.logger.clone()),
+                DispatchOptions {
+                    scheduler: self.scheduler.as_ref(),
+                    priority: RequestPriority::Background,
+                    ollama_pool: self.ollama_pool.as_deref(),
+                    ..Default::default()
+                },
+            )
+            .await;
+            let (relation, reason) = match outcome.result.and_then(|reply| parse_judgement(&reply)) {
+                Ok(judgement) => judgement,
+                Err(e) => {
+                    self.logger.log(
+                        LogLevel::Warning,
+                        "contradiction",
+                        &format!("⚠️ Could not compare {} with {}: {}", deliberation_id, past.deliberation_id, e),
+                    );
+                    continue;
+                }
+            };
+
+            let link = VerdictLink {
+                deliberation_id: deliberation_id.to_string(),
+                related_id: past.deliberation_id.clone(),
+                relation,
+                reason,
+                similarity: past.similarity,
+                judge: judge.name.clone(),
+                created_at: chrono::Utc::now().timestamp(),
+            };
+            knowledge_bank.store_verdict_link(&link).await?;
+            if relation == VerdictRelation::Contradicting {
+                contradictions.push((past, link.clone()));
+            }
+            links.push(link);
+        }
+
+        if !contradictions.is_empty() {
+            self.logger.log(
+                LogLevel::Warning,
+                "contradiction",
+                &format!("⚖️ Verdict {} contradicts {} earlier verdict(s)", deliberation_id, contradictions.len()),
+            );
+            if self.announces_for(namespace) {
+                self.channel_manager
+                    .send_system_message(ChannelType::Knowledge, announcement(question, verdict, &contradictions))?;
+            }
+        }
+        Ok(links)
+    }
+
+    /// Whether #knowledge belongs to `namespace`
+    fn announces_for(&self, namespace: Option<&str>) -> bool {
+        let namespace = namespace.unwrap_or(DEFAULT_NAMESPACE);
+        match &self.namespaces {
+            Some(namespaces) => namespaces.namespace_of_channel(ChannelType::Knowledge) == namespace,
+            None => namespace == DEFAULT_NAMESPACE,
+        }
+    }
+
+    /// Run `review_in` in the background, logging failures
+    pub fn spawn_review(
+        self: &Arc<Self>,
+        knowledge_bank: Arc<KnowledgeBank>,
+        namespace: Option<String>,
+        config: AppConfig,
+        deliberation_id: String,
+        question: String,
+        verdict: String,
+    ) {
+        let detector = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = detector
+                .review_in(&knowledge_bank, namespace.as_deref(), &config, &deliberation_id, &question, &verdict)
+                .await
+            {
+                detector.logger.log(
+                    LogLevel::Warning,
+                    "contradiction",
+                    &format!("⚠️ Contradiction check for {} failed: {}", deliberation_id, e),
+                );
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::local_embeddings::LocalEmbeddingProvider;
+    use crate::providers::mock::{MockRule, MockScript};
+
+    #[test]
+    fn test_parse_judgement() {
+        let (relation, reason) =
+            parse_judgement("Sure.\n{\"relation\": \"Refining\", \"reason\": \"Adds a budget cap.\"}").unwrap();
+        assert_eq!((relation, reason.as_str()), (VerdictRelation::Refining, "Adds a budget cap."));
+
+        let (relation, _) = parse_judgement("These verdicts contradict each other; not consistent.").unwrap();
+        assert_eq!(relation, VerdictRelation::Contradicting);
+        let (relation, _) = parse_judgement("The two verdicts are inconsistent.").unwrap();
+        assert_eq!(relation, VerdictRelation::Contradicting);
+        let (relation, _) = parse_judgement("They are not consistent: one adopts it, one rejects it.").unwrap();
+        assert_eq!(relation, VerdictRelation::Contradicting);
+        let (relation, _) = parse_judgement("Consistent; both adopt the plan.").unwrap();
+        assert_eq!(relation, VerdictRelation::Consistent);
+
+        assert!(parse_judgement("{\"relation\": \"unsure\"}").is_err());
+        assert!(parse_judgement("No idea.").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_review_links_and_announces_contradictions() {
+        let logger = Arc::new(Logger::new(false));
+        let embedder = Arc::new(LocalEmbeddingProvider::new(logger.clone()));
+        let kb = Arc::new(KnowledgeBank::new("sqlite::memory:", logger.clone(), embedder).await.unwrap());
+        for (id, question, verdict) in [
+            ("old", "Should the council adopt a four-day work week?", "Yes, adopt it."),
+            ("other", "What is the best recipe for banana bread?", "Use ripe bananas."),
+        ] {
+            kb.store_deliberation(&crate::deliberation::DeliberationResult {
+                session_id: id.to_string(),
+                question: question.to_string(),
+                rounds: Vec::new(),
+                consensus: Some(verdict.to_string()),
+                created_at: 0,
+                completed: true,
+            })
+            .await
+            .unwrap();
+        }
+        kb.process_embedding_queue().await.unwrap();
+
+        let script = std::env::temp_dir().join(format!("judge-{}.json", uuid::Uuid::new_v4()));
+        let rules = MockScript {
+            rules: vec![MockRule {
+                prompt_contains: Some("Classify how the new verdict".to_string()),
+                responses: vec!["{\"relation\": \"contradicting\", \"reason\": \"One adopts it, one rejects it.\"}".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        std::fs::write(&script, serde_json::to_string(&rules).unwrap()).unwrap();
+        let config = AppConfig {
+            mock_script_path: Some(script.to_string_lossy().into_owned()),
+            contradiction_judge_agent: Some("Judge".to_string()),
+            ..Default::default()
+        };
+
+        let agent_pool = Arc::new(AgentPool::new());
+        agent_pool
+            .add_agent(Agent::with_provider("Judge".to_string(), "mock".to_string(), "judge".to_string(), String::new()))
+            .await
+            .unwrap();
+        let channels = Arc::new(ChannelManager::new(None));
+        let detector = ContradictionDetector::new(kb.clone(), agent_pool.clone(), channels.clone(), logger.clone());
+
+        let links = detector
+            .review(&config, "new", "Should the council adopt a four day work week?", "No, reject it.")
+            .await
+            .unwrap();
+
+        assert_eq!(links.len(), 1);
+        assert_eq!((links[0].related_id.as_str(), links[0].relation), ("old", VerdictRelation::Contradicting));
+        assert_eq!(kb.verdict_links("old").await.unwrap().len(), 1);
+        let announced = channels.get_messages(ChannelType::Knowledge, 10, 0).unwrap();
+        assert!(announced[0].content.contains("Contradiction Detected"));
+
+        // #knowledge owned by another namespace: links are stored, nothing is announced there
+        let namespaces = Arc::new(NamespaceRegistry::new(Some(kb.clone()), logger.clone()));
+        let legal = crate::config::NamespaceConfig { channels: vec!["knowledge".to_string()], ..Default::default() };
+        namespaces.configure(&std::collections::HashMap::from([("legal".to_string(), legal.clone())]));
+        let scoped = ContradictionDetector::new(kb.clone(), agent_pool, channels.clone(), logger)
+            .with_namespaces(namespaces.clone());
+        let question = "Should the council adopt a four day work week?";
+        let links = scoped.review_in(&kb, None, &config, "newer", question, "No, reject it.").await.unwrap();
+        assert!(!links.is_empty());
+        assert_eq!(channels.get_messages(ChannelType::Knowledge, 10, 0).unwrap().len(), announced.len());
+
+        // A judge claimed by another namespace's council is not used
+        let legal = crate::config::NamespaceConfig { agents: vec!["Judge".to_string()], ..legal };
+        namespaces.configure(&std::collections::HashMap::from([("legal".to_string(), legal)]));
+        assert!(scoped.review_in(&kb, None, &config, "newest", question, "No, reject it.").await.is_err());
+        let _ = std::fs::remove_file(script);
+    }
+}
@@ -8,12 +8,29 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::knowledge::KnowledgeBank;
+use crate::logger::Logger;
+use crate::namespaces::{NamespaceRegistry, DEFAULT_NAMESPACE};
+use crate::providers::discovery::{fit_attachments, ModelCatalog};
+use crate::providers::mock::{append_fixture, Fixture};
+use crate::providers::reasoning::split_reasoning;
+use crate::providers::scheduler::lane_for;
+use crate::providers::{
+    AIProvider, Attachment, MockProvider, OllamaPool, OllamaProvider, RequestPriority,
+    RequestScheduler, ResponseCache, SchedulerSlot,
+};
 
 /// Manages council deliberation sessions
 pub struct CouncilSessionManager {
     sessions: Arc<Mutex<HashMap<String, CouncilSession>>>,
     consensus_threshold: f64, // Byzantine fault tolerance: 67%
     knowledge_bank: Option<Arc<KnowledgeBank>>,
+    response_cache: Option<Arc<ResponseCache>>,
+    mock_provider: Option<Arc<MockProvider>>,
+    scheduler: Option<Arc<RequestScheduler>>,
+    model_catalog: Option<Arc<ModelCatalog>>,
+    ollama_pool: Option<Arc<OllamaPool>>,
+    namespaces: Option<Arc<NamespaceRegistry>>,
+    record_fixtures_path: Option<String>,
 }
 
 impl CouncilSessionManager {
@@ -23,15 +40,95 @@ impl CouncilSessionManager {
             sessions: Arc::new(Mutex::new(HashMap::new())),
             consensus_threshold: 0.67,
             knowledge_bank,
+            response_cache: None,
+            mock_provider: None,
+            scheduler: None,
+            model_catalog: None,
+            ollama_pool: None,
+            namespaces: None,
+            record_fixtures_path: None,
         }
     }
 
-    /// Load sessions from DB
+    /// Answer repeated questions from the response cache
+    pub fn with_response_cache(mut self, response_cache: Option<Arc<ResponseCache>>) -> Self {
+        self.response_cache = response_cache;
+        self
+    }
+
+    /// Scripted provider used for agents whose provider is "mock"
+    pub fn with_mock_provider(mut self, mock_provider: Arc<MockProvider>) -> Self {
+        self.mock_provider = Some(mock_provider);
+        self
+    }
+
+    /// Queue agent requests behind interactive chat (council priority)
+    pub fn with_scheduler(mut self, scheduler: Arc<RequestScheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    /// Model metadata used to decide which agents can see attached images
+    pub fn with_model_catalog(mut self, model_catalog: Arc<ModelCatalog>) -> Self {
+        self.model_catalog = Some(model_catalog);
+        self
+    }
+
+    /// Spread Ollama agents over the host pool instead of the single `ollama_url`
+    pub fn with_ollama_pool(mut self, ollama_pool: Arc<OllamaPool>) -> Self {
+        self.ollama_pool = Some(ollama_pool);
+        self
+    }
+
+    /// Keep each session in the knowledge bank of its namespace
+    pub fn with_namespaces(mut self, namespaces: Arc<NamespaceRegistry>) -> Self {
+        self.namespaces = Some(namespaces);
+        self
+    }
+
+    /// Append live agent replies to a JSONL file the mock provider can replay
+    pub fn with_fixture_recording(mut self, path: Option<String>) -> Self {
+        self.record_fixtures_path = path;
+        self
+    }
+
+    /// Bank a session of `namespace` is persisted to (`None` = default)
+    fn bank_for(&self, namespace: Option<&str>) -> Option<Arc<KnowledgeBank>> {
+        match (namespace, &self.namespaces) {
+            (None, _) => self.knowledge_bank.clone(),
+            (Some(name), Some(namespaces)) => namespaces.get(Some(name)).ok(),
+            (Some(_), None) => None,
+        }
+    }
+
+    /// Check a requested namespace exists; "default" and empty names map to `None`
+    fn resolve_namespace(&self, namespace: Option<String>) -> Result<Option<String>, String> {
+        match namespace {
+            Some(name) if !name.is_empty() && name != DEFAULT_NAMESPACE => match &self.namespaces {
+                Some(namespaces) => namespaces.get(Some(name.as_str())).map(|_| Some(name)),
+                None => Err(format!("Unknown namespace '{}'", name)),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Load sessions from DB (every open namespace when namespaces are enabled)
     pub async fn load_from_db(&self) {
-        if let Some(kb) = &self.knowledge_bank {
+        let banks = match &self.namespaces {
+            Some(namespaces) => namespaces.open_banks(),
+            None => self
+                .knowledge_bank
+                .iter()
+                .map(|kb| (DEFAULT_NAMESPACE.to_string(), kb.clone()))
+                .collect(),
+        };
+        for (name, kb) in banks {
             if let Ok(loaded_sessions) = kb.load_sessions().await {
                 let mut sessions = self.sessions.lock().await;
-                for session in loaded_sessions {
+                for mut session in loaded_sessions {
+                    if name != DEFAULT_NAMESPACE {
+                        session.namespace = Some(name.clone());
+                    }
                     sessions.insert(session.id.clone(), session);
                 }
             }
@@ -40,6 +137,35 @@ impl CouncilSessionManager {
 
     /// Create new council session
     pub async fn create_session(&self, question: String) -> String {
+        self.create_session_with_attachments(question, Vec::new()).await
+    }
+
+    /// Create a council session about a question plus images (screenshots, diagrams, charts)
+    pub async fn create_session_with_attachments(
+        &self,
+        question: String,
+        attachments: Vec<Attachment>,
+    ) -> String {
+        self.insert_session(question, attachments, None).await
+    }
+
+    /// Create a council session belonging to `namespace` (`None` or "default" = default council)
+    pub async fn create_session_in_namespace(
+        &self,
+        question: String,
+        attachments: Vec<Attachment>,
+        namespace: Option<String>,
+    ) -> Result<String, String> {
+        let namespace = self.resolve_namespace(namespace)?;
+        Ok(self.insert_session(question, attachments, namespace).await)
+    }
+
+    async fn insert_session(
+        &self,
+        question: String,
+        attachments: Vec<Attachment>,
+        namespace: Option<String>,
+    ) -> String {
         let session_id = self.generate_session_id(&question);
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -55,13 +181,15 @@ impl CouncilSessionManager {
             consensus: None,
             status: SessionStatus::GatheringResponses,
             created_at: timestamp,
+            attachments,
+            namespace,
         };
 
         let mut sessions = self.sessions.lock().await;
         sessions.insert(session_id.clone(), session.clone());
 
         // Save to DB
-        if let Some(kb) = &self.knowledge_bank {
+        if let Some(kb) = self.bank_for(session.namespace.as_deref()) {
             let _ = kb.save_session(&session).await;
         }
 
@@ -78,6 +206,28 @@ impl CouncilSessionManager {
         signature: Option<String>,
         public_key: Option<String>,
     ) -> Result<(), String> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or(std::time::Duration::from_secs(0))
+            .as_secs();
+
+        self.push_response(
+            session_id,
+            CouncilResponse {
+                model_name,
+                response,
+                peer_id,
+                timestamp,
+                signature,
+                public_key,
+                reasoning: None,
+            },
+        )
+        .await
+    }
+
+    /// Append a response to a session that is still gathering responses
+    async fn push_response(&self, session_id: &str, response: CouncilResponse) -> Result<(), String> {
         let mut sessions = self.sessions.lock().await;
         let session = sessions.get_mut(session_id).ok_or("Session not found")?;
 
@@ -85,22 +235,10 @@ impl CouncilSessionManager {
             return Err("Session not in response gathering phase".to_string());
         }
 
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or(std::time::Duration::from_secs(0))
-            .as_secs();
-
-        session.responses.push(CouncilResponse {
-            model_name,
-            response,
-            peer_id,
-            timestamp,
-            signature,
-            public_key,
-        });
+        session.responses.push(response);
 
         // Save to DB
-        if let Some(kb) = &self.knowledge_bank {
+        if let Some(kb) = self.bank_for(session.namespace.as_deref()) {
             let _ = kb.save_session(session).await;
         }
 
@@ -226,7 +364,7 @@ impl CouncilSessionManager {
                 session.status = SessionStatus::ConsensusReached;
                 
                 // Save to DB
-                if let Some(kb) = &self.knowledge_bank {
+                if let Some(kb) = self.bank_for(session.namespace.as_deref()) {
                     let _ = kb.save_session(session).await;
                     
                     // Post result to #knowledge channel
@@ -306,6 +444,17 @@ impl CouncilSessionManager {
         sessions.values().cloned().collect()
     }
 
+    /// List the sessions of one namespace (`None` or "default" = default council)
+    pub async fn list_sessions_in(&self, namespace: Option<&str>) -> Vec<CouncilSession> {
+        let namespace = namespace.filter(|name| !name.is_empty() && *name != DEFAULT_NAMESPACE);
+        let sessions = self.sessions.lock().await;
+        sessions
+            .values()
+            .filter(|session| session.namespace.as_deref() == namespace)
+            .cloned()
+            .collect()
+    }
+
     /// Generate commitment hash for blind voting
     pub fn hash_vote(&self, vote: &str, salt: &str) -> String {
         let mut hasher = Sha256::new();
@@ -359,11 +508,64 @@ impl CouncilSessionManager {
         ollama_url: &str,
         timeout_seconds: u64,
         auth: Option<(String, String)>,
+    ) -> Result<String, String> {
+        let session_id = self.create_session(question).await;
+        self.gather_responses(session_id, agent_pool, agent_ids, ollama_url, timeout_seconds, auth, false)
+            .await
+    }
+
+    /// Like `create_session_with_agents`, with images attached to the question
+    ///
+    /// Agents whose models lack vision get a text description of each image instead.
+    /// With a `namespace`, the session is stored in that council's knowledge bank and only
+    /// agents sitting on that council may answer. `bypass_cache` asks the agents again
+    /// instead of replaying cached answers (fresh answers are still cached).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_session_with_agents_and_attachments(
+        &self,
+        question: String,
+        attachments: Vec<Attachment>,
+        namespace: Option<String>,
+        bypass_cache: bool,
+        agent_pool: Arc<AgentPool>,
+        agent_ids: Vec<String>,
+        ollama_url: &str,
+        auth: Option<(String, String)>,
+    ) -> Result<String, String> {
+        let namespace = self.resolve_namespace(namespace)?;
+        if let Some(namespaces) = &self.namespaces {
+            let agents = agent_pool.get_agents_by_ids(&agent_ids).await?;
+            let requested = agents.len();
+            if namespaces.members(namespace.as_deref(), agents).len() < requested {
+                return Err(format!(
+                    "Some agents do not sit on the '{}' council",
+                    namespace.as_deref().unwrap_or(DEFAULT_NAMESPACE)
+                ));
+            }
+        }
+        let session_id = self.insert_session(question, attachments, namespace).await;
+        self.gather_responses(session_id, agent_pool, agent_ids, ollama_url, 30, auth, bypass_cache)
+            .await
+    }
+
+    /// Ask every agent about an existing session's question, waiting at most `timeout_seconds`
+    #[allow(clippy::too_many_arguments)]
+    async fn gather_responses(
+        &self,
+        session_id: String,
+        agent_pool: Arc<AgentPool>,
+        agent_ids: Vec<String>,
+        ollama_url: &str,
+        timeout_seconds: u64,
+        auth: Option<(String, String)>,
+        bypass_cache: bool,
     ) -> Result<String, String> {
         use tokio::time::{timeout, Duration};
 
-        // Create session
-        let session_id = self.create_session(question.clone()).await;
+        let (question, attachments) = match self.get_session(&session_id).await {
+            Some(session) => (session.question, Arc::new(session.attachments)),
+            None => return Err("Session not found".to_string()),
+        };
 
         // Get agents
         let agents = agent_pool.get_agents_by_ids(&agent_ids).await?;
@@ -383,10 +585,21 @@ impl CouncilSessionManager {
             let ollama_url = ollama_url.to_string();
             let self_clone = self.clone();
             let auth_clone = auth.clone();
+            let attachments = attachments.clone();
+            let agent_pool = agent_pool.clone();
 
             let handle = tokio::spawn(async move {
                 self_clone
-                    .gather_agent_response(&session_id, &agent, &question, &ollama_url, auth_clone)
+                    .gather_agent_response(
+                        &session_id,
+                        &agent_pool,
+                        &agent,
+                        &question,
+                        &attachments,
+                        &ollama_url,
+                        auth_clone,
+                        bypass_cache,
+                    )
                     .await
             });
 
@@ -455,30 +668,125 @@ impl CouncilSessionManager {
     }
 
     /// Gather response from a single agent
+    #[allow(clippy::too_many_arguments)]
     async fn gather_agent_response(
         &self,
         session_id: &str,
+        agent_pool: &AgentPool,
         agent: &Agent,
         question: &str,
+        attachments: &[Attachment],
         ollama_url: &str,
         auth: Option<(String, String)>,
+        bypass_cache: bool,
     ) -> Result<(), String> {
         // Build prompt with agent's system context
         let system_prompt = crate::prompt::compose_system_prompt(&agent.system_prompt);
         let prompt = format!("Question: {}\n\nProvide your analysis and recommendation.", question);
 
-        // Call Ollama API
-        let auth_ref = auth.as_ref().map(|(u, p)| (u.as_str(), p.as_str()));
-        let response = crate::ollama::ask_ollama_with_auth(ollama_url, &agent.model, prompt, Some(system_prompt), auth_ref).await?;
+        let mut cache_request = agent.generation_request(prompt.clone(), Some(system_prompt.clone()));
+        cache_request.bypass_cache = bypass_cache;
+        let is_mock = agent.provider.eq_ignore_ascii_case("mock");
+        let cache_provider = if is_mock { "mock" } else { "ollama" };
+
+        if !attachments.is_empty() {
+            cache_request.attachments = attachments.to_vec();
+            let logger = Arc::new(Logger::new(false));
+            let provider: Arc<dyn AIProvider> = if is_mock {
+                match &self.mock_provider {
+                    Some(mock) => mock.clone(),
+                    None => Arc::new(MockProvider::from_optional_path(None, logger)),
+                }
+            } else {
+                let (username, password) = auth.clone().unzip();
+                Arc::new(
+                    OllamaProvider::new(ollama_url.to_string(), agent.model.clone(), logger)
+                        .with_auth(username, password),
+                )
+            };
+            cache_request = fit_attachments(
+                self.model_catalog.as_deref(),
+                cache_provider,
+                Some(provider.as_ref()),
+                cache_request,
+            )
+            .await;
+        }
+        let cached = match &self.response_cache {
+            Some(cache) => cache.get_reply(cache_provider, &cache_request).await,
+            None => None,
+        };
+
+        let reply = match cached {
+            Some(reply) => reply,
+            None => {
+                // Each attempt queues for its own slot, so backoff sleeps don't hold the lane
+                let slot = self.scheduler.as_ref().map(|scheduler| {
+                    SchedulerSlot::new(
+                        scheduler.clone(),
+                        lane_for(cache_provider, ollama_url),
+                        RequestPriority::Council,
+                        "council",
+                    )
+                });
+                let text = if is_mock {
+                    match &self.mock_provider {
+                        Some(mock) => mock.respond(&cache_request),
+                        None => MockProvider::from_optional_path(None, Arc::new(Logger::new(false)))
+                            .respond(&cache_request),
+                    }
+                } else {
+                    // Call Ollama API (pooled hosts when configured)
+                    let auth_ref = auth.as_ref().map(|(u, p)| (u.as_str(), p.as_str()));
+                    let outcome = match &self.ollama_pool {
+                        Some(pool) => {
+                            crate::ollama::ask_ollama_pooled(pool, &cache_request, agent.timeout_secs, slot.as_ref())
+                                .await
+                        }
+                        None => {
+                            crate::ollama::ask_ollama_with_retry(
+                                ollama_url,
+                                &cache_request,
+                                auth_ref,
+                                agent.timeout_secs,
+                                slot.as_ref(),
+                            )
+                            .await
+                        }
+                    };
+                    agent_pool.record_retries(&agent.id, outcome.retries).await;
+                    let text = outcome.result.map_err(|e| e.to_string())?;
+                    if let Some(path) = &self.record_fixtures_path {
+                        if let Err(e) = append_fixture(path, &Fixture::new(cache_provider, &cache_request, &text)) {
+                            eprintln!("⚠️ Fixture not recorded: {}", e);
+                        }
+                    }
+                    text
+                };
+                // Keep <think> traces out of the response other agents and the consensus see
+                let reply = split_reasoning(&text);
+                if let Some(cache) = &self.response_cache {
+                    cache.put_reply(cache_provider, &cache_request, &reply).await;
+                }
+                reply
+            }
+        };
 
         // Add response to session
-        self.add_response(
+        self.push_response(
             session_id,
-            agent.name.clone(),
-            response,
-            agent.id.clone(), // Use agent ID as peer ID
-            None,             // TODO: Add signature support
-            None,
+            CouncilResponse {
+                model_name: agent.name.clone(),
+                response: reply.answer,
+                peer_id: agent.id.clone(), // Use agent ID as peer ID
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or(std::time::Duration::from_secs(0))
+                    .as_secs(),
+                signature: None, // TODO: Add signature support
+                public_key: None,
+                reasoning: reply.reasoning,
+            },
         )
         .await?;
 
@@ -523,6 +831,13 @@ impl Clone for CouncilSessionManager {
             sessions: Arc::clone(&self.sessions),
             consensus_threshold: self.consensus_threshold,
             knowledge_bank: self.knowledge_bank.clone(),
+            response_cache: self.response_cache.clone(),
+            mock_provider: self.mock_provider.clone(),
+            scheduler: self.scheduler.clone(),
+            model_catalog: self.model_catalog.clone(),
+            ollama_pool: self.ollama_pool.clone(),
+            namespaces: self.namespaces.clone(),
+            record_fixtures_path: self.record_fixtures_path.clone(),
         }
     }
 }
@@ -545,6 +860,32 @@ mod tests {
         assert_eq!(session.responses.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_sessions_are_stored_in_their_namespace() {
+        let logger = Arc::new(Logger::new(false));
+        let embedder = Arc::new(crate::providers::LocalEmbeddingProvider::new(logger.clone()));
+        let infra = Arc::new(KnowledgeBank::new("sqlite::memory:", logger.clone(), embedder).await.unwrap());
+        let namespaces = Arc::new(NamespaceRegistry::new(None, logger));
+        namespaces.register("infra", infra.clone());
+        let manager = CouncilSessionManager::new(None).with_namespaces(namespaces);
+
+        assert!(manager
+            .create_session_in_namespace("Hire a lawyer?".to_string(), Vec::new(), Some("legal".to_string()))
+            .await
+            .is_err());
+        let infra_id = manager
+            .create_session_in_namespace("Migrate to IPv6?".to_string(), Vec::new(), Some("infra".to_string()))
+            .await
+            .unwrap();
+        let default_id = manager.create_session("Is free will real?".to_string()).await;
+
+        let stored: Vec<String> = infra.load_sessions().await.unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(stored, vec![infra_id.clone()]);
+        let ids = |sessions: Vec<CouncilSession>| sessions.into_iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(manager.list_sessions_in(Some("infra")).await), vec![infra_id]);
+        assert_eq!(ids(manager.list_sessions_in(None).await), vec![default_id]);
+    }
+
     #[tokio::test]
     async fn test_add_response() {
         let manager = CouncilSessionManager::new(None);
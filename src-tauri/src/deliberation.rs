@@ -1,6 +1,7 @@
 use crate::logger::{LogLevel, Logger};
 use crate::ollama::OllamaClient;
 use crate::prompt;
+use crate::providers::reasoning::split_reasoning;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -29,6 +30,9 @@ pub struct MemberResponse {
     pub model: String,
     pub response: String,
     pub timestamp: u64,
+    /// `<think>` trace split from `response`; excluded from later rounds and embeddings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
 }
 
 /// Result of a deliberation session
@@ -250,7 +254,7 @@ impl DeliberationEngine {
 
         // Query Ollama
         let client = ollama_client.lock().await;
-        let response = client.ask(&member.model, &prompt, Some(&system_directive)).await?;
+        let reply = split_reasoning(&client.ask(&member.model, &prompt, Some(&system_directive)).await?);
 
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -260,8 +264,9 @@ impl DeliberationEngine {
         Ok(MemberResponse {
             member_name: member.name,
             model: member.model,
-            response,
+            response: reply.answer,
             timestamp,
+            reasoning: reply.reasoning,
         })
     }
 
@@ -368,6 +373,7 @@ mod tests {
                 model: "test-model".to_string(),
                 response: "Test response".to_string(),
                 timestamp: 0,
+                reasoning: None,
             }],
         };
 
@@ -394,12 +400,14 @@ mod tests {
                     model: "model1".to_string(),
                     response: "I agree with the previous analysis.".to_string(),
                     timestamp: 0,
+                    reasoning: None,
                 },
                 MemberResponse {
                     member_name: "Member2".to_string(),
                     model: "model2".to_string(),
                     response: "I concur with this approach.".to_string(),
                     timestamp: 0,
+                    reasoning: None,
                 },
             ],
         };
@@ -415,12 +423,14 @@ mod tests {
                     model: "model1".to_string(),
                     response: "I strongly disagree.".to_string(),
                     timestamp: 0,
+                    reasoning: None,
                 },
                 MemberResponse {
                     member_name: "Member2".to_string(),
                     model: "model2".to_string(),
                     response: "This is completely wrong.".to_string(),
                     timestamp: 0,
+                    reasoning: None,
                 },
             ],
         };
@@ -0,0 +1,223 @@
+// Embedding queue - Durable backlog of chunks and chat messages awaiting vectors
+//
+// Writers enqueue a reference (migration 0004) instead of calling the embedding
+// endpoint inline; the knowledge bank's worker drains due items in batches.
+// Failed items stay queued and are retried with exponential backoff.
+
+use crate::vector_index::VectorSource;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+
+/// Items sent to the embedder per request
+pub const BATCH_SIZE: usize = 32;
+
+/// First retry delay; doubles per failed attempt
+const RETRY_BASE_SECS: i64 = 5;
+/// Retry delay ceiling
+const RETRY_MAX_SECS: i64 = 3600;
+
+/// A queued item with the text to embed
+#[derive(Debug, Clone)]
+pub struct QueuedItem {
+    pub source: VectorSource,
+    pub id: String,
+    pub text: String,
+    /// Channel for chat messages, empty otherwise
+    pub scope: String,
+    pub attempts: i64,
+    /// Revision read; re-queueing bumps it (migration 0009)
+    version: i64,
+}
+
+/// Backlog summary for the UI
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueStatus {
+    /// Items still awaiting an embedding
+    pub pending: i64,
+    /// Of those, items that failed at least once
+    pub retrying: i64,
+    pub last_error: Option<String>,
+    /// Unix time of the next scheduled attempt
+    pub next_attempt_at: Option<i64>,
+}
+
+/// Seconds to wait after the `attempts`-th failure
+pub fn retry_delay(attempts: i64) -> i64 {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    RETRY_BASE_SECS.saturating_mul(1 << exponent).min(RETRY_MAX_SECS)
+}
+
+/// Queue `id` for embedding; re-queueing resets its retry state (the text changed)
+pub async fn enqueue(pool: &SqlitePool, source: VectorSource, id: &str) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp();
+    sqlx::query(
+        "INSERT INTO embedding_queue (source, item_id, next_attempt_at, enqueued_at) VALUES (?, ?, ?, ?) \
+         ON CONFLICT(source, item_id) DO UPDATE SET \
+             attempts = 0, last_error = NULL, version = embedding_queue.version + 1, \
+             next_attempt_at = excluded.next_attempt_at, enqueued_at = excluded.enqueued_at",
+    )
+    .bind(source.key())
+    .bind(id)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to queue {} for embedding: {}", id, e))?;
+    Ok(())
+}
+
+/// Drop entries whose content was deleted (or chat cleared) while queued
+async fn prune(pool: &SqlitePool) -> Result<(), String> {
+    sqlx::query(
+        "DELETE FROM embedding_queue WHERE \
+            (source = 'chunks' AND item_id NOT IN (SELECT id FROM text_chunks)) \
+         OR (source = 'chat' AND item_id NOT IN (SELECT id FROM chat_logs WHERE context_cleared = 0))",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to prune embedding queue: {}", e))?;
+    Ok(())
+}
+
+/// Up to `limit` items whose next attempt is due, oldest first
+pub async fn due(pool: &SqlitePool, limit: usize) -> Result<Vec<QueuedItem>, String> {
+    prune(pool).await?;
+
+    let rows = sqlx::query(
+        "SELECT q.source, q.item_id, q.attempts, q.version, \
+                COALESCE(t.text, c.content) AS text, COALESCE(c.channel, '') AS scope \
+         FROM embedding_queue q \
+         LEFT JOIN text_chunks t ON q.source = 'chunks' AND t.id = q.item_id \
+         LEFT JOIN chat_logs c ON q.source = 'chat' AND c.id = q.item_id \
+         WHERE q.next_attempt_at <= ? \
+         ORDER BY q.next_attempt_at, q.enqueued_at LIMIT ?",
+    )
+    .bind(chrono::Utc::now().timestamp())
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read embedding queue: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|r| {
+            Some(QueuedItem {
+                source: VectorSource::from_key(&r.get::<String, _>("source"))?,
+                id: r.get("item_id"),
+                text: r.get::<Option<String>, _>("text")?,
+                scope: r.get("scope"),
+                attempts: r.get("attempts"),
+                version: r.get("version"),
+            })
+        })
+        .collect())
+}
+
+/// Remove embedded items (unless re-queued since they were read)
+pub async fn complete(pool: &SqlitePool, items: &[QueuedItem]) -> Result<(), String> {
+    for item in items {
+        sqlx::query("DELETE FROM embedding_queue WHERE source = ? AND item_id = ? AND version = ?")
+            .bind(item.source.key())
+            .bind(&item.id)
+            .bind(item.version)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to dequeue {}: {}", item.id, e))?;
+    }
+    Ok(())
+}
+
+/// Record a failed attempt and schedule the retry
+pub async fn fail(pool: &SqlitePool, items: &[QueuedItem], error: &str) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp();
+    for item in items {
+        sqlx::query(
+            "UPDATE embedding_queue SET attempts = attempts + 1, last_error = ?, next_attempt_at = ? \
+             WHERE source = ? AND item_id = ?",
+        )
+        .bind(error)
+        .bind(now + retry_delay(item.attempts + 1))
+        .bind(item.source.key())
+        .bind(&item.id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to reschedule {}: {}", item.id, e))?;
+    }
+    Ok(())
+}
+
+pub async fn status(pool: &SqlitePool) -> Result<QueueStatus, String> {
+    let row = sqlx::query(
+        "SELECT COUNT(*) AS pending, \
+                COALESCE(SUM(CASE WHEN attempts > 0 THEN 1 ELSE 0 END), 0) AS retrying, \
+                MIN(next_attempt_at) AS next_attempt_at, \
+                (SELECT last_error FROM embedding_queue WHERE last_error IS NOT NULL \
+                 ORDER BY next_attempt_at DESC LIMIT 1) AS last_error \
+         FROM embedding_queue",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to read embedding queue status: {}", e))?;
+
+    Ok(QueueStatus {
+        pending: row.get("pending"),
+        retrying: row.get("retrying"),
+        last_error: row.get("last_error"),
+        next_attempt_at: row.get("next_attempt_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(1), 5);
+        assert_eq!(retry_delay(2), 10);
+        assert_eq!(retry_delay(4), 40);
+        assert_eq!(retry_delay(20), RETRY_MAX_SECS);
+        assert_eq!(retry_delay(i64::MAX), RETRY_MAX_SECS);
+    }
+
+    #[tokio::test]
+    async fn test_queue_lifecycle() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::schema::MIGRATOR.run(&pool).await.unwrap();
+        for sql in [
+            "INSERT INTO deliberations VALUES ('d1', 'Ticket review', NULL, 0, 1)",
+            "INSERT INTO text_chunks VALUES ('c1', 'd1', 'ticket approved', 'text', NULL)",
+            "INSERT INTO chat_logs (id, channel, author, author_type, content, timestamp) VALUES ('m1', 'general', 'a', 'Human', 'hello', 0)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+        enqueue(&pool, VectorSource::Chunks, "c1").await.unwrap();
+        enqueue(&pool, VectorSource::Chat, "m1").await.unwrap();
+        enqueue(&pool, VectorSource::Chunks, "gone").await.unwrap();
+
+        let items = due(&pool, 10).await.unwrap();
+        assert_eq!(items.len(), 2);
+        let chat = items.iter().find(|i| i.source == VectorSource::Chat).unwrap();
+        assert_eq!((chat.text.as_str(), chat.scope.as_str()), ("hello", "general"));
+
+        fail(&pool, &items, "endpoint down").await.unwrap();
+        assert!(due(&pool, 10).await.unwrap().is_empty());
+        let queued = status(&pool).await.unwrap();
+        assert_eq!((queued.pending, queued.retrying), (2, 2));
+        assert_eq!(queued.last_error.as_deref(), Some("endpoint down"));
+
+        // Re-queueing (text changed) makes the item due again
+        enqueue(&pool, VectorSource::Chunks, "c1").await.unwrap();
+        let items = due(&pool, 10).await.unwrap();
+        assert_eq!(items.len(), 1);
+
+        // Edited again while being embedded (same second): the stale vector doesn't dequeue it
+        enqueue(&pool, VectorSource::Chunks, "c1").await.unwrap();
+        complete(&pool, &items).await.unwrap();
+        assert_eq!(status(&pool).await.unwrap().pending, 2);
+
+        let items = due(&pool, 10).await.unwrap();
+        complete(&pool, &items).await.unwrap();
+        assert_eq!(status(&pool).await.unwrap().pending, 1);
+    }
+}
@@ -4,7 +4,7 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Query, State,
     },
     http::{header, StatusCode},
     response::{IntoResponse, Response},
@@ -79,6 +79,9 @@ impl HttpServer {
             .route("/api/council/generate_question", post(generate_question))
             .route("/api/council/session", post(council_session_get))
             .route("/api/council/sessions", get(council_sessions_list))
+            // Knowledge namespaces
+            .route("/api/namespaces", get(namespaces_list).post(namespace_create))
+            .route("/api/knowledge/search", get(knowledge_search))
             // PoHV API
             .route("/api/pohv/status", get(pohv_status))
             // Agent API
@@ -223,7 +226,7 @@ async fn ollama_ask(
 }
 
 async fn config_get(State(state): State<Arc<AppState>>) -> Json<ApiResponse<crate::config::AppConfig>> {
-    Json(ApiResponse::success(state.get_config()))
+    Json(ApiResponse::success(state.get_config().redacted()))
 }
 
 #[derive(Deserialize)]
@@ -235,12 +238,21 @@ async fn config_save(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<ConfigSaveRequest>,
 ) -> Result<Json<ApiResponse<()>>, AppError> {
+    // Keys come back redacted; new plaintext keys go into the secret store when it is unlocked
+    let mut config = payload.config;
+    config.restore_redacted(&state.get_config());
+    let secrets = crate::secrets::global();
+    if secrets.is_unlocked() {
+        config.seal_api_keys(secrets).map_err(AppError)?;
+    }
+
     state.update_config(|c| {
-        *c = payload.config.clone();
+        *c = config.clone();
     });
+    state.namespaces.configure(&config.namespaces);
     
     // Persist to disk
-    payload.config.save().map_err(AppError)?;
+    config.save().map_err(AppError)?;
     
     state.log_info("http_server", "Configuration saved via HTTP API");
     Ok(Json(ApiResponse::success(())))
@@ -308,13 +320,77 @@ struct CouncilSessionsListResponse {
     sessions: Vec<crate::protocol::CouncilSession>,
 }
 
+#[derive(Deserialize)]
+struct NamespaceQuery {
+    namespace: Option<String>,
+}
+
 async fn council_sessions_list(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<NamespaceQuery>,
 ) -> Json<ApiResponse<CouncilSessionsListResponse>> {
-    let sessions = state.council_manager.list_sessions().await;
+    let sessions = match query.namespace {
+        Some(namespace) => state.council_manager.list_sessions_in(Some(namespace.as_str())).await,
+        None => state.council_manager.list_sessions().await,
+    };
     Json(ApiResponse::success(CouncilSessionsListResponse { sessions }))
 }
 
+async fn namespaces_list(
+    State(state): State<Arc<AppState>>,
+) -> Json<ApiResponse<Vec<crate::namespaces::NamespaceInfo>>> {
+    Json(ApiResponse::success(state.namespaces.list()))
+}
+
+#[derive(Deserialize)]
+struct NamespaceCreateRequest {
+    name: String,
+}
+
+async fn namespace_create(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<NamespaceCreateRequest>,
+) -> Result<Json<ApiResponse<Vec<crate::namespaces::NamespaceInfo>>>, ApiError> {
+    state.log_info("http_server", &format!("Opening namespace: {}", payload.name));
+    state
+        .namespaces
+        .open(&payload.name)
+        .await
+        .map_err(ApiError::BadRequest)?;
+    Ok(Json(ApiResponse::success(state.namespaces.list())))
+}
+
+#[derive(Deserialize)]
+struct KnowledgeSearchQuery {
+    query: String,
+    limit: Option<usize>,
+    mode: Option<crate::knowledge::SearchMode>,
+    namespace: Option<String>,
+    #[serde(default, alias = "crossNamespace")]
+    cross_namespace: bool,
+}
+
+async fn knowledge_search(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<KnowledgeSearchQuery>,
+) -> Result<Json<ApiResponse<Vec<crate::knowledge::SearchResult>>>, ApiError> {
+    let limit = query.limit.unwrap_or(5);
+    let mode = query.mode.unwrap_or_default();
+    // Other councils' debates are only searched when explicitly asked for
+    let results = if query.cross_namespace {
+        state.namespaces.search_all(&query.query, limit, mode).await
+    } else {
+        let kb = state
+            .namespaces
+            .get(query.namespace.as_deref())
+            .map_err(ApiError::BadRequest)?;
+        kb.semantic_search(&query.query, limit, mode).await
+    };
+    results
+        .map(|results| Json(ApiResponse::success(results)))
+        .map_err(ApiError::InternalError)
+}
+
 async fn pohv_status(
     State(state): State<Arc<AppState>>,
 ) -> Json<ApiResponse<crate::pohv::PoHVState>> {
@@ -423,6 +499,8 @@ async fn agent_reset_identity(
 struct ChatMessagePayload {
     content: String,
     channel: Option<crate::chat::ChannelType>,
+    #[serde(default)]
+    attachments: Vec<crate::providers::Attachment>,
 }
 
 async fn chat_message_send(
@@ -441,6 +519,9 @@ async fn chat_message_send(
         signature: None,
         reply_to: None,
         reactions: vec![],
+        attachments: payload.attachments,
+        reasoning: None,
+        citations: Vec::new(),
     };
 
     // Add to channel manager
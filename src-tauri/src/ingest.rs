@@ -0,0 +1,269 @@
+// Document ingestion - Text extraction and heading-aware chunking for the Knowledge Bank
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Target chunk size in characters (roughly 300 tokens)
+pub const DEFAULT_CHUNK_CHARS: usize = 1200;
+
+/// File formats the ingester understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentFormat {
+    Markdown,
+    Text,
+    Html,
+    Pdf,
+}
+
+impl DocumentFormat {
+    /// Detect the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "md" | "markdown" => Some(DocumentFormat::Markdown),
+            "txt" | "text" => Some(DocumentFormat::Text),
+            "html" | "htm" => Some(DocumentFormat::Html),
+            "pdf" => Some(DocumentFormat::Pdf),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentFormat::Markdown => "markdown",
+            DocumentFormat::Text => "text",
+            DocumentFormat::Html => "html",
+            DocumentFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Plain text of a document; HTML comes back as markdown so headings survive
+pub fn extract_text(format: DocumentFormat, bytes: &[u8]) -> Result<String, String> {
+    match format {
+        DocumentFormat::Markdown | DocumentFormat::Text => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        DocumentFormat::Html => {
+            html2text::from_read(bytes, 10_000).map_err(|e| format!("Failed to parse HTML: {}", e))
+        }
+        // pdf-extract panics on some malformed files
+        DocumentFormat::Pdf => std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(bytes))
+            .map_err(|_| "PDF parser crashed on this file".to_string())?
+            .map_err(|e| format!("Failed to extract PDF text: {}", e)),
+    }
+}
+
+/// A piece of a document with the heading path it sits under
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentChunk {
+    /// e.g. "Deployment > Rollback"
+    pub heading: Option<String>,
+    pub text: String,
+}
+
+/// Split text into chunks of at most `max_chars`, never across a markdown
+/// heading; paragraphs are packed greedily and oversized ones split at
+/// sentence, then word boundaries
+pub fn chunk_document(text: &str, max_chars: usize) -> Vec<DocumentChunk> {
+    let max_chars = max_chars.max(100);
+    let mut chunks = Vec::new();
+    let mut headings: Vec<(usize, String)> = Vec::new();
+    let mut section = String::new();
+    let mut in_fence = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        match (!in_fence).then(|| parse_heading(line)).flatten() {
+            Some((level, title)) => {
+                pack_section(&section, &headings, max_chars, &mut chunks);
+                section.clear();
+                headings.retain(|(l, _)| *l < level);
+                headings.push((level, title));
+            }
+            None => {
+                section.push_str(line);
+                section.push('\n');
+            }
+        }
+    }
+    pack_section(&section, &headings, max_chars, &mut chunks);
+    chunks
+}
+
+/// `## Title` -> (2, "Title")
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    let title = trimmed[level..].strip_prefix(' ')?.trim().trim_end_matches('#').trim();
+    ((1..=6).contains(&level) && !title.is_empty()).then(|| (level, title.to_string()))
+}
+
+fn pack_section(section: &str, headings: &[(usize, String)], max_chars: usize, out: &mut Vec<DocumentChunk>) {
+    let heading = (!headings.is_empty()).then(|| {
+        headings.iter().map(|(_, t)| t.as_str()).collect::<Vec<_>>().join(" > ")
+    });
+    let mut current = String::new();
+    let mut flush = |current: &mut String| {
+        if !current.trim().is_empty() {
+            out.push(DocumentChunk {
+                heading: heading.clone(),
+                text: current.trim().to_string(),
+            });
+        }
+        current.clear();
+    };
+
+    for paragraph in section.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        for piece in split_oversized(paragraph, max_chars) {
+            if char_len(&current) + char_len(&piece) + 2 > max_chars {
+                flush(&mut current);
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&piece);
+        }
+    }
+    flush(&mut current);
+}
+
+/// Break a paragraph longer than `max_chars` at sentence ends, then at spaces
+fn split_oversized(paragraph: &str, max_chars: usize) -> Vec<String> {
+    if char_len(paragraph) <= max_chars {
+        return vec![paragraph.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for word in paragraph.split_whitespace() {
+        let sentence_end = current.ends_with(['.', '!', '?']);
+        let len = char_len(&current);
+        // Prefer to cut after a sentence once the piece is reasonably full
+        if !current.is_empty() && (len + 1 + char_len(word) > max_chars || (sentence_end && len > max_chars * 3 / 4)) {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        // A single "word" longer than a chunk (base64, URLs...) is cut hard
+        let mut word = word;
+        while char_len(word) > max_chars {
+            let cut = word.char_indices().nth(max_chars).map(|(i, _)| i).unwrap_or(word.len());
+            pieces.push(word[..cut].to_string());
+            word = &word[cut..];
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Title from the first heading, falling back to the file name
+pub fn document_title(text: &str, path: &Path) -> String {
+    text.lines()
+        .find_map(parse_heading)
+        .map(|(_, title)| title)
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string_lossy().into_owned())
+        })
+}
+
+/// Supported files under `path` (the file itself, or a recursive directory walk
+/// skipping hidden entries), sorted
+pub fn collect_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    if metadata.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| format!("Cannot list {}: {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if entry_path.is_dir() {
+                pending.push(entry_path);
+            } else if DocumentFormat::from_path(&entry_path).is_some() {
+                files.push(entry_path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Outcome of ingesting a file or directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestReport {
+    /// Files parsed, chunked and embedded
+    pub ingested: Vec<String>,
+    /// Files whose content hash matched the stored one
+    pub unchanged: Vec<String>,
+    /// (path, error)
+    pub failed: Vec<(String, String)>,
+    pub chunks: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_document_follows_headings() {
+        let text = "# Runbook\nIntro line.\n\n## Rollback\nStep one.\n\n```\n# not a heading\n```\n\n### Database\nRestore the snapshot.\n\n## Contacts\nOn-call.";
+        let chunks = chunk_document(text, 1000);
+        let headings: Vec<Option<&str>> = chunks.iter().map(|c| c.heading.as_deref()).collect();
+        assert_eq!(
+            headings,
+            vec![
+                Some("Runbook"),
+                Some("Runbook > Rollback"),
+                Some("Runbook > Rollback > Database"),
+                Some("Runbook > Contacts"),
+            ]
+        );
+        assert!(chunks[1].text.contains("# not a heading"));
+        assert_eq!(document_title(text, Path::new("x.md")), "Runbook");
+        assert_eq!(document_title("no headings", Path::new("/a/notes.txt")), "notes");
+    }
+
+    #[test]
+    fn test_chunk_document_respects_size() {
+        let sentence = "The council weighs every option carefully. ";
+        let paragraph = sentence.repeat(40);
+        let text = format!("{}\n\nShort tail.\n\n{}", paragraph, "x".repeat(350));
+        let chunks = chunk_document(&text, 300);
+
+        assert!(chunks.len() > 5);
+        assert!(chunks.iter().all(|c| c.text.chars().count() <= 300));
+        assert!(chunks.iter().all(|c| c.heading.is_none()));
+        // Long paragraphs are cut after a sentence
+        assert!(chunks[0].text.ends_with('.'));
+        let total: usize = chunks.iter().map(|c| c.text.matches("council").count()).sum();
+        assert_eq!(total, 40);
+    }
+
+    #[test]
+    fn test_extract_html_keeps_headings() {
+        let html = b"<html><body><h2>Policy</h2><p>Budget <b>BL-9</b> approved.</p></body></html>";
+        let text = extract_text(DocumentFormat::Html, html).unwrap();
+        assert!(text.contains("## Policy"));
+        assert!(text.contains("BL-9"));
+        assert_eq!(DocumentFormat::from_path(Path::new("a/B.HTM")), Some(DocumentFormat::Html));
+        assert_eq!(DocumentFormat::from_path(Path::new("a/b.docx")), None);
+    }
+}
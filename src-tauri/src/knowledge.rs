@@ -1,10 +1,29 @@
+use crate::bundle::{self, ExportReport, ImportReport};
+use crate::config::RetentionPolicy;
+use crate::citations::{self, Citation};
+use crate::contradiction::{PastVerdict, VerdictLink, VerdictRelation};
+use crate::crypto::SigningIdentity;
 use crate::deliberation::{DeliberationResult, DeliberationRound, MemberResponse};
+use crate::embedding_queue::{self, QueueStatus, QueuedItem};
+use crate::ingest::{self, DocumentFormat, IngestReport};
 use crate::logger::{LogLevel, Logger};
+use crate::providers::AIProvider;
 use crate::protocol::{CouncilSession, CouncilResponse, SessionStatus};
 use crate::reputation::{AgentReputation, AgentTier, ReputationScore};
+use crate::lexical_index::{self, LexicalSource};
+use crate::ledger::{self, Checkpoint, InclusionProof, LedgerVerification};
+use crate::reembed::{self, ReembedJob};
+use crate::retention::{self, Initiator, RetentionEvent, RetentionKind};
+use crate::schema;
+use crate::vector_index::{self, VectorIndex, VectorSource};
 use serde::{Deserialize, Serialize};
-use sqlx::{sqlite::SqlitePool, Row};
+use sha2::{Digest, Sha256};
+use sqlx::{sqlite::{SqliteConnection, SqlitePool}, Row};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
 
 /// Vector embedding for semantic search
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +31,8 @@ use std::sync::Arc;
 pub struct Embedding {
     pub vector: Vec<f32>,
     pub dimension: usize,
+    /// Embedding model that produced the vector (vectors from different models are not comparable)
+    pub model: String,
 }
 
 /// Chunk of text with embedding for RAG
@@ -31,6 +52,8 @@ pub enum ChunkType {
     Question,
     Response { round: usize, member: String },
     Consensus,
+    /// Ingested reference material
+    Document { source: String, heading: Option<String> },
 }
 
 /// Search result with similarity score
@@ -40,6 +63,56 @@ pub struct SearchResult {
     pub question: String,
     pub relevance_score: f32,
     pub text_snippet: String,
+    /// Set when the hit is an ingested document rather than a past deliberation
+    /// (`deliberation_id` is then the document id and `question` its title)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document: Option<DocumentCitation>,
+    /// Namespace the hit came from (set by cross-namespace searches)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+/// Where a document hit came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentCitation {
+    pub source_path: String,
+    pub heading: Option<String>,
+}
+
+/// An ingested document as tracked in the `documents` table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentInfo {
+    pub id: String,
+    pub source_path: String,
+    pub title: String,
+    pub format: String,
+    pub content_hash: String,
+    pub chunk_count: i64,
+    pub ingested_at: i64,
+}
+
+/// How `semantic_search` ranks results
+///
+/// Scores are only comparable within a mode: cosine similarity for `Semantic`,
+/// reciprocal-rank-fusion scores for `Lexical` and `Hybrid`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Embedding similarity only
+    Semantic,
+    /// FTS5 BM25 only (works without an embedding endpoint)
+    Lexical,
+    /// BM25 and vector rankings fused; lexical only when embeddings are unavailable
+    #[default]
+    Hybrid,
+}
+
+/// Something a search can return
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SearchHit {
+    Chunk(String),
+    Deliberation(String),
+    Document(String),
 }
 
 /// RAG context for deliberation
@@ -47,207 +120,250 @@ pub struct SearchResult {
 pub struct RAGContext {
     pub relevant_decisions: Vec<SearchResult>,
     pub context_text: String,
+    /// Sources labelled in `context_text`, one per record, for checking what a reply cites
+    pub sources: Vec<Citation>,
 }
 
 /// Knowledge Bank with RAG capabilities
 pub struct KnowledgeBank {
     pool: SqlitePool,
     logger: Arc<Logger>,
-    ollama_url: String,
-    ollama_auth: Option<(String, String)>,
-    embedding_model: String,
+    embedder: Arc<dyn AIProvider>,
+    fallback_embedder: Option<Arc<dyn AIProvider>>,
+    /// Embeds search queries (same model as `embedder`, scheduled ahead of background work)
+    query_embedder: Option<Arc<dyn AIProvider>>,
+    vectors: VectorIndex,
+    /// Set while a re-embedding job runs
+    reembedding: AtomicBool,
+    /// Wakes the embedding worker when something is queued
+    queue_signal: Notify,
+    /// Signs verdict ledger checkpoints
+    signing_identity: Option<Arc<SigningIdentity>>,
+    /// Serialises ledger appends (each links to the previous head)
+    ledger_lock: Mutex<()>,
 }
 
 impl KnowledgeBank {
-    /// Initialize knowledge bank with database
+    /// Initialize knowledge bank with database and embedding provider
     pub async fn new(
         db_path: &str,
         logger: Arc<Logger>,
-        ollama_url: String,
-        ollama_auth: Option<(String, String)>,
+        embedder: Arc<dyn AIProvider>,
     ) -> Result<Self, String> {
         logger.log(
             LogLevel::Info,
             "knowledge",
-            &format!("🧠 Initializing Knowledge Bank at {}", db_path),
+            &format!(
+                "🧠 Initializing Knowledge Bank at {} (embeddings: {}/{})",
+                db_path,
+                embedder.name(),
+                embedder.embedding_model()
+            ),
         );
 
+        vector_index::register_extension();
         let pool = SqlitePool::connect(db_path)
             .await
             .map_err(|e| format!("Failed to connect to database: {}", e))?;
 
         let kb = Self {
+            vectors: VectorIndex::open(pool.clone(), &logger).await,
             pool,
             logger: logger.clone(),
-            ollama_url,
-            ollama_auth,
-            embedding_model: "nomic-embed-text".to_string(),
+            embedder,
+            fallback_embedder: None,
+            query_embedder: None,
+            reembedding: AtomicBool::new(false),
+            queue_signal: Notify::new(),
+            signing_identity: None,
+            ledger_lock: Mutex::new(()),
         };
 
         kb.initialize_schema().await?;
-        logger.log(LogLevel::Success, "knowledge", "✅ Knowledge Bank ready");
+        kb.migrate_vectors().await?;
+        kb.record_missing_verdicts().await?;
+        logger.log(
+            LogLevel::Success,
+            "knowledge",
+            &format!(
+                "✅ Knowledge Bank ready (vector search: {})",
+                if kb.vectors.uses_knn() { "vec0 KNN" } else { "full scan" }
+            ),
+        );
 
         Ok(kb)
     }
 
-    /// Create database schema with vector support
-    async fn initialize_schema(&self) -> Result<(), String> {
-        self.logger
-            .log(LogLevel::Debug, "knowledge", "📊 Creating database schema");
+    /// Embedder used when the primary one fails (e.g. local vectoriser when Ollama is down)
+    pub fn with_fallback_embedder(mut self, embedder: Arc<dyn AIProvider>) -> Self {
+        self.fallback_embedder = Some(embedder);
+        self
+    }
 
-        // Main deliberations table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS deliberations (
-                id TEXT PRIMARY KEY,
-                question TEXT NOT NULL,
-                consensus TEXT,
-                created_at INTEGER NOT NULL,
-                completed BOOLEAN NOT NULL
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create deliberations table: {}", e))?;
+    /// Embedder for search queries; the queue worker and re-embedding keep using the primary one
+    pub fn with_query_embedder(mut self, embedder: Arc<dyn AIProvider>) -> Self {
+        self.query_embedder = Some(embedder);
+        self
+    }
 
-        // Topics history table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS topics (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                topic TEXT NOT NULL,
-                created_at INTEGER NOT NULL,
-                created_by TEXT
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create topics table: {}", e))?;
+    /// Node key that signs verdict ledger checkpoints
+    pub fn with_signing_identity(mut self, identity: Arc<SigningIdentity>) -> Self {
+        self.signing_identity = Some(identity);
+        self
+    }
 
-        // Rounds table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS rounds (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                deliberation_id TEXT NOT NULL,
-                round_number INTEGER NOT NULL,
-                FOREIGN KEY (deliberation_id) REFERENCES deliberations(id)
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create rounds table: {}", e))?;
+    /// Apply pending schema migrations (see `schema.rs` and `src-tauri/migrations`)
+    async fn initialize_schema(&self) -> Result<(), String> {
+        let version = schema::migrate(&self.pool, &self.logger).await?;
+        self.logger.log(
+            LogLevel::Success,
+            "knowledge",
+            &format!("✅ Database schema at v{}", version),
+        );
+        Ok(())
+    }
 
-        // Responses table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS responses (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                round_id INTEGER NOT NULL,
-                member_name TEXT NOT NULL,
-                model TEXT NOT NULL,
-                response TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                FOREIGN KEY (round_id) REFERENCES rounds(id)
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create responses table: {}", e))?;
+    /// Copy embeddings stored before the `vec0` index existed into it
+    async fn migrate_vectors(&self) -> Result<(), String> {
+        for source in [VectorSource::Chunks, VectorSource::Chat, VectorSource::Documents] {
+            let added = self.vectors.migrate(source).await?;
+            if added > 0 {
+                self.logger.log(
+                    LogLevel::Info,
+                    "knowledge",
+                    &format!("📐 Indexed {} existing {:?} embeddings for KNN search", added, source),
+                );
+            }
+        }
+        Ok(())
+    }
 
-        // Text chunks with embeddings for RAG
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS text_chunks (
-                id TEXT PRIMARY KEY,
-                deliberation_id TEXT NOT NULL,
-                text TEXT NOT NULL,
-                chunk_type TEXT NOT NULL,
-                chunk_metadata TEXT,
-                FOREIGN KEY (deliberation_id) REFERENCES deliberations(id)
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create text_chunks table: {}", e))?;
+    /// Re-embed everything stored with a model other than the configured one,
+    /// resuming an interrupted job; returns the completed job, or None if all
+    /// vectors are current
+    ///
+    /// Only the primary embedder is used. New vectors are staged and swapped in
+    /// once every item is done; embedding failures are retried with backoff.
+    pub async fn reembed(&self) -> Result<Option<ReembedJob>, String> {
+        if self.reembedding.swap(true, Ordering::SeqCst) {
+            return Err("Re-embedding already in progress".to_string());
+        }
+        let result = self.run_reembedding().await;
+        self.reembedding.store(false, Ordering::SeqCst);
+        result
+    }
 
-        // Embeddings table (separate for flexibility)
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS embeddings (
-                chunk_id TEXT PRIMARY KEY,
-                embedding BLOB NOT NULL,
-                dimension INTEGER NOT NULL,
-                FOREIGN KEY (chunk_id) REFERENCES text_chunks(id)
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create embeddings table: {}", e))?;
+    async fn run_reembedding(&self) -> Result<Option<ReembedJob>, String> {
+        const RETRY_MIN: Duration = Duration::from_secs(5);
+        const RETRY_MAX: Duration = Duration::from_secs(300);
+
+        let target = self.embedder.embedding_model().to_string();
+        let mut job = match reembed::running_job(&self.pool).await? {
+            Some(job) if job.target_model == target => {
+                self.logger.log(
+                    LogLevel::Info,
+                    "knowledge",
+                    &format!("🔁 Resuming re-embedding with {} at {}/{}", target, job.done, job.total),
+                );
+                job
+            }
+            stale => {
+                if let Some(stale) = stale {
+                    reembed::cancel_job(&self.pool, &stale.id).await?;
+                    self.logger.log(
+                        LogLevel::Info,
+                        "knowledge",
+                        &format!("🔁 Cancelled re-embedding to {} (model is now {})", stale.target_model, target),
+                    );
+                }
+                let total = reembed::pending_count(&self.pool, None, &target).await?;
+                if total == 0 {
+                    return Ok(None);
+                }
+                self.logger.log(
+                    LogLevel::Info,
+                    "knowledge",
+                    &format!("🔁 Re-embedding {} stored vectors with {}", total, target),
+                );
+                reembed::start_job(&self.pool, &target, total).await?
+            }
+        };
 
-        // Reputation table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS reputation (
-                agent_id TEXT PRIMARY KEY,
-                tier TEXT NOT NULL,
-                accuracy REAL NOT NULL,
-                reasoning REAL NOT NULL,
-                contribution REAL NOT NULL,
-                total_votes INTEGER NOT NULL,
-                successful_consensus INTEGER NOT NULL,
-                last_updated INTEGER NOT NULL
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create reputation table: {}", e))?;
+        let mut retry_delay = RETRY_MIN;
+        loop {
+            let items = reembed::pending_items(&self.pool, &job, reembed::BATCH_SIZE).await?;
+            if items.is_empty() {
+                break;
+            }
 
-        // Chat logs table for per-channel RAG/Context
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS chat_logs (
-                id TEXT PRIMARY KEY,
-                channel TEXT NOT NULL,
-                author TEXT NOT NULL,
-                author_type TEXT NOT NULL,
-                content TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                signature TEXT,
-                reply_to TEXT
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create chat_logs table: {}", e))?;
+            let mut error = None;
+            for item in &items {
+                match Self::embed_with(self.embedder.as_ref(), &item.text).await {
+                    Ok(embedding) => reembed::stage(&self.pool, &job.id, item, &embedding).await?,
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                }
+            }
+            job = reembed::record_progress(&self.pool, &job, error.as_deref()).await?;
+
+            match error {
+                Some(e) => {
+                    self.logger.log(
+                        LogLevel::Warning,
+                        "knowledge",
+                        &format!("⚠️ Re-embedding paused ({}), retrying in {}s", e, retry_delay.as_secs()),
+                    );
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = (retry_delay * 2).min(RETRY_MAX);
+                }
+                None => {
+                    retry_delay = RETRY_MIN;
+                    self.logger.log(
+                        LogLevel::Info,
+                        "knowledge",
+                        &format!(
+                            "🔁 Re-embedding {}/{} ({}%)",
+                            job.done,
+                            job.total,
+                            job.done * 100 / job.total.max(1)
+                        ),
+                    );
+                }
+            }
+        }
 
-        // Chat embeddings table (future use for semantic search)
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS chat_embeddings (
-                message_id TEXT PRIMARY KEY,
-                embedding BLOB NOT NULL,
-                dimension INTEGER NOT NULL,
-                FOREIGN KEY (message_id) REFERENCES chat_logs(id)
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to create chat_embeddings table: {}", e))?;
+        let replaced = reembed::switch_over(&self.pool, &job).await?;
+        for source in reembed::SOURCES {
+            self.vectors.rebuild(source).await?;
+        }
+        self.logger.log(
+            LogLevel::Success,
+            "knowledge",
+            &format!("✅ Switched {} vectors to {}", replaced, target),
+        );
+        reembed::latest_job(&self.pool).await
+    }
+
+    /// Run `reembed` in the background (no-op if a job is already running)
+    pub fn spawn_reembedding(self: &Arc<Self>) {
+        let kb = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = kb.reembed().await {
+                kb.logger.log(LogLevel::Error, "knowledge", &format!("❌ Re-embedding failed: {}", e));
+            }
+        });
+    }
 
-        self.logger
-            .log(LogLevel::Success, "knowledge", "✅ Database schema initialized");
+    /// Whether a re-embedding job is running in this process
+    pub fn is_reembedding(&self) -> bool {
+        self.reembedding.load(Ordering::SeqCst)
+    }
 
-        Ok(())
+    /// Latest re-embedding job, if one was ever started
+    pub async fn reembed_status(&self) -> Result<Option<ReembedJob>, String> {
+        reembed::latest_job(&self.pool).await
     }
 
     /// Store deliberation result with full RAG processing
@@ -283,14 +399,18 @@ impl KnowledgeBank {
             }
         }
 
-        // Generate and store embeddings for RAG
-        self.generate_embeddings(result).await?;
+        // Store chunks for RAG (embedded by the queue worker)
+        self.store_chunks(result).await?;
+
+        if let (true, Some(consensus)) = (result.completed, &result.consensus) {
+            self.record_verdict(&result.session_id, &result.question, consensus).await?;
+        }
 
         self.logger.log(
             LogLevel::Success,
             "knowledge",
             &format!(
-                "✅ Stored deliberation with embeddings: {}",
+                "✅ Stored deliberation: {}",
                 result.session_id
             ),
         );
@@ -323,8 +443,8 @@ impl KnowledgeBank {
     async fn store_response(&self, round_id: i64, response: &MemberResponse) -> Result<(), String> {
         sqlx::query(
             r#"
-            INSERT INTO responses (round_id, member_name, model, response, timestamp)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO responses (round_id, member_name, model, response, timestamp, reasoning)
+            VALUES (?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(round_id)
@@ -332,6 +452,7 @@ impl KnowledgeBank {
         .bind(&response.model)
         .bind(&response.response)
         .bind(response.timestamp as i64)
+        .bind(&response.reasoning)
         .execute(&self.pool)
         .await
         .map_err(|e| format!("Failed to store response: {}", e))?;
@@ -339,13 +460,13 @@ impl KnowledgeBank {
         Ok(())
     }
 
-    /// Generate embeddings for all chunks of a deliberation
-    async fn generate_embeddings(&self, result: &DeliberationResult) -> Result<(), String> {
+    /// Store all chunks of a deliberation and queue them for embedding
+    async fn store_chunks(&self, result: &DeliberationResult) -> Result<(), String> {
         self.logger.log(
             LogLevel::Debug,
             "knowledge",
             &format!(
-                "🔢 Generating embeddings for deliberation: {}",
+                "🔢 Chunking deliberation: {}",
                 result.session_id
             ),
         );
@@ -381,7 +502,6 @@ impl KnowledgeBank {
             ));
         }
 
-        // Store chunks and generate embeddings in parallel
         let total_chunks = chunks.len();
         for (chunk_id, text, metadata) in &chunks {
             // Store text chunk
@@ -400,98 +520,251 @@ impl KnowledgeBank {
             .await
             .map_err(|e| format!("Failed to store chunk: {}", e))?;
 
-            // Generate embedding via Ollama
-            let embedding = self.generate_embedding(text).await?;
-
-            // Store embedding as BLOB
-            let embedding_bytes = Self::serialize_embedding(&embedding);
-            sqlx::query(
-                r#"
-                INSERT INTO embeddings (chunk_id, embedding, dimension)
-                VALUES (?, ?, ?)
-                "#,
-            )
-            .bind(chunk_id)
-            .bind(&embedding_bytes)
-            .bind(embedding.len() as i64)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| format!("Failed to store embedding: {}", e))?;
+            self.queue_embedding(VectorSource::Chunks, chunk_id).await?;
         }
 
         self.logger.log(
-            LogLevel::Success,
+            LogLevel::Debug,
             "knowledge",
-            &format!("✅ Generated {} embeddings", total_chunks),
+            &format!("📥 Queued {} chunks for embedding", total_chunks),
         );
 
         Ok(())
     }
 
-    /// Generate embedding using Ollama
-    async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>, String> {
-        // Use the existing Ollama client
-        // Note: This assumes the embedding model is pulled and available
-        let client = reqwest::Client::new();
-        let url = format!("{}/api/embeddings", self.ollama_url);
-        
-        let payload = serde_json::json!({
-            "model": self.embedding_model,
-            "prompt": text
-        });
+    /// Embed a search query via the query embedder, else the primary one (falls back if configured)
+    async fn generate_embedding(&self, text: &str) -> Result<Embedding, String> {
+        let embedder = self.query_embedder.as_ref().unwrap_or(&self.embedder);
+        match Self::embed_with(embedder.as_ref(), text).await {
+            Ok(embedding) => Ok(embedding),
+            Err(e) => match &self.fallback_embedder {
+                Some(fallback) => {
+                    self.logger.log(
+                        LogLevel::Warning,
+                        "knowledge",
+                        &format!(
+                            "⚠️ {} embeddings unavailable ({}), using {}",
+                            self.embedder.name(),
+                            e,
+                            fallback.name()
+                        ),
+                    );
+                    Self::embed_with(fallback.as_ref(), text).await
+                }
+                None => Err(e),
+            },
+        }
+    }
 
-        let mut request = client.post(&url).json(&payload);
-        
-        // Add basic auth if configured
-        if let Some((username, password)) = &self.ollama_auth {
-            request = request.basic_auth(username, Some(password));
+    async fn embed_with(provider: &dyn AIProvider, text: &str) -> Result<Embedding, String> {
+        let vector = provider.embed(text).await.map_err(|e| e.to_string())?;
+        if vector.is_empty() {
+            return Err(format!("{} returned an empty embedding", provider.name()));
         }
 
-        let res = request
-            .send()
-            .await
-            .map_err(|e| format!("Failed to call Ollama: {}", e))?;
+        Ok(Embedding {
+            dimension: vector.len(),
+            vector,
+            model: provider.embedding_model().to_string(),
+        })
+    }
 
-        if !res.status().is_success() {
-            return Err(format!("Ollama error: {}", res.status()));
+    /// Batch counterpart of `generate_embedding`
+    async fn generate_embedding_batch(&self, texts: &[String]) -> Result<Vec<Embedding>, String> {
+        match Self::embed_batch_with(self.embedder.as_ref(), texts).await {
+            Ok(embeddings) => Ok(embeddings),
+            Err(e) => match &self.fallback_embedder {
+                Some(fallback) => {
+                    self.logger.log(
+                        LogLevel::Warning,
+                        "knowledge",
+                        &format!(
+                            "⚠️ {} embeddings unavailable ({}), using {}",
+                            self.embedder.name(),
+                            e,
+                            fallback.name()
+                        ),
+                    );
+                    Self::embed_batch_with(fallback.as_ref(), texts).await
+                }
+                None => Err(e),
+            },
         }
+    }
 
-        let body: serde_json::Value = res
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+    async fn embed_batch_with(provider: &dyn AIProvider, texts: &[String]) -> Result<Vec<Embedding>, String> {
+        let vectors = provider.embed_batch(texts).await.map_err(|e| e.to_string())?;
+        if vectors.len() != texts.len() || vectors.iter().any(|v| v.is_empty()) {
+            return Err(format!("{} returned incomplete embeddings", provider.name()));
+        }
 
-        let embedding = body["embedding"]
-            .as_array()
-            .ok_or("Invalid embedding format")?
-            .iter()
-            .map(|v| v.as_f64().unwrap() as f32)
-            .collect();
+        Ok(vectors
+            .into_iter()
+            .map(|vector| Embedding {
+                dimension: vector.len(),
+                vector,
+                model: provider.embedding_model().to_string(),
+            })
+            .collect())
+    }
 
-        Ok(embedding)
+    /// Queue an item for the embedding worker
+    async fn queue_embedding(&self, source: VectorSource, id: &str) -> Result<(), String> {
+        embedding_queue::enqueue(&self.pool, source, id).await?;
+        self.queue_signal.notify_one();
+        Ok(())
+    }
+
+    /// Embed every due queue item, batch by batch; returns how many were embedded
+    ///
+    /// A failed batch is rescheduled with backoff and ends this pass.
+    pub async fn process_embedding_queue(&self) -> Result<usize, String> {
+        let mut embedded = 0;
+        loop {
+            let items = embedding_queue::due(&self.pool, embedding_queue::BATCH_SIZE).await?;
+            if items.is_empty() {
+                break;
+            }
+
+            let texts: Vec<String> = items.iter().map(|item| item.text.clone()).collect();
+            match self.generate_embedding_batch(&texts).await {
+                Ok(embeddings) => {
+                    for (item, embedding) in items.iter().zip(&embeddings) {
+                        self.store_queued_embedding(item, embedding).await?;
+                    }
+                    embedding_queue::complete(&self.pool, &items).await?;
+                    embedded += items.len();
+                }
+                Err(e) => {
+                    embedding_queue::fail(&self.pool, &items, &e).await?;
+                    self.logger.log(
+                        LogLevel::Warning,
+                        "knowledge",
+                        &format!("⚠️ Embedding {} queued items failed, will retry: {}", items.len(), e),
+                    );
+                    break;
+                }
+            }
+        }
+
+        if embedded > 0 {
+            self.logger.log(
+                LogLevel::Debug,
+                "knowledge",
+                &format!("✅ Generated {} embeddings", embedded),
+            );
+        }
+        Ok(embedded)
+    }
+
+    async fn store_queued_embedding(&self, item: &QueuedItem, embedding: &Embedding) -> Result<(), String> {
+        match item.source {
+            VectorSource::Chat => self.store_chat_embedding(&item.id, &item.scope, embedding).await,
+            _ => self.store_embedding(&item.id, embedding).await,
+        }
+    }
+
+    /// Drain the embedding queue in the background: on every enqueue, and when
+    /// the next retry falls due
+    pub fn start_embedding_worker(self: &Arc<Self>) {
+        const IDLE_POLL: Duration = Duration::from_secs(60);
+
+        let kb = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = kb.process_embedding_queue().await {
+                    kb.logger.log(LogLevel::Error, "knowledge", &format!("❌ Embedding queue: {}", e));
+                }
+
+                let wait = match embedding_queue::status(&kb.pool).await {
+                    Ok(QueueStatus { next_attempt_at: Some(at), .. }) => {
+                        let secs = (at - chrono::Utc::now().timestamp()).clamp(1, IDLE_POLL.as_secs() as i64);
+                        Duration::from_secs(secs as u64)
+                    }
+                    _ => IDLE_POLL,
+                };
+                tokio::select! {
+                    _ = kb.queue_signal.notified() => {}
+                    _ = tokio::time::sleep(wait) => {}
+                }
+            }
+        });
+    }
+
+    /// Items still awaiting an embedding
+    pub async fn embedding_queue_status(&self) -> Result<QueueStatus, String> {
+        embedding_queue::status(&self.pool).await
     }
 
     /// Store embedding in DB
-    async fn store_embedding(&self, chunk_id: &str, embedding: &[f32]) -> Result<(), String> {
-        let embedding_bytes = Self::serialize_embedding(embedding);
+    async fn store_embedding(&self, chunk_id: &str, embedding: &Embedding) -> Result<(), String> {
+        let embedding_bytes = Self::serialize_embedding(&embedding.vector);
         
         sqlx::query(
             r#"
-            INSERT OR REPLACE INTO embeddings (chunk_id, embedding, dimension)
-            VALUES (?, ?, ?)
+            INSERT OR REPLACE INTO embeddings (chunk_id, embedding, dimension, model)
+            VALUES (?, ?, ?, ?)
             "#,
         )
         .bind(chunk_id)
         .bind(embedding_bytes)
-        .bind(embedding.len() as i64)
+        .bind(embedding.dimension as i64)
+        .bind(&embedding.model)
         .execute(&self.pool)
         .await
         .map_err(|e| format!("Failed to store embedding: {}", e))?;
 
+        self.vectors.upsert(VectorSource::Chunks, chunk_id, "", embedding).await
+    }
+
+    /// Refuse to compare a query vector against rows from a different model/dimension
+    ///
+    /// Incompatible rows are skipped with a warning; if nothing compatible is left
+    /// while incompatible rows exist, the search is refused.
+    async fn check_embedding_compatibility(&self, table: &str, query: &Embedding) -> Result<(), String> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT
+                SUM(CASE WHEN dimension = ? AND model = ? THEN 1 ELSE 0 END) AS compatible,
+                SUM(CASE WHEN dimension = ? AND model = ? THEN 0 ELSE 1 END) AS incompatible
+            FROM {}
+            "#,
+            table
+        ))
+        .bind(query.dimension as i64)
+        .bind(&query.model)
+        .bind(query.dimension as i64)
+        .bind(&query.model)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to check {} compatibility: {}", table, e))?;
+
+        let compatible: i64 = row.get::<Option<i64>, _>("compatible").unwrap_or(0);
+        let incompatible: i64 = row.get::<Option<i64>, _>("incompatible").unwrap_or(0);
+
+        if incompatible == 0 {
+            return Ok(());
+        }
+
+        if compatible == 0 {
+            return Err(format!(
+                "Refusing search: all {} stored vectors in {} come from a different embedding model/dimension than {} ({} dims)",
+                incompatible, table, query.model, query.dimension
+            ));
+        }
+
+        self.logger.log(
+            LogLevel::Warning,
+            "knowledge",
+            &format!(
+                "⚠️ Skipping {} vectors in {} not made with {} ({} dims)",
+                incompatible, table, query.model, query.dimension
+            ),
+        );
         Ok(())
     }
 
-    fn serialize_embedding(embedding: &[f32]) -> Vec<u8> {
+    pub(crate) fn serialize_embedding(embedding: &[f32]) -> Vec<u8> {
         let mut bytes = Vec::with_capacity(embedding.len() * 4);
         for val in embedding {
             bytes.extend_from_slice(&val.to_le_bytes());
@@ -499,7 +772,7 @@ impl KnowledgeBank {
         bytes
     }
 
-    fn deserialize_embedding(bytes: &[u8]) -> Vec<f32> {
+    pub(crate) fn deserialize_embedding(bytes: &[u8]) -> Vec<f32> {
         bytes
             .chunks(4)
             .map(|chunk| {
@@ -510,7 +783,12 @@ impl KnowledgeBank {
             .collect()
     }
 
-    fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+        // Vectors from different embedders are not comparable
+        if a.len() != b.len() {
+            return 0.0;
+        }
+
         let dot_product: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
         let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
         let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
@@ -522,84 +800,731 @@ impl KnowledgeBank {
         }
     }
 
-    fn truncate(s: &str, max_chars: usize) -> String {
-        if s.len() <= max_chars {
-            s.to_string()
-        } else {
-            format!("{}...", &s[..max_chars])
+    fn truncate(s: &str, max_chars: usize) -> String {
+        if s.len() <= max_chars {
+            s.to_string()
+        } else {
+            format!("{}...", &s[..max_chars])
+        }
+    }
+
+    /// Search for relevant deliberations
+    pub async fn semantic_search(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: SearchMode,
+    ) -> Result<Vec<SearchResult>, String> {
+        self.logger.log(
+            LogLevel::Debug,
+            "knowledge",
+            &format!("🔍 Searching ({:?}) for: {}", mode, query),
+        );
+
+        // Until a re-embedding job switches over, stored vectors come from the old
+        // model and query vectors from the new one, so rank lexically in between
+        let mode = match reembed::running_job(&self.pool).await? {
+            Some(job) if mode != SearchMode::Lexical => {
+                self.logger.log(
+                    LogLevel::Debug,
+                    "knowledge",
+                    &format!("🔁 Re-embedding to {} in progress, searching lexically", job.target_model),
+                );
+                SearchMode::Lexical
+            }
+            _ => mode,
+        };
+
+        // Fusion needs deeper candidate lists than the final result count
+        let depth = if mode == SearchMode::Semantic { limit } else { (limit * 4).max(20) };
+
+        let semantic = match mode {
+            SearchMode::Lexical => None,
+            _ => match self.vector_ranking(query, depth, true).await {
+                Ok(ranking) => Some(ranking),
+                Err(e) if mode == SearchMode::Hybrid => {
+                    self.logger.log(
+                        LogLevel::Warning,
+                        "knowledge",
+                        &format!("⚠️ Semantic ranking unavailable ({}), searching lexically", e),
+                    );
+                    None
+                }
+                Err(e) => return Err(e),
+            },
+        };
+
+        let ranked: Vec<(SearchHit, f32)> = match (mode, semantic) {
+            (SearchMode::Semantic, Some(semantic)) => semantic,
+            (_, semantic) => {
+                let mut rankings: Vec<Vec<SearchHit>> = Vec::new();
+                if let Some(semantic) = semantic {
+                    rankings.push(semantic.into_iter().map(|(hit, _)| hit).collect());
+                }
+                let chunks = lexical_index::search(&self.pool, LexicalSource::Chunks, query, depth, None).await?;
+                rankings.push(chunks.into_iter().map(SearchHit::Chunk).collect());
+                let deliberations =
+                    lexical_index::search(&self.pool, LexicalSource::Deliberations, query, depth, None).await?;
+                rankings.push(deliberations.into_iter().map(SearchHit::Deliberation).collect());
+                let documents =
+                    lexical_index::search(&self.pool, LexicalSource::Documents, query, depth, None).await?;
+                rankings.push(documents.into_iter().map(SearchHit::Document).collect());
+                lexical_index::reciprocal_rank_fusion(&rankings)
+            }
+        };
+
+        let mut results = Vec::new();
+        for (hit, score) in ranked {
+            if results.len() >= limit {
+                break;
+            }
+            if let Some(result) = self.resolve_hit(&hit, score).await? {
+                results.push(result);
+            }
+        }
+
+        self.logger.log(
+            LogLevel::Success,
+            "knowledge",
+            &format!("✅ Found {} relevant results", results.len()),
+        );
+
+        Ok(results)
+    }
+
+    /// Semantic search over deliberation chunks only, closest first
+    ///
+    /// For callers that compare questions with past deliberations (duplicate
+    /// checks): ingested documents never take a slot in the ranking. Returns
+    /// nothing while a re-embedding job runs, as similarities aren't comparable then.
+    pub async fn search_deliberations(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        let mut results = Vec::new();
+        if reembed::running_job(&self.pool).await?.is_some() {
+            return Ok(results);
+        }
+        for (hit, score) in self.vector_ranking(query, limit, false).await? {
+            if let Some(result) = self.resolve_hit(&hit, score).await? {
+                results.push(result);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Deliberation (and optionally document) chunks nearest to `query` by embedding, with cosine similarity
+    async fn vector_ranking(
+        &self,
+        query: &str,
+        limit: usize,
+        include_documents: bool,
+    ) -> Result<Vec<(SearchHit, f32)>, String> {
+        let query_embedding = self.generate_embedding(query).await?;
+        self.check_embedding_compatibility("embeddings", &query_embedding).await?;
+
+        let mut ranking: Vec<(SearchHit, f32)> = self
+            .vectors
+            .nearest(VectorSource::Chunks, &query_embedding, limit, None)
+            .await?
+            .into_iter()
+            .map(|(id, similarity)| (SearchHit::Chunk(id), similarity))
+            .collect();
+        if include_documents {
+            self.check_embedding_compatibility("document_embeddings", &query_embedding).await?;
+            let documents = self
+                .vectors
+                .nearest(VectorSource::Documents, &query_embedding, limit, None)
+                .await?;
+            ranking.extend(documents.into_iter().map(|(id, similarity)| (SearchHit::Document(id), similarity)));
+        }
+
+        // Same model and dimension, so the similarities are comparable
+        ranking.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranking.truncate(limit);
+        Ok(ranking)
+    }
+
+    /// Load the text behind a search hit; `None` if it no longer exists
+    async fn resolve_hit(&self, hit: &SearchHit, score: f32) -> Result<Option<SearchResult>, String> {
+        let row = match hit {
+            SearchHit::Chunk(id) => sqlx::query(
+                r#"
+                SELECT t.deliberation_id, t.text AS snippet, d.question
+                FROM text_chunks t
+                JOIN deliberations d ON t.deliberation_id = d.id
+                WHERE t.id = ?
+                "#,
+            )
+            .bind(id),
+            SearchHit::Deliberation(id) => sqlx::query(
+                r#"
+                SELECT id AS deliberation_id, COALESCE(consensus, question) AS snippet, question
+                FROM deliberations
+                WHERE id = ?
+                "#,
+            )
+            .bind(id),
+            SearchHit::Document(id) => sqlx::query(
+                r#"
+                SELECT d.id AS deliberation_id, c.text AS snippet, d.title AS question,
+                       d.source_path, c.heading
+                FROM document_chunks c
+                JOIN documents d ON c.document_id = d.id
+                WHERE c.id = ?
+                "#,
+            )
+            .bind(id),
+        }
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to load search hit: {}", e))?;
+
+        Ok(row.map(|row| SearchResult {
+            deliberation_id: row.get("deliberation_id"),
+            question: row.get("question"),
+            relevance_score: score,
+            text_snippet: Self::truncate(&row.get::<String, _>("snippet"), 200),
+            document: matches!(hit, SearchHit::Document(_)).then(|| DocumentCitation {
+                source_path: row.get("source_path"),
+                heading: row.get("heading"),
+            }),
+            namespace: None,
+        }))
+    }
+
+    /// Earlier finalised verdicts on questions semantically close to `question`, closest first
+    pub async fn similar_verdicts(
+        &self,
+        deliberation_id: &str,
+        question: &str,
+        limit: usize,
+        min_similarity: f32,
+    ) -> Result<Vec<PastVerdict>, String> {
+        // Each deliberation has several chunks, so look deeper than `limit`;
+        // documents are left out of the ranking so they can't crowd verdicts out
+        let hits = self.search_deliberations(question, limit * 4).await?;
+
+        let mut verdicts: Vec<PastVerdict> = Vec::new();
+        for hit in hits {
+            if verdicts.len() >= limit {
+                break;
+            }
+            if hit.deliberation_id == deliberation_id
+                || hit.relevance_score < min_similarity
+                || verdicts.iter().any(|v| v.deliberation_id == hit.deliberation_id)
+            {
+                continue;
+            }
+            let row = sqlx::query(
+                "SELECT question, consensus FROM deliberations WHERE id = ? AND completed = 1 AND consensus IS NOT NULL",
+            )
+            .bind(&hit.deliberation_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to load verdict: {}", e))?;
+            if let Some(row) = row {
+                verdicts.push(PastVerdict {
+                    deliberation_id: hit.deliberation_id,
+                    question: row.get("question"),
+                    verdict: row.get("consensus"),
+                    similarity: hit.relevance_score,
+                });
+            }
+        }
+        Ok(verdicts)
+    }
+
+    /// Store (or re-judge) the relation between two verdicts
+    pub async fn store_verdict_link(&self, link: &VerdictLink) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO verdict_links (deliberation_id, related_id, relation, reason, similarity, judge, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&link.deliberation_id)
+        .bind(&link.related_id)
+        .bind(link.relation.as_str())
+        .bind(&link.reason)
+        .bind(link.similarity)
+        .bind(&link.judge)
+        .bind(link.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to store verdict link: {}", e))?;
+        Ok(())
+    }
+
+    /// Links from or to a deliberation's verdict, newest first
+    pub async fn verdict_links(&self, deliberation_id: &str) -> Result<Vec<VerdictLink>, String> {
+        let rows = sqlx::query(
+            "SELECT * FROM verdict_links WHERE deliberation_id = ? OR related_id = ? ORDER BY created_at DESC",
+        )
+        .bind(deliberation_id)
+        .bind(deliberation_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to load verdict links: {}", e))?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                Some(VerdictLink {
+                    deliberation_id: row.get("deliberation_id"),
+                    related_id: row.get("related_id"),
+                    relation: VerdictRelation::parse(&row.get::<String, _>("relation"))?,
+                    reason: row.get("reason"),
+                    similarity: row.get("similarity"),
+                    judge: row.get("judge"),
+                    created_at: row.get("created_at"),
+                })
+            })
+            .collect())
+    }
+
+    /// Build RAG context string from query
+    pub async fn build_rag_context(
+        &self,
+        query: &str,
+        limit: usize,
+        mode: SearchMode,
+    ) -> Result<RAGContext, String> {
+        let results = self.semantic_search(query, limit, mode).await?;
+        let (documents, decisions): (Vec<&SearchResult>, Vec<&SearchResult>) =
+            results.iter().partition(|r| r.document.is_some());
+
+        let mut sources: Vec<Citation> = Vec::new();
+        let mut label = |result: &SearchResult| {
+            let citation = Citation::from_result(result);
+            let id = citation.id.clone();
+            if !sources.iter().any(|s| s.id == id) {
+                sources.push(citation);
+            }
+            id
+        };
+
+        let mut context_text = String::from("### Relevant Past Decisions:\n\n");
+        for result in &decisions {
+            context_text.push_str(&format!("- [{}] **Question:** {}\n  **Snippet:** {}\n\n", 
+                label(result), result.question, result.text_snippet));
+        }
+        if !documents.is_empty() {
+            context_text.push_str("### Reference Documents:\n\n");
+            for result in &documents {
+                context_text.push_str(&format!(
+                    "- [{}] **Source:** {}\n  **Excerpt:** {}\n\n",
+                    label(result),
+                    Self::cite(result),
+                    result.text_snippet
+                ));
+            }
+        }
+        if !results.is_empty() {
+            context_text.push_str(citations::CITATION_INSTRUCTIONS);
+            context_text.push('\n');
+        }
+        
+        Ok(RAGContext {
+            relevant_decisions: results,
+            context_text,
+            sources,
+        })
+    }
+
+    /// "Title (path § heading)" for a document hit, the question otherwise
+    pub fn cite(result: &SearchResult) -> String {
+        match &result.document {
+            Some(doc) => match &doc.heading {
+                Some(heading) => format!("{} ({} § {})", result.question, doc.source_path, heading),
+                None => format!("{} ({})", result.question, doc.source_path),
+            },
+            None => result.question.clone(),
+        }
+    }
+
+    /// Ingest a file or every supported file under a directory
+    ///
+    /// Files whose content hash matches the stored one are skipped; changed
+    /// files have their old chunks replaced.
+    pub async fn ingest_path(&self, path: &Path) -> Result<IngestReport, String> {
+        let files = ingest::collect_files(path)?;
+        self.logger.log(
+            LogLevel::Info,
+            "knowledge",
+            &format!("📥 Ingesting {} file(s) from {}", files.len(), path.display()),
+        );
+
+        let mut report = IngestReport::default();
+        for file in files {
+            let display = file.to_string_lossy().into_owned();
+            match self.ingest_file(&file).await {
+                Ok(Some(chunks)) => {
+                    report.chunks += chunks;
+                    report.ingested.push(display);
+                }
+                Ok(None) => report.unchanged.push(display),
+                Err(e) => {
+                    self.logger.log(
+                        LogLevel::Warning,
+                        "knowledge",
+                        &format!("⚠️ Failed to ingest {}: {}", display, e),
+                    );
+                    report.failed.push((display, e));
+                }
+            }
+        }
+
+        self.logger.log(
+            LogLevel::Success,
+            "knowledge",
+            &format!(
+                "✅ Ingested {} file(s) ({} chunks), {} unchanged, {} failed",
+                report.ingested.len(),
+                report.chunks,
+                report.unchanged.len(),
+                report.failed.len()
+            ),
+        );
+        Ok(report)
+    }
+
+    /// Ingest one file; returns the chunk count, or `None` if it is unchanged
+    async fn ingest_file(&self, path: &Path) -> Result<Option<usize>, String> {
+        let format = DocumentFormat::from_path(path)
+            .ok_or_else(|| "Unsupported file type (expected .md, .txt, .html or .pdf)".to_string())?;
+        let source_path = std::fs::canonicalize(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .to_string_lossy()
+            .into_owned();
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let content_hash = format!("{:x}", Sha256::digest(&bytes));
+
+        let existing = sqlx::query("SELECT id, content_hash FROM documents WHERE source_path = ?")
+            .bind(&source_path)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to look up document: {}", e))?;
+        if let Some(row) = &existing {
+            if row.get::<String, _>("content_hash") == content_hash {
+                return Ok(None);
+            }
+        }
+
+        let text = ingest::extract_text(format, &bytes)?;
+        let title = ingest::document_title(&text, path);
+        let pieces = ingest::chunk_document(&text, ingest::DEFAULT_CHUNK_CHARS);
+        if pieces.is_empty() {
+            return Err("No text found".to_string());
+        }
+
+        let document_id = existing
+            .as_ref()
+            .map(|row| row.get::<String, _>("id"))
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        // Embed everything before touching the database so a failure leaves the old version intact
+        let mut embeddings = Vec::with_capacity(pieces.len());
+        for batch in pieces.chunks(embedding_queue::BATCH_SIZE) {
+            let texts: Vec<String> = batch.iter().map(|piece| piece.text.clone()).collect();
+            embeddings.extend(self.generate_embedding_batch(&texts).await?);
+        }
+        let mut chunks = Vec::with_capacity(pieces.len());
+        for (position, (piece, embedding)) in pieces.into_iter().zip(embeddings).enumerate() {
+            chunks.push(TextChunk {
+                id: format!("{}-{}", document_id, position),
+                deliberation_id: document_id.clone(),
+                text: piece.text,
+                chunk_type: ChunkType::Document {
+                    source: source_path.clone(),
+                    heading: piece.heading,
+                },
+                embedding: Some(embedding),
+            });
+        }
+
+        // Old chunks, the new ones and the hash go in together: a failure part-way
+        // must not leave the new hash over missing chunks (the next scan would skip the file)
+        let mut tx = self.pool.begin().await.map_err(|e| format!("Failed to begin ingest: {}", e))?;
+        let old_ids = if existing.is_some() {
+            Self::delete_document_chunks(&mut tx, &document_id).await?
+        } else {
+            Vec::new()
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO documents (id, source_path, title, format, content_hash, chunk_count, ingested_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(source_path) DO UPDATE SET
+                title = excluded.title,
+                format = excluded.format,
+                content_hash = excluded.content_hash,
+                chunk_count = excluded.chunk_count,
+                ingested_at = excluded.ingested_at
+            "#,
+        )
+        .bind(&document_id)
+        .bind(&source_path)
+        .bind(&title)
+        .bind(format.as_str())
+        .bind(&content_hash)
+        .bind(chunks.len() as i64)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to store document: {}", e))?;
+
+        for (position, chunk) in chunks.iter().enumerate() {
+            Self::store_document_chunk(&mut tx, chunk, position).await?;
+        }
+        tx.commit().await.map_err(|e| format!("Failed to commit ingest: {}", e))?;
+
+        // Chunk ids are positional, so drop the old entries before adding the new ones
+        for id in &old_ids {
+            self.vectors.remove(VectorSource::Documents, id).await?;
+        }
+        for chunk in &chunks {
+            if let Some(embedding) = &chunk.embedding {
+                self.vectors.upsert(VectorSource::Documents, &chunk.id, "", embedding).await?;
+            }
+        }
+
+        Ok(Some(chunks.len()))
+    }
+
+    async fn store_document_chunk(
+        conn: &mut SqliteConnection,
+        chunk: &TextChunk,
+        position: usize,
+    ) -> Result<(), String> {
+        let heading = match &chunk.chunk_type {
+            ChunkType::Document { heading, .. } => heading.clone(),
+            _ => None,
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO document_chunks (id, document_id, position, heading, text)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&chunk.id)
+        .bind(&chunk.deliberation_id)
+        .bind(position as i64)
+        .bind(&heading)
+        .bind(&chunk.text)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to store document chunk: {}", e))?;
+
+        if let Some(embedding) = &chunk.embedding {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO document_embeddings (chunk_id, embedding, dimension, model)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(&chunk.id)
+            .bind(Self::serialize_embedding(&embedding.vector))
+            .bind(embedding.dimension as i64)
+            .bind(&embedding.model)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to store document embedding: {}", e))?;
         }
+        Ok(())
     }
 
-    /// Search for relevant deliberations (Semantic Search)
-    pub async fn semantic_search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
-        self.logger.log(
-            LogLevel::Debug,
-            "knowledge",
-            &format!("🔍 Searching for: {}", query),
-        );
+    /// Delete a document's chunks and their embeddings; returns the chunk ids
+    /// so the caller can drop their vector index entries once committed
+    async fn delete_document_chunks(
+        conn: &mut SqliteConnection,
+        document_id: &str,
+    ) -> Result<Vec<String>, String> {
+        let ids: Vec<String> = sqlx::query("SELECT id FROM document_chunks WHERE document_id = ?")
+            .bind(document_id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to list document chunks: {}", e))?
+            .iter()
+            .map(|row| row.get("id"))
+            .collect();
 
-        let query_embedding = self.generate_embedding(query).await?;
-        
-        // Fetch all embeddings (naive approach for MVP)
-        // In production, use vector extension or specialized DB
+        sqlx::query(
+            "DELETE FROM document_embeddings WHERE chunk_id IN (SELECT id FROM document_chunks WHERE document_id = ?)",
+        )
+        .bind(document_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to delete document embeddings: {}", e))?;
+        sqlx::query("DELETE FROM document_chunks WHERE document_id = ?")
+            .bind(document_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to delete document chunks: {}", e))?;
+        Ok(ids)
+    }
+
+    /// Ingested documents, most recent first
+    pub async fn list_documents(&self) -> Result<Vec<DocumentInfo>, String> {
         let rows = sqlx::query(
             r#"
-            SELECT e.embedding, t.deliberation_id, t.text, d.question
-            FROM embeddings e
-            JOIN text_chunks t ON e.chunk_id = t.id
-            JOIN deliberations d ON t.deliberation_id = d.id
+            SELECT id, source_path, title, format, content_hash, chunk_count, ingested_at
+            FROM documents
+            ORDER BY ingested_at DESC
             "#,
         )
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| format!("Failed to fetch embeddings: {}", e))?;
+        .map_err(|e| format!("Failed to list documents: {}", e))?;
 
-        let mut results = Vec::new();
+        Ok(rows
+            .iter()
+            .map(|row| DocumentInfo {
+                id: row.get("id"),
+                source_path: row.get("source_path"),
+                title: row.get("title"),
+                format: row.get("format"),
+                content_hash: row.get("content_hash"),
+                chunk_count: row.get("chunk_count"),
+                ingested_at: row.get("ingested_at"),
+            })
+            .collect())
+    }
 
-        for row in rows {
-            let delib_id: String = row.get("deliberation_id");
-            let question: String = row.get("question");
-            let text: String = row.get("text");
-            let embedding_bytes: Vec<u8> = row.get("embedding");
-
-            let embedding = Self::deserialize_embedding(&embedding_bytes);
-            let similarity = Self::cosine_similarity(&query_embedding, &embedding);
-
-            results.push(SearchResult {
-                deliberation_id: delib_id,
-                question,
-                relevance_score: similarity,
-                text_snippet: Self::truncate(&text, 200),
-            });
+    /// Forget an ingested document; returns whether it existed
+    pub async fn remove_document(&self, id: &str) -> Result<bool, String> {
+        let mut tx = self.pool.begin().await.map_err(|e| format!("Failed to begin removal: {}", e))?;
+        let chunk_ids = Self::delete_document_chunks(&mut tx, id).await?;
+        let result = sqlx::query("DELETE FROM documents WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete document: {}", e))?;
+        tx.commit().await.map_err(|e| format!("Failed to commit removal: {}", e))?;
+
+        for chunk_id in &chunk_ids {
+            self.vectors.remove(VectorSource::Documents, chunk_id).await?;
         }
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Write a signed bundle of deliberations, chunks, embeddings and reputations
+    pub async fn export_bundle(&self, path: &Path, identity: &SigningIdentity) -> Result<ExportReport, String> {
+        let report = bundle::export(&self.pool, identity, path).await?;
+        self.logger.log(
+            LogLevel::Success,
+            "knowledge",
+            &format!(
+                "📦 Exported {} deliberations, {} chunks, {} embeddings, {} reputations to {}",
+                report.deliberations, report.chunks, report.embeddings, report.reputations, report.path
+            ),
+        );
+        Ok(report)
+    }
+
+    /// Verify a bundle's signature and import the records it has that this node lacks
+    ///
+    /// With `reembed`, vectors from another embedding model are replaced by
+    /// queueing their chunks for the local embedder.
+    pub async fn import_bundle(&self, path: &Path, trusted_key: Option<&str>, reembed: bool) -> Result<ImportReport, String> {
+        let path = path.to_path_buf();
+        let trusted_key = trusted_key.map(str::to_string);
+        let verified = tokio::task::spawn_blocking(move || bundle::read_verified(&path, trusted_key.as_deref()))
+            .await
+            .map_err(|e| format!("Bundle reader crashed: {}", e))??;
 
-        // Sort by similarity and take top results
-        results.sort_by(|a, b| b.relevance_score.partial_cmp(&a.relevance_score).unwrap());
-        results.truncate(limit);
+        let report = bundle::import(&self.pool, &verified, self.embedder.embedding_model(), reembed).await?;
+        self.vectors.migrate(VectorSource::Chunks).await?;
+        self.record_missing_verdicts().await?;
+        if report.queued_for_embedding > 0 {
+            self.queue_signal.notify_one();
+        }
 
         self.logger.log(
             LogLevel::Success,
             "knowledge",
-            &format!("✅ Found {} relevant results", results.len()),
+            &format!(
+                "📦 Imported bundle from {}: {} deliberations, {} chunks, {} embeddings, {} reputations ({} duplicates skipped, {} queued for embedding)",
+                report.signer,
+                report.deliberations,
+                report.chunks,
+                report.embeddings,
+                report.reputations,
+                report.duplicates,
+                report.queued_for_embedding
+            ),
         );
+        Ok(report)
+    }
 
-        Ok(results)
+    /// Append a finalised verdict to the ledger and checkpoint when due
+    async fn record_verdict(&self, deliberation_id: &str, question: &str, verdict: &str) -> Result<(), String> {
+        let _guard = self.ledger_lock.lock().await;
+        if let Some(entry) = ledger::append(&self.pool, deliberation_id, question, verdict).await? {
+            self.logger.log(
+                LogLevel::Debug,
+                "knowledge",
+                &format!("⛓️ Ledger entry {} for {}", entry.seq, deliberation_id),
+            );
+        }
+        if let Some(identity) = &self.signing_identity {
+            if let Some(checkpoint) = ledger::checkpoint_if_due(&self.pool, identity).await? {
+                self.logger.log(
+                    LogLevel::Info,
+                    "knowledge",
+                    &format!("🔏 Signed ledger checkpoint at entry {}", checkpoint.seq),
+                );
+            }
+        }
+        Ok(())
     }
 
-    /// Build RAG context string from query
-    pub async fn build_rag_context(&self, query: &str, limit: usize) -> Result<RAGContext, String> {
-        let results = self.semantic_search(query, limit).await?;
-        
-        let mut context_text = String::from("### Relevant Past Decisions:\n\n");
-        for result in &results {
-            context_text.push_str(&format!("- **Question:** {}\n  **Snippet:** {}\n\n", 
-                result.question, result.text_snippet));
+    /// Add stored verdicts the ledger has not seen (databases older than the ledger, imports)
+    async fn record_missing_verdicts(&self) -> Result<(), String> {
+        let _guard = self.ledger_lock.lock().await;
+        let added = ledger::record_missing(&self.pool).await?;
+        if added > 0 {
+            self.logger.log(
+                LogLevel::Info,
+                "knowledge",
+                &format!("⛓️ Recorded {} earlier verdicts in the ledger", added),
+            );
         }
-        
-        Ok(RAGContext {
-            relevant_decisions: results,
-            context_text,
-        })
+        Ok(())
+    }
+
+    /// Sign the current ledger head (no-op if already checkpointed)
+    pub async fn checkpoint_ledger(&self) -> Result<Option<Checkpoint>, String> {
+        let identity = self
+            .signing_identity
+            .as_ref()
+            .ok_or_else(|| "No signing identity configured for the ledger".to_string())?;
+        let _guard = self.ledger_lock.lock().await;
+        ledger::checkpoint(&self.pool, identity).await
+    }
+
+    /// Check the whole ledger against its checkpoints and the stored verdicts
+    pub async fn verify_ledger(&self, trusted_key: Option<&str>) -> Result<LedgerVerification, String> {
+        let report = ledger::verify(&self.pool, trusted_key).await?;
+        if !report.valid {
+            self.logger.log(
+                LogLevel::Error,
+                "knowledge",
+                &format!("🚨 Verdict ledger verification failed: {}", report.problems.join("; ")),
+            );
+        }
+        Ok(report)
+    }
+
+    /// Inclusion proof for a deliberation's latest verdict, checkpointing first if needed
+    pub async fn prove_verdict(&self, deliberation_id: &str) -> Result<InclusionProof, String> {
+        if let Some(proof) = ledger::prove(&self.pool, deliberation_id).await? {
+            return Ok(proof);
+        }
+        self.checkpoint_ledger().await?;
+        ledger::prove(&self.pool, deliberation_id)
+            .await?
+            .ok_or_else(|| format!("No checkpoint covers the verdict of {}", deliberation_id))
     }
 
     /// Get a specific deliberation by ID
@@ -654,7 +1579,7 @@ impl KnowledgeBank {
             // Get responses for this round
             let response_rows = sqlx::query(
                 r#"
-                SELECT member_name, model, response, timestamp
+                SELECT member_name, model, response, timestamp, reasoning
                 FROM responses
                 WHERE round_id = ?
                 "#,
@@ -676,6 +1601,7 @@ impl KnowledgeBank {
                     model,
                     response,
                     timestamp: timestamp as u64,
+                    reasoning: resp_row.get("reasoning"),
                 });
             }
 
@@ -881,8 +1807,8 @@ impl KnowledgeBank {
         for response in &session.responses {
             sqlx::query(
                 r#"
-                INSERT INTO responses (round_id, member_name, model, response, timestamp)
-                VALUES (?, ?, ?, ?, ?)
+                INSERT INTO responses (round_id, member_name, model, response, timestamp, reasoning)
+                VALUES (?, ?, ?, ?, ?, ?)
                 "#,
             )
             .bind(round_id)
@@ -890,11 +1816,16 @@ impl KnowledgeBank {
             .bind(&response.model_name)
             .bind(&response.response)
             .bind(response.timestamp as i64)
+            .bind(&response.reasoning)
             .execute(&self.pool)
             .await
             .map_err(|e| format!("Failed to save response: {}", e))?;
         }
 
+        if let (SessionStatus::ConsensusReached, Some(consensus)) = (&session.status, &session.consensus) {
+            self.record_verdict(&session.id, &session.question, consensus).await?;
+        }
+
         Ok(())
     }
 
@@ -920,7 +1851,7 @@ impl KnowledgeBank {
             // Load responses
             let response_rows = sqlx::query(
                 r#"
-                SELECT r.member_name, r.model, r.response, r.timestamp
+                SELECT r.member_name, r.model, r.response, r.timestamp, r.reasoning
                 FROM responses r
                 JOIN rounds ro ON r.round_id = ro.id
                 WHERE ro.deliberation_id = ?
@@ -940,6 +1871,7 @@ impl KnowledgeBank {
                     timestamp: r_row.get::<i64, _>("timestamp") as u64,
                     signature: None, // Not stored in DB yet
                     public_key: None,
+                    reasoning: r_row.get("reasoning"),
                 });
             }
 
@@ -952,6 +1884,8 @@ impl KnowledgeBank {
                 consensus: row.get("consensus"),
                 status: if completed { SessionStatus::ConsensusReached } else { SessionStatus::GatheringResponses },
                 created_at: row.get::<i64, _>("created_at") as u64,
+                attachments: Vec::new(), // Attachments are not persisted
+                namespace: None,
             });
         }
 
@@ -962,8 +1896,8 @@ impl KnowledgeBank {
     pub async fn save_chat_message(&self, message: &crate::chat::Message) -> Result<(), String> {
         sqlx::query(
             r#"
-            INSERT INTO chat_logs (id, channel, author, author_type, content, timestamp, signature, reply_to)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO chat_logs (id, channel, author, author_type, content, timestamp, signature, reply_to, reasoning, citations)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&message.id)
@@ -974,34 +1908,39 @@ impl KnowledgeBank {
         .bind(message.timestamp.timestamp())
         .bind(&message.signature)
         .bind(&message.reply_to)
+        .bind(&message.reasoning)
+        .bind(citations::to_column(&message.citations))
         .execute(&self.pool)
         .await
         .map_err(|e| format!("Failed to save chat message: {}", e))?;
 
-        // Generate embedding for RAG (per-channel context)
+        // Queue an embedding for RAG (per-channel context)
         // Only for non-system messages to save resources
         if message.author_type != crate::chat::AuthorType::System {
-            if let Ok(embedding) = self.generate_embedding(&message.content).await {
-                // Store in chat_embeddings table
-                let embedding_bytes = Self::serialize_embedding(&embedding);
-                sqlx::query(
-                    r#"
-                    INSERT OR REPLACE INTO chat_embeddings (message_id, embedding, dimension)
-                    VALUES (?, ?, ?)
-                    "#,
-                )
-                .bind(&message.id)
-                .bind(&embedding_bytes)
-                .bind(embedding.len() as i64)
-                .execute(&self.pool)
-                .await
-                .map_err(|e| format!("Failed to store chat embedding: {}", e))?;
-            }
+            self.queue_embedding(VectorSource::Chat, &message.id).await?;
         }
 
         Ok(())
     }
 
+    async fn store_chat_embedding(&self, message_id: &str, channel: &str, embedding: &Embedding) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO chat_embeddings (message_id, embedding, dimension, model)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(message_id)
+        .bind(Self::serialize_embedding(&embedding.vector))
+        .bind(embedding.dimension as i64)
+        .bind(&embedding.model)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to store chat embedding: {}", e))?;
+
+        self.vectors.upsert(VectorSource::Chat, message_id, channel, embedding).await
+    }
+
     /// Get recent chat messages for a channel (Context)
     pub async fn get_chat_history(
         &self,
@@ -1010,7 +1949,7 @@ impl KnowledgeBank {
     ) -> Result<Vec<crate::chat::Message>, String> {
         let rows = sqlx::query(
             r#"
-            SELECT id, channel, author, author_type, content, timestamp, signature, reply_to
+            SELECT id, channel, author, author_type, content, timestamp, signature, reply_to, reasoning, citations
             FROM chat_logs
             WHERE channel = ?
             ORDER BY timestamp DESC
@@ -1023,41 +1962,45 @@ impl KnowledgeBank {
         .await
         .map_err(|e| format!("Failed to fetch chat history: {}", e))?;
 
-        let mut messages = Vec::new();
-        for row in rows {
-            let channel_str: String = row.get("channel");
-            let author_type_str: String = row.get("author_type");
-            let timestamp_int: i64 = row.get("timestamp");
+        let mut messages = rows.iter().map(Self::chat_message_from_row).collect::<Result<Vec<_>, _>>()?;
 
-            let channel_type = crate::chat::ChannelType::from_str(&channel_str)
-                .ok_or_else(|| format!("Invalid channel type: {}", channel_str))?;
+        // Return in chronological order (oldest first)
+        messages.reverse();
+        Ok(messages)
+    }
 
-            let author_type = match author_type_str.as_str() {
-                "Human" => crate::chat::AuthorType::Human,
-                "AI" => crate::chat::AuthorType::AI,
-                "System" => crate::chat::AuthorType::System,
-                _ => crate::chat::AuthorType::Human, // Default fallback
-            };
+    fn chat_message_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<crate::chat::Message, String> {
+        let channel_str: String = row.get("channel");
+        let author_type_str: String = row.get("author_type");
+        let timestamp_int: i64 = row.get("timestamp");
 
-            let timestamp = chrono::DateTime::<chrono::Utc>::from_timestamp(timestamp_int, 0)
-                .ok_or("Invalid timestamp")?;
+        let channel_type = crate::chat::ChannelType::from_str(&channel_str)
+            .ok_or_else(|| format!("Invalid channel type: {}", channel_str))?;
 
-            messages.push(crate::chat::Message {
-                id: row.get("id"),
-                channel: channel_type,
-                author: row.get("author"),
-                author_type,
-                content: row.get("content"),
-                timestamp,
-                signature: row.get("signature"),
-                reply_to: row.get("reply_to"),
-                reactions: Vec::new(), // Reactions not persisted yet
-            });
-        }
+        let author_type = match author_type_str.as_str() {
+            "Human" => crate::chat::AuthorType::Human,
+            "AI" => crate::chat::AuthorType::AI,
+            "System" => crate::chat::AuthorType::System,
+            _ => crate::chat::AuthorType::Human, // Default fallback
+        };
 
-        // Return in chronological order (oldest first)
-        messages.reverse();
-        Ok(messages)
+        let timestamp = chrono::DateTime::<chrono::Utc>::from_timestamp(timestamp_int, 0)
+            .ok_or("Invalid timestamp")?;
+
+        Ok(crate::chat::Message {
+            id: row.get("id"),
+            channel: channel_type,
+            author: row.get("author"),
+            author_type,
+            content: row.get("content"),
+            timestamp,
+            signature: row.get("signature"),
+            reply_to: row.get("reply_to"),
+            reactions: Vec::new(), // Reactions not persisted yet
+            attachments: Vec::new(), // Attachments are not persisted
+            reasoning: row.get("reasoning"),
+            citations: citations::from_column(row.get("citations")),
+        })
     }
 
     /// Add a text chunk manually (e.g. for consensus results)
@@ -1073,6 +2016,7 @@ impl KnowledgeBank {
             ChunkType::Question => "Question",
             ChunkType::Response { .. } => "Response",
             ChunkType::Consensus => "Consensus",
+            ChunkType::Document { .. } => "Document",
         };
 
         sqlx::query(
@@ -1089,57 +2033,76 @@ impl KnowledgeBank {
         .await
         .map_err(|e| format!("Failed to add text chunk: {}", e))?;
 
-        // Generate embedding (async/background ideally, but inline for now)
-        if let Ok(embedding) = self.generate_embedding(text).await {
-            self.store_embedding(id, &embedding).await?;
-        }
-
-        Ok(())
+        self.queue_embedding(VectorSource::Chunks, id).await
     }
 
     /// Search chat history for a specific channel (Scoped RAG)
+    ///
+    /// Hybrid: vector and BM25 rankings are fused, or lexical only when embeddings are unavailable.
     pub async fn search_channel_context(
         &self,
         channel: crate::chat::ChannelType,
         query: &str,
         limit: usize,
     ) -> Result<Vec<String>, String> {
-        let query_embedding = self.generate_embedding(query).await?;
-        
-        // Join chat_logs and chat_embeddings, filter by channel
-        let rows = sqlx::query(
-            r#"
-            SELECT c.content, e.embedding
-            FROM chat_logs c
-            JOIN chat_embeddings e ON c.id = e.message_id
-            WHERE c.channel = ?
-            "#,
-        )
-        .bind(channel.as_str())
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| format!("Failed to fetch channel embeddings: {}", e))?;
+        let depth = (limit * 4).max(20);
+        let mut rankings: Vec<Vec<String>> = Vec::new();
+
+        match self.channel_vector_ranking(channel, query, depth).await {
+            Ok(ids) => rankings.push(ids),
+            Err(e) => self.logger.log(
+                LogLevel::Warning,
+                "knowledge",
+                &format!("⚠️ Semantic channel search unavailable ({}), searching lexically", e),
+            ),
+        }
+        rankings.push(
+            lexical_index::search(&self.pool, LexicalSource::Chat, query, depth, Some(channel.as_str())).await?,
+        );
 
         let mut results = Vec::new();
-
-        for row in rows {
-            let content: String = row.get("content");
-            let embedding_bytes: Vec<u8> = row.get("embedding");
-            let embedding = Self::deserialize_embedding(&embedding_bytes);
-            
-            let similarity = Self::cosine_similarity(&query_embedding, &embedding);
-            results.push((content, similarity));
+        for (id, _) in lexical_index::reciprocal_rank_fusion(&rankings) {
+            if results.len() >= limit {
+                break;
+            }
+            let row = sqlx::query("SELECT content FROM chat_logs WHERE id = ?")
+                .bind(&id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| format!("Failed to fetch channel message: {}", e))?;
+            if let Some(row) = row {
+                results.push(row.get("content"));
+            }
         }
 
-        // Sort by similarity
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        results.truncate(limit);
+        Ok(results)
+    }
 
-        Ok(results.into_iter().map(|(content, _)| content).collect())
+    /// Message ids in `channel` nearest to `query` by embedding
+    async fn channel_vector_ranking(
+        &self,
+        channel: crate::chat::ChannelType,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, String> {
+        if let Some(job) = reembed::running_job(&self.pool).await? {
+            return Err(format!("re-embedding to {} in progress", job.target_model));
+        }
+        let query_embedding = self.generate_embedding(query).await?;
+        self.check_embedding_compatibility("chat_embeddings", &query_embedding).await?;
+        let nearest = self
+            .vectors
+            .nearest(VectorSource::Chat, &query_embedding, limit, Some(channel.as_str()))
+            .await?;
+        Ok(nearest.into_iter().map(|(id, _)| id).collect())
     }
 
-    /// Clear chat context for a channel (Reset Period)
-    pub async fn clear_channel_context(&self, channel: crate::chat::ChannelType) -> Result<(), String> {
+    /// Clear chat context for a channel (Reset Period); the reset is logged in `retention_events`
+    pub async fn clear_channel_context(
+        &self,
+        channel: crate::chat::ChannelType,
+        initiated_by: Initiator,
+    ) -> Result<RetentionEvent, String> {
         // Delete embeddings first (foreign key)
         sqlx::query(
             r#"
@@ -1151,14 +2114,126 @@ impl KnowledgeBank {
         .execute(&self.pool)
         .await
         .map_err(|e| format!("Failed to clear chat embeddings: {}", e))?;
-        
+        self.vectors.remove_scope(VectorSource::Chat, channel.as_str()).await?;
+
+        let cleared = sqlx::query("UPDATE chat_logs SET context_cleared = 1 WHERE channel = ? AND context_cleared = 0")
+            .bind(channel.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to clear chat context: {}", e))?
+            .rows_affected();
+
         self.logger.log(
             LogLevel::Info,
             "knowledge",
-            &format!("🧹 Cleared RAG context for #{}", channel.as_str()),
+            &format!("🧹 Cleared RAG context for #{} ({} messages, {})", channel.as_str(), cleared, initiated_by.key()),
         );
 
-        Ok(())
+        retention::record(&self.pool, channel.as_str(), RetentionKind::Reset, initiated_by, cleared as i64, None).await
+    }
+
+    /// Whether `channel` is due for a periodic context reset every `interval_secs`
+    pub async fn channel_reset_due(&self, channel: crate::chat::ChannelType, interval_secs: i64) -> Result<bool, String> {
+        let last_reset = retention::last_reset(&self.pool, channel.as_str()).await?;
+        let row = sqlx::query("SELECT MIN(timestamp) AS oldest FROM chat_logs WHERE channel = ? AND context_cleared = 0")
+            .bind(channel.as_str())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to read channel context: {}", e))?;
+        let oldest: Option<i64> = row.get("oldest");
+        Ok(retention::reset_due(interval_secs, last_reset, oldest, chrono::Utc::now().timestamp()))
+    }
+
+    /// Delete messages of `channel` outside `policy`, archiving them first if the policy asks;
+    /// `None` when nothing was over the limits
+    pub async fn prune_channel(
+        &self,
+        channel: crate::chat::ChannelType,
+        policy: &RetentionPolicy,
+        archive_dir: &Path,
+        initiated_by: Initiator,
+    ) -> Result<Option<RetentionEvent>, String> {
+        let now = chrono::Utc::now().timestamp();
+        let (cutoff, keep) = retention::limits(policy, now);
+        let rows = sqlx::query(
+            r#"
+            SELECT id, channel, author, author_type, content, timestamp, signature, reply_to, reasoning, citations
+            FROM chat_logs
+            WHERE channel = ?
+              AND (timestamp < ?
+                   OR id NOT IN (SELECT id FROM chat_logs WHERE channel = ? ORDER BY timestamp DESC, rowid DESC LIMIT ?))
+            ORDER BY timestamp, rowid
+            "#,
+        )
+        .bind(channel.as_str())
+        .bind(cutoff)
+        .bind(channel.as_str())
+        .bind(keep)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to select expired messages: {}", e))?;
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let messages = rows.iter().map(Self::chat_message_from_row).collect::<Result<Vec<_>, _>>()?;
+
+        // Nothing is deleted unless the archive was written
+        let archive_path = if policy.archive {
+            let dir = archive_dir.to_path_buf();
+            let archived = messages.clone();
+            let path = tokio::task::spawn_blocking(move || retention::write_archive(&dir, channel, &archived, now))
+                .await
+                .map_err(|e| format!("Archive task failed: {}", e))??;
+            Some(path.to_string_lossy().into_owned())
+        } else {
+            None
+        };
+
+        let mut tx = self.pool.begin().await.map_err(|e| format!("Failed to begin prune: {}", e))?;
+        for message in &messages {
+            for sql in [
+                "DELETE FROM chat_embeddings WHERE message_id = ?",
+                "DELETE FROM embedding_queue WHERE source = 'chat' AND item_id = ?",
+                "DELETE FROM chat_logs WHERE id = ?",
+            ] {
+                sqlx::query(sql)
+                    .bind(&message.id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to prune message {}: {}", message.id, e))?;
+            }
+        }
+        tx.commit().await.map_err(|e| format!("Failed to commit prune: {}", e))?;
+        for message in &messages {
+            self.vectors.remove(VectorSource::Chat, &message.id).await?;
+        }
+
+        self.logger.log(
+            LogLevel::Info,
+            "knowledge",
+            &format!(
+                "🗑️ Pruned {} messages from #{}{}",
+                messages.len(),
+                channel.as_str(),
+                archive_path.as_deref().map(|p| format!(" (archived to {})", p)).unwrap_or_default()
+            ),
+        );
+
+        retention::record(
+            &self.pool,
+            channel.as_str(),
+            RetentionKind::Prune,
+            initiated_by,
+            messages.len() as i64,
+            archive_path,
+        )
+        .await
+        .map(Some)
+    }
+
+    /// Logged prunes and resets, newest first
+    pub async fn retention_history(&self, channel: Option<&str>, limit: usize) -> Result<Vec<RetentionEvent>, String> {
+        retention::history(&self.pool, channel, limit).await
     }
 
     // Removed duplicate store_embedding
@@ -1167,21 +2242,330 @@ impl KnowledgeBank {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::local_embeddings::LOCAL_EMBEDDING_MODEL;
+    use crate::providers::mock::MOCK_EMBEDDING_MODEL;
+    use crate::providers::{LocalEmbeddingProvider, MockProvider, OllamaProvider};
 
     #[tokio::test]
     async fn test_knowledge_bank_initialization() {
         let logger = Arc::new(Logger::new(false));
-        let kb = KnowledgeBank::new(
-            "sqlite::memory:",
-            logger,
-            "http://localhost:11434".to_string(),
-            None, // No auth for tests
-        )
-        .await;
+        let embedder = Arc::new(LocalEmbeddingProvider::new(logger.clone()));
+        let kb = KnowledgeBank::new("sqlite::memory:", logger, embedder).await;
 
         assert!(kb.is_ok());
     }
 
+    async fn store_question(kb: &KnowledgeBank, id: &str, question: &str) {
+        kb.store_deliberation(&crate::deliberation::DeliberationResult {
+            session_id: id.to_string(),
+            question: question.to_string(),
+            rounds: vec![],
+            consensus: None,
+            completed: true,
+            created_at: 0,
+        })
+        .await
+        .unwrap();
+        kb.process_embedding_queue().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fallback_embedder_when_primary_unreachable() {
+        let logger = Arc::new(Logger::new(false));
+        // Nothing listens on port 9
+        let ollama = Arc::new(OllamaProvider::new(
+            "http://127.0.0.1:9".to_string(),
+            "test".to_string(),
+            logger.clone(),
+        ));
+        let kb = KnowledgeBank::new("sqlite::memory:", logger.clone(), ollama)
+            .await
+            .unwrap()
+            .with_fallback_embedder(Arc::new(LocalEmbeddingProvider::new(logger)));
+
+        store_question(&kb, "s1", "Should artificial intelligence have legal rights?").await;
+        store_question(&kb, "s2", "What is the best recipe for banana bread?").await;
+
+        let results = kb
+            .semantic_search("Should AI get legal rights?", 1, SearchMode::Semantic)
+            .await
+            .unwrap();
+        assert_eq!(results[0].deliberation_id, "s1");
+    }
+
+    #[tokio::test]
+    async fn test_failed_embeddings_stay_queued() {
+        let logger = Arc::new(Logger::new(false));
+        // Nothing listens on port 9
+        let ollama = Arc::new(OllamaProvider::new(
+            "http://127.0.0.1:9".to_string(),
+            "test".to_string(),
+            logger.clone(),
+        ));
+        let kb = KnowledgeBank::new("sqlite::memory:", logger, ollama).await.unwrap();
+
+        // Storing does not wait for (or fail on) the embedding endpoint
+        store_question(&kb, "s1", "Should we approve budget line BL-4471?").await;
+        let status = kb.embedding_queue_status().await.unwrap();
+        assert_eq!((status.pending, status.retrying), (1, 1));
+        assert!(status.last_error.is_some());
+        assert!(status.next_attempt_at.unwrap() > chrono::Utc::now().timestamp());
+
+        // Not due yet
+        assert_eq!(kb.process_embedding_queue().await.unwrap(), 0);
+        let lexical = kb.semantic_search("BL-4471", 1, SearchMode::Hybrid).await.unwrap();
+        assert_eq!(lexical[0].deliberation_id, "s1");
+    }
+
+    #[tokio::test]
+    async fn test_search_refuses_incompatible_dimensions() {
+        let logger = Arc::new(Logger::new(false));
+        let db = std::env::temp_dir().join(format!("kb-dims-{}.sqlite", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}?mode=rwc", db.to_string_lossy());
+
+        let kb = KnowledgeBank::new(&url, logger.clone(), Arc::new(LocalEmbeddingProvider::new(logger.clone())))
+            .await
+            .unwrap();
+        store_question(&kb, "s1", "Should artificial intelligence have legal rights?").await;
+        drop(kb);
+
+        let narrow = LocalEmbeddingProvider::new(logger.clone()).with_dimension(64);
+        let kb = KnowledgeBank::new(&url, logger, Arc::new(narrow)).await.unwrap();
+        let result = kb.semantic_search("legal rights", 1, SearchMode::Semantic).await;
+        assert!(result.unwrap_err().contains("Refusing search"));
+
+        let _ = std::fs::remove_file(db);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_degrades_to_lexical() {
+        let logger = Arc::new(Logger::new(false));
+        let db = std::env::temp_dir().join(format!("kb-hybrid-{}.sqlite", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}?mode=rwc", db.to_string_lossy());
+
+        let kb = KnowledgeBank::new(&url, logger.clone(), Arc::new(LocalEmbeddingProvider::new(logger.clone())))
+            .await
+            .unwrap();
+        store_question(&kb, "s1", "Should we approve budget line BL-4471 for the data centre?").await;
+        store_question(&kb, "s2", "Should artificial intelligence have legal rights?").await;
+
+        let hybrid = kb.semantic_search("BL-4471", 1, SearchMode::Hybrid).await.unwrap();
+        assert_eq!(hybrid[0].deliberation_id, "s1");
+        drop(kb);
+
+        // Nothing listens on port 9
+        let ollama = Arc::new(OllamaProvider::new(
+            "http://127.0.0.1:9".to_string(),
+            "test".to_string(),
+            logger.clone(),
+        ));
+        let kb = KnowledgeBank::new(&url, logger, ollama).await.unwrap();
+        assert!(kb.semantic_search("BL-4471", 1, SearchMode::Semantic).await.is_err());
+        let hybrid = kb.semantic_search("BL-4471", 1, SearchMode::Hybrid).await.unwrap();
+        assert_eq!(hybrid[0].deliberation_id, "s1");
+        let lexical = kb.semantic_search("legal rights", 1, SearchMode::Lexical).await.unwrap();
+        assert_eq!(lexical[0].deliberation_id, "s2");
+
+        let _ = std::fs::remove_file(db);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_skips_unchanged_and_cites_documents() {
+        let logger = Arc::new(Logger::new(false));
+        let embedder = Arc::new(LocalEmbeddingProvider::new(logger.clone()));
+        let kb = KnowledgeBank::new("sqlite::memory:", logger, embedder).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("kb-ingest-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join(".hidden")).unwrap();
+        std::fs::write(dir.join("policy.md"), "# Travel Policy\n\n## Approvals\nTrips over 5000 EUR need form TP-77.").unwrap();
+        std::fs::write(dir.join("faq.html"), "<h1>FAQ</h1><p>Expenses are paid monthly.</p>").unwrap();
+        std::fs::write(dir.join("notes.docx"), "ignored").unwrap();
+        std::fs::write(dir.join(".hidden/secret.md"), "# Secret").unwrap();
+
+        let report = kb.ingest_path(&dir).await.unwrap();
+        assert_eq!(report.ingested.len(), 2);
+        assert!(report.failed.is_empty());
+
+        let again = kb.ingest_path(&dir).await.unwrap();
+        assert!(again.ingested.is_empty());
+        assert_eq!(again.unchanged.len(), 2);
+
+        std::fs::write(dir.join("policy.md"), "# Travel Policy\n\n## Approvals\nTrips over 8000 EUR need form TP-78.").unwrap();
+        let changed = kb.ingest_path(&dir).await.unwrap();
+        assert_eq!(changed.ingested.len(), 1);
+
+        let rag = kb.build_rag_context("form TP-78", 3, SearchMode::Hybrid).await.unwrap();
+        let top = &rag.relevant_decisions[0];
+        let citation = top.document.as_ref().unwrap();
+        assert_eq!(citation.heading.as_deref(), Some("Travel Policy > Approvals"));
+        assert!(top.text_snippet.contains("TP-78"));
+        assert!(rag.context_text.contains("### Reference Documents:"));
+        assert!(rag.context_text.contains(&format!("[{}] **Source:**", rag.sources[0].id)));
+        assert!(kb.semantic_search("TP-77", 3, SearchMode::Lexical).await.unwrap().is_empty());
+        // Documents are not past questions
+        assert!(kb.search_deliberations("form TP-78", 3).await.unwrap().is_empty());
+
+        let documents = kb.list_documents().await.unwrap();
+        assert_eq!(documents.len(), 2);
+        assert!(kb.remove_document(&documents[0].id).await.unwrap());
+        assert_eq!(kb.list_documents().await.unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_reembedding_resumes_and_switches_over_when_complete() {
+        let logger = Arc::new(Logger::new(false));
+        let db = std::env::temp_dir().join(format!("kb-reembed-{}.sqlite", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}?mode=rwc", db.to_string_lossy());
+        let models = |kb: &KnowledgeBank| {
+            let pool = kb.pool.clone();
+            async move {
+                sqlx::query("SELECT model FROM embeddings UNION ALL SELECT model FROM chat_embeddings")
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap()
+                    .iter()
+                    .map(|r| r.get::<String, _>("model"))
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        let kb = KnowledgeBank::new(&url, logger.clone(), Arc::new(LocalEmbeddingProvider::new(logger.clone())))
+            .await
+            .unwrap();
+        store_question(&kb, "s1", "Should artificial intelligence have legal rights?").await;
+        store_question(&kb, "s2", "What is the best recipe for banana bread?").await;
+        let message = crate::chat::Message::new(
+            crate::chat::ChannelType::General,
+            "Pragmatist".to_string(),
+            crate::chat::AuthorType::AI,
+            "Bake it at 180 degrees.".to_string(),
+        );
+        kb.save_chat_message(&message).await.unwrap();
+        kb.process_embedding_queue().await.unwrap();
+        assert!(kb.reembed().await.unwrap().is_none());
+        drop(kb);
+
+        // Model changed; simulate a job interrupted after its first item
+        let mock = Arc::new(MockProvider::from_optional_path(None, logger.clone()));
+        let kb = KnowledgeBank::new(&url, logger.clone(), mock.clone()).await.unwrap();
+        let job = reembed::start_job(&kb.pool, MOCK_EMBEDDING_MODEL, 3).await.unwrap();
+        let first = reembed::pending_items(&kb.pool, &job, 1).await.unwrap();
+        let embedding = KnowledgeBank::embed_with(mock.as_ref(), &first[0].text).await.unwrap();
+        reembed::stage(&kb.pool, &job.id, &first[0], &embedding).await.unwrap();
+        assert!(models(&kb).await.iter().all(|m| m == LOCAL_EMBEDDING_MODEL));
+        drop(kb);
+
+        let kb = KnowledgeBank::new(&url, logger, mock).await.unwrap();
+        // Stored vectors are still the old model's: searches during the job rank lexically
+        let during = kb.semantic_search("legal rights", 1, SearchMode::Semantic).await.unwrap();
+        assert_eq!(during[0].deliberation_id, "s1");
+        assert!(kb.search_deliberations("legal rights", 1).await.unwrap().is_empty());
+
+        let finished = kb.reembed().await.unwrap().unwrap();
+        assert_eq!(finished.id, job.id);
+        assert_eq!(finished.status, "completed");
+        assert_eq!((finished.done, finished.total), (3, 3));
+        assert!(models(&kb).await.iter().all(|m| m == MOCK_EMBEDDING_MODEL));
+
+        let results = kb
+            .semantic_search("Should AI get legal rights?", 1, SearchMode::Semantic)
+            .await
+            .unwrap();
+        assert_eq!(results[0].deliberation_id, "s1");
+        assert!(kb.reembed().await.unwrap().is_none());
+
+        let _ = std::fs::remove_file(db);
+    }
+
+    #[tokio::test]
+    async fn test_chat_reasoning_stored_apart_from_content() {
+        let logger = Arc::new(Logger::new(false));
+        let embedder = Arc::new(LocalEmbeddingProvider::new(logger.clone()));
+        let kb = KnowledgeBank::new("sqlite::memory:", logger, embedder).await.unwrap();
+
+        let message = crate::chat::Message::new(
+            crate::chat::ChannelType::General,
+            "Pragmatist".to_string(),
+            crate::chat::AuthorType::AI,
+            "Ship it behind a flag.".to_string(),
+        )
+        .with_reasoning(Some("The risk is low if we can roll back.".to_string()));
+        kb.save_chat_message(&message).await.unwrap();
+
+        let history = kb.get_chat_history(crate::chat::ChannelType::General, 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "Ship it behind a flag.");
+        assert_eq!(history[0].reasoning, message.reasoning);
+    }
+
+    #[tokio::test]
+    async fn test_chat_citations_round_trip() {
+        let logger = Arc::new(Logger::new(false));
+        let embedder = Arc::new(LocalEmbeddingProvider::new(logger.clone()));
+        let kb = KnowledgeBank::new("sqlite::memory:", logger, embedder).await.unwrap();
+
+        let source = Citation::from_result(&SearchResult {
+            deliberation_id: "3f2a9c1d77".to_string(),
+            question: "Adopt proposal P-17?".to_string(),
+            relevance_score: 0.9,
+            text_snippet: String::new(),
+            document: None,
+            namespace: None,
+        });
+        let reply = "We already adopted it [V-3f2a9c1d].".to_string();
+        let checked = citations::check(&reply, std::slice::from_ref(&source));
+
+        let cited = crate::chat::Message::new(
+            crate::chat::ChannelType::Topic,
+            "Historian".to_string(),
+            crate::chat::AuthorType::AI,
+            reply,
+        )
+        .with_citations(checked.resolved);
+        kb.save_chat_message(&cited).await.unwrap();
+
+        let history = kb.get_chat_history(crate::chat::ChannelType::Topic, 10).await.unwrap();
+        assert_eq!(history[0].citations, vec![source]);
+    }
+
+    #[tokio::test]
+    async fn test_finalised_sessions_are_recorded_in_ledger() {
+        let logger = Arc::new(Logger::new(false));
+        let embedder = Arc::new(LocalEmbeddingProvider::new(logger.clone()));
+        let identity = Arc::new(SigningIdentity::generate());
+        let kb = KnowledgeBank::new("sqlite::memory:", logger, embedder)
+            .await
+            .unwrap()
+            .with_signing_identity(identity.clone());
+
+        let mut session = CouncilSession {
+            id: "s1".to_string(),
+            question: "Adopt proposal P-17?".to_string(),
+            responses: Vec::new(),
+            commitments: Vec::new(),
+            reveals: Vec::new(),
+            consensus: None,
+            status: SessionStatus::GatheringResponses,
+            created_at: 0,
+            attachments: Vec::new(),
+            namespace: None,
+        };
+        kb.save_session(&session).await.unwrap();
+        assert!(kb.prove_verdict("s1").await.is_err());
+
+        session.consensus = Some("Adopted".to_string());
+        session.status = SessionStatus::ConsensusReached;
+        kb.save_session(&session).await.unwrap();
+        kb.save_session(&session).await.unwrap();
+
+        let proof = kb.prove_verdict("s1").await.unwrap();
+        assert_eq!((proof.entry.seq, proof.entry.verdict.as_str()), (1, "Adopted"));
+        ledger::verify_proof(&proof, Some(&identity.public_key_base64())).unwrap();
+        assert!(kb.verify_ledger(None).await.unwrap().valid);
+    }
+
     #[tokio::test]
     async fn test_cosine_similarity() {
         let a = vec![1.0, 0.0, 0.0];
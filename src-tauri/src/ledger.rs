@@ -0,0 +1,499 @@
+// Verdict ledger - Tamper-evident, hash-chained record of finalised verdicts
+//
+// Every verdict is appended to `verdict_ledger` (migration 0005) with
+// entry_hash = sha256(prev_hash || payload_hash). Every `CHECKPOINT_INTERVAL`
+// entries the head hash is signed with the node key. Rewriting any recorded
+// verdict, or the chain itself, no longer matches the signed checkpoints.
+
+use crate::crypto::{self, SignedMessage, SigningIdentity};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::HashMap;
+
+/// Entries between automatic checkpoints
+pub const CHECKPOINT_INTERVAL: i64 = 16;
+
+/// `prev_hash` of the first entry
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub seq: i64,
+    pub deliberation_id: String,
+    pub question: String,
+    pub verdict: String,
+    pub recorded_at: i64,
+    pub prev_hash: String,
+    pub payload_hash: String,
+    pub entry_hash: String,
+}
+
+impl LedgerEntry {
+    /// Hash of the entry's content (everything but the chain links)
+    pub fn compute_payload_hash(&self) -> String {
+        let payload = serde_json::to_vec(&(
+            self.seq,
+            &self.deliberation_id,
+            &self.question,
+            &self.verdict,
+            self.recorded_at,
+        ))
+        .unwrap_or_default();
+        format!("{:x}", Sha256::digest(payload))
+    }
+}
+
+/// sha256(prev_hash || payload_hash), both hex
+pub fn chain_hash(prev_hash: &str, payload_hash: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(payload_hash.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Head of the first `seq` entries, signed by a node key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub seq: i64,
+    pub head_hash: String,
+    pub signed_at: i64,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl Checkpoint {
+    fn message(seq: i64, head_hash: &str) -> String {
+        format!("verdict-ledger:{}:{}", seq, head_hash)
+    }
+
+    /// Whether the signature covers this seq and head
+    pub fn verify_signature(&self) -> Result<bool, String> {
+        crypto::verify_signed_message(&SignedMessage {
+            content: Self::message(self.seq, &self.head_hash),
+            signature: self.signature.clone(),
+            public_key: self.public_key.clone(),
+            timestamp: self.signed_at.max(0) as u64,
+        })
+    }
+}
+
+/// Everything needed to check one verdict against a signed checkpoint offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub entry: LedgerEntry,
+    /// Payload hashes of the entries after `entry`, up to the checkpoint
+    pub following: Vec<String>,
+    pub checkpoint: Checkpoint,
+}
+
+/// Result of checking the whole ledger
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LedgerVerification {
+    pub valid: bool,
+    pub entries: i64,
+    pub checkpoints: i64,
+    pub head_hash: Option<String>,
+    /// Human-readable description of every inconsistency found
+    pub problems: Vec<String>,
+}
+
+fn entry_from_row(row: &sqlx::sqlite::SqliteRow) -> LedgerEntry {
+    LedgerEntry {
+        seq: row.get("seq"),
+        deliberation_id: row.get("deliberation_id"),
+        question: row.get("question"),
+        verdict: row.get("verdict"),
+        recorded_at: row.get("recorded_at"),
+        prev_hash: row.get("prev_hash"),
+        payload_hash: row.get("payload_hash"),
+        entry_hash: row.get("entry_hash"),
+    }
+}
+
+fn checkpoint_from_row(row: &sqlx::sqlite::SqliteRow) -> Checkpoint {
+    Checkpoint {
+        seq: row.get("seq"),
+        head_hash: row.get("head_hash"),
+        signed_at: row.get("signed_at"),
+        public_key: row.get("public_key"),
+        signature: row.get("signature"),
+    }
+}
+
+async fn head(pool: &SqlitePool) -> Result<Option<LedgerEntry>, String> {
+    let row = sqlx::query("SELECT * FROM verdict_ledger ORDER BY seq DESC LIMIT 1")
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to read ledger head: {}", e))?;
+    Ok(row.as_ref().map(entry_from_row))
+}
+
+/// Latest entry for a deliberation
+pub async fn latest_entry(pool: &SqlitePool, deliberation_id: &str) -> Result<Option<LedgerEntry>, String> {
+    let row = sqlx::query("SELECT * FROM verdict_ledger WHERE deliberation_id = ? ORDER BY seq DESC LIMIT 1")
+        .bind(deliberation_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to read ledger: {}", e))?;
+    Ok(row.as_ref().map(entry_from_row))
+}
+
+/// Append a verdict; returns None if it is already the deliberation's latest entry
+pub async fn append(pool: &SqlitePool, deliberation_id: &str, question: &str, verdict: &str) -> Result<Option<LedgerEntry>, String> {
+    if latest_entry(pool, deliberation_id).await?.is_some_and(|e| e.verdict == verdict) {
+        return Ok(None);
+    }
+
+    let (seq, prev_hash) = match head(pool).await? {
+        Some(head) => (head.seq + 1, head.entry_hash),
+        None => (1, GENESIS_HASH.to_string()),
+    };
+    let mut entry = LedgerEntry {
+        seq,
+        deliberation_id: deliberation_id.to_string(),
+        question: question.to_string(),
+        verdict: verdict.to_string(),
+        recorded_at: chrono::Utc::now().timestamp(),
+        prev_hash,
+        payload_hash: String::new(),
+        entry_hash: String::new(),
+    };
+    entry.payload_hash = entry.compute_payload_hash();
+    entry.entry_hash = chain_hash(&entry.prev_hash, &entry.payload_hash);
+
+    sqlx::query(
+        "INSERT INTO verdict_ledger (seq, deliberation_id, question, verdict, recorded_at, prev_hash, payload_hash, entry_hash) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(entry.seq)
+    .bind(&entry.deliberation_id)
+    .bind(&entry.question)
+    .bind(&entry.verdict)
+    .bind(entry.recorded_at)
+    .bind(&entry.prev_hash)
+    .bind(&entry.payload_hash)
+    .bind(&entry.entry_hash)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to append verdict to ledger: {}", e))?;
+    Ok(Some(entry))
+}
+
+/// Append every stored verdict missing from the ledger (pre-ledger databases, imports)
+pub async fn record_missing(pool: &SqlitePool) -> Result<usize, String> {
+    let rows = sqlx::query(
+        "SELECT d.id, d.question, d.consensus FROM deliberations d \
+         WHERE d.completed = 1 AND d.consensus IS NOT NULL \
+         AND NOT EXISTS (SELECT 1 FROM verdict_ledger l WHERE l.deliberation_id = d.id) \
+         ORDER BY d.created_at, d.id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to find unrecorded verdicts: {}", e))?;
+
+    let mut added = 0;
+    for row in &rows {
+        let consensus: String = row.get("consensus");
+        if append(pool, &row.get::<String, _>("id"), &row.get::<String, _>("question"), &consensus)
+            .await?
+            .is_some()
+        {
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+/// Latest checkpoint, if any
+pub async fn latest_checkpoint(pool: &SqlitePool) -> Result<Option<Checkpoint>, String> {
+    let row = sqlx::query("SELECT * FROM ledger_checkpoints ORDER BY seq DESC LIMIT 1")
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to read ledger checkpoint: {}", e))?;
+    Ok(row.as_ref().map(checkpoint_from_row))
+}
+
+/// Sign the current head unless it is already checkpointed
+pub async fn checkpoint(pool: &SqlitePool, identity: &SigningIdentity) -> Result<Option<Checkpoint>, String> {
+    let Some(head) = head(pool).await? else {
+        return Ok(None);
+    };
+    if latest_checkpoint(pool).await?.is_some_and(|c| c.seq >= head.seq) {
+        return Ok(None);
+    }
+
+    let signed = identity.sign(&Checkpoint::message(head.seq, &head.entry_hash));
+    let checkpoint = Checkpoint {
+        seq: head.seq,
+        head_hash: head.entry_hash,
+        signed_at: signed.timestamp as i64,
+        public_key: signed.public_key,
+        signature: signed.signature,
+    };
+    sqlx::query("INSERT INTO ledger_checkpoints (seq, head_hash, signed_at, public_key, signature) VALUES (?, ?, ?, ?, ?)")
+        .bind(checkpoint.seq)
+        .bind(&checkpoint.head_hash)
+        .bind(checkpoint.signed_at)
+        .bind(&checkpoint.public_key)
+        .bind(&checkpoint.signature)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to store ledger checkpoint: {}", e))?;
+    Ok(Some(checkpoint))
+}
+
+/// Checkpoint once `CHECKPOINT_INTERVAL` entries have accumulated since the last one
+pub async fn checkpoint_if_due(pool: &SqlitePool, identity: &SigningIdentity) -> Result<Option<Checkpoint>, String> {
+    let covered = latest_checkpoint(pool).await?.map(|c| c.seq).unwrap_or(0);
+    match head(pool).await? {
+        Some(head) if head.seq - covered >= CHECKPOINT_INTERVAL => checkpoint(pool, identity).await,
+        _ => Ok(None),
+    }
+}
+
+/// Proof that the deliberation's latest verdict is in the ledger, against the
+/// first checkpoint covering it (None if no checkpoint does yet)
+pub async fn prove(pool: &SqlitePool, deliberation_id: &str) -> Result<Option<InclusionProof>, String> {
+    let entry = latest_entry(pool, deliberation_id)
+        .await?
+        .ok_or_else(|| format!("No verdict recorded for {}", deliberation_id))?;
+    let Some(checkpoint) = sqlx::query("SELECT * FROM ledger_checkpoints WHERE seq >= ? ORDER BY seq LIMIT 1")
+        .bind(entry.seq)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to read ledger checkpoint: {}", e))?
+        .as_ref()
+        .map(checkpoint_from_row)
+    else {
+        return Ok(None);
+    };
+
+    let following = sqlx::query("SELECT payload_hash FROM verdict_ledger WHERE seq > ? AND seq <= ? ORDER BY seq")
+        .bind(entry.seq)
+        .bind(checkpoint.seq)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read ledger: {}", e))?
+        .iter()
+        .map(|r| r.get("payload_hash"))
+        .collect();
+
+    Ok(Some(InclusionProof {
+        entry,
+        following,
+        checkpoint,
+    }))
+}
+
+/// Check a proof without the database; `trusted_key` (base64 public key or
+/// fingerprint) pins the checkpoint signer
+pub fn verify_proof(proof: &InclusionProof, trusted_key: Option<&str>) -> Result<(), String> {
+    let entry = &proof.entry;
+    if entry.compute_payload_hash() != entry.payload_hash {
+        return Err(format!("Entry {} does not match its payload hash", entry.seq));
+    }
+    if chain_hash(&entry.prev_hash, &entry.payload_hash) != entry.entry_hash {
+        return Err(format!("Entry {} does not match its chain hash", entry.seq));
+    }
+    if entry.seq + proof.following.len() as i64 != proof.checkpoint.seq {
+        return Err("Proof does not reach the checkpoint".to_string());
+    }
+
+    let head = proof
+        .following
+        .iter()
+        .fold(entry.entry_hash.clone(), |prev, payload| chain_hash(&prev, payload));
+    if head != proof.checkpoint.head_hash {
+        return Err("Entry is not part of the checkpointed chain".to_string());
+    }
+    if !proof.checkpoint.verify_signature()? {
+        return Err("Checkpoint signature is invalid".to_string());
+    }
+    if let Some(trusted) = trusted_key {
+        let fingerprint = crypto::public_key_fingerprint(&proof.checkpoint.public_key)?;
+        if trusted != proof.checkpoint.public_key && trusted != fingerprint {
+            return Err(format!("Checkpoint signed by {}, not the trusted key", fingerprint));
+        }
+    }
+    Ok(())
+}
+
+/// Walk the whole chain, check every checkpoint, and compare the ledger with
+/// the verdicts currently stored in `deliberations`
+pub async fn verify(pool: &SqlitePool, trusted_key: Option<&str>) -> Result<LedgerVerification, String> {
+    let entries: Vec<LedgerEntry> = sqlx::query("SELECT * FROM verdict_ledger ORDER BY seq")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read ledger: {}", e))?
+        .iter()
+        .map(entry_from_row)
+        .collect();
+    let checkpoints: Vec<Checkpoint> = sqlx::query("SELECT * FROM ledger_checkpoints ORDER BY seq")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read ledger checkpoints: {}", e))?
+        .iter()
+        .map(checkpoint_from_row)
+        .collect();
+
+    let mut problems = Vec::new();
+    let mut prev_hash = GENESIS_HASH.to_string();
+    // Heads as recomputed from the content, by seq
+    let mut heads = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.seq != i as i64 + 1 {
+            problems.push(format!("Entry {} is out of sequence (expected {})", entry.seq, i + 1));
+        }
+        if entry.compute_payload_hash() != entry.payload_hash {
+            problems.push(format!("Entry {} ({}) was modified", entry.seq, entry.deliberation_id));
+        }
+        if entry.prev_hash != prev_hash {
+            problems.push(format!("Entry {} does not link to the entry before it", entry.seq));
+        }
+        prev_hash = chain_hash(&prev_hash, &entry.compute_payload_hash());
+        heads.insert(entry.seq, prev_hash.clone());
+    }
+
+    for checkpoint in &checkpoints {
+        match checkpoint.verify_signature() {
+            Ok(true) => {}
+            _ => problems.push(format!("Checkpoint {} has an invalid signature", checkpoint.seq)),
+        }
+        if let Some(trusted) = trusted_key {
+            let fingerprint = crypto::public_key_fingerprint(&checkpoint.public_key).unwrap_or_default();
+            if trusted != checkpoint.public_key && trusted != fingerprint {
+                problems.push(format!("Checkpoint {} was signed by another key ({})", checkpoint.seq, fingerprint));
+            }
+        }
+        match heads.get(&checkpoint.seq) {
+            Some(head) if *head == checkpoint.head_hash => {}
+            Some(_) => problems.push(format!("History up to entry {} was rewritten after it was checkpointed", checkpoint.seq)),
+            None => problems.push(format!("Entries up to checkpoint {} are missing", checkpoint.seq)),
+        }
+    }
+
+    // The stored verdict must be the latest one the ledger recorded
+    let mut latest: HashMap<&str, &LedgerEntry> = HashMap::new();
+    for entry in &entries {
+        latest.insert(entry.deliberation_id.as_str(), entry);
+    }
+    let stored = sqlx::query("SELECT id, consensus, completed FROM deliberations")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read deliberations: {}", e))?;
+    let mut present = std::collections::HashSet::new();
+    for row in &stored {
+        let id: String = row.get("id");
+        let consensus: Option<String> = row.get("consensus");
+        let finalised = row.get::<bool, _>("completed") && consensus.is_some();
+        match latest.get(id.as_str()) {
+            Some(entry) if finalised && consensus.as_deref() == Some(entry.verdict.as_str()) => {}
+            Some(entry) => problems.push(format!(
+                "Verdict of {} was changed outside the ledger (entry {})",
+                id, entry.seq
+            )),
+            None if finalised => problems.push(format!("Verdict of {} was never recorded in the ledger", id)),
+            None => {}
+        }
+        present.insert(id);
+    }
+    let mut missing: Vec<&str> = latest.keys().filter(|id| !present.contains(**id)).copied().collect();
+    missing.sort();
+    for id in missing {
+        problems.push(format!("Recorded verdict of {} is missing from the knowledge bank", id));
+    }
+
+    Ok(LedgerVerification {
+        valid: problems.is_empty(),
+        entries: entries.len() as i64,
+        checkpoints: checkpoints.len() as i64,
+        head_hash: entries.last().map(|e| e.entry_hash.clone()),
+        problems,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn ledger_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::schema::MIGRATOR.run(&pool).await.unwrap();
+        pool
+    }
+
+    async fn finalise(pool: &SqlitePool, id: &str, verdict: &str) {
+        sqlx::query("INSERT OR REPLACE INTO deliberations VALUES (?, 'Approve budget?', ?, 0, 1)")
+            .bind(id)
+            .bind(verdict)
+            .execute(pool)
+            .await
+            .unwrap();
+        append(pool, id, "Approve budget?", verdict).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_inclusion_proof_verifies_offline() {
+        let pool = ledger_pool().await;
+        let identity = SigningIdentity::generate();
+        for i in 0..3 {
+            finalise(&pool, &format!("d{}", i), "Approved").await;
+        }
+        // Same verdict again is not a new entry; a changed one is
+        assert!(append(&pool, "d0", "Approve budget?", "Approved").await.unwrap().is_none());
+        assert!(prove(&pool, "d1").await.unwrap().is_none());
+        checkpoint(&pool, &identity).await.unwrap().unwrap();
+
+        let proof = prove(&pool, "d1").await.unwrap().unwrap();
+        assert_eq!(proof.following.len(), 1);
+        verify_proof(&proof, Some(&identity.public_key_base64())).unwrap();
+
+        let mut forged = proof.clone();
+        forged.entry.verdict = "Rejected".to_string();
+        assert!(verify_proof(&forged, None).is_err());
+        let stranger = SigningIdentity::generate().public_key_base64();
+        assert!(verify_proof(&proof, Some(&stranger)).unwrap_err().contains("not the trusted key"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_rewritten_history() {
+        let pool = ledger_pool().await;
+        let identity = SigningIdentity::generate();
+        finalise(&pool, "d1", "Approved").await;
+        finalise(&pool, "d2", "Rejected").await;
+        checkpoint(&pool, &identity).await.unwrap();
+        assert!(verify(&pool, None).await.unwrap().valid);
+
+        // The ledger itself refuses edits
+        assert!(sqlx::query("UPDATE verdict_ledger SET verdict = 'Approved' WHERE seq = 2")
+            .execute(&pool)
+            .await
+            .is_err());
+
+        // Editing the knowledge bank directly is caught
+        sqlx::query("UPDATE deliberations SET consensus = 'Approved' WHERE id = 'd2'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let report = verify(&pool, None).await.unwrap();
+        assert!(!report.valid);
+        assert!(report.problems[0].contains("changed outside the ledger"));
+
+        // So is rewriting the chain itself, even with recomputed hashes
+        sqlx::query("DROP TRIGGER verdict_ledger_no_update").execute(&pool).await.unwrap();
+        let mut entry = latest_entry(&pool, "d2").await.unwrap().unwrap();
+        entry.verdict = "Approved".to_string();
+        entry.payload_hash = entry.compute_payload_hash();
+        entry.entry_hash = chain_hash(&entry.prev_hash, &entry.payload_hash);
+        sqlx::query("UPDATE verdict_ledger SET verdict = ?, payload_hash = ?, entry_hash = ? WHERE seq = 2")
+            .bind(&entry.verdict)
+            .bind(&entry.payload_hash)
+            .bind(&entry.entry_hash)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let report = verify(&pool, None).await.unwrap();
+        assert_eq!(report.problems, vec!["History up to entry 2 was rewritten after it was checkpointed"]);
+    }
+}
@@ -0,0 +1,205 @@
+// Lexical index - FTS5 full-text search (BM25) and reciprocal rank fusion
+//
+// External-content FTS5 tables (migration 0002) mirror `text_chunks`,
+// `deliberations`, `chat_logs` and `document_chunks` through triggers, so
+// exact identifiers, names and numbers are searchable without embeddings.
+
+use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Rank constant from the original RRF paper (Cormack et al., 2009)
+pub const RRF_K: f32 = 60.0;
+
+/// Which table a full-text hit comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexicalSource {
+    /// `text_chunks.text`
+    Chunks,
+    /// `deliberations.question` and `deliberations.consensus`
+    Deliberations,
+    /// `chat_logs.content`, scoped by channel
+    Chat,
+    /// `document_chunks.heading` and `document_chunks.text`
+    Documents,
+}
+
+impl LexicalSource {
+    fn fts_table(self) -> &'static str {
+        match self {
+            LexicalSource::Chunks => "fts_chunks",
+            LexicalSource::Deliberations => "fts_deliberations",
+            LexicalSource::Chat => "fts_chat",
+            LexicalSource::Documents => "fts_documents",
+        }
+    }
+
+    fn content_table(self) -> &'static str {
+        match self {
+            LexicalSource::Chunks => "text_chunks",
+            LexicalSource::Deliberations => "deliberations",
+            LexicalSource::Chat => "chat_logs",
+            LexicalSource::Documents => "document_chunks",
+        }
+    }
+
+    /// Column of the content table holding the channel, if results can be scoped
+    fn scope_column(self) -> Option<&'static str> {
+        match self {
+            LexicalSource::Chat => Some("channel"),
+            _ => None,
+        }
+    }
+
+    /// Extra condition on the content row `c` (chat cleared by a context reset is hidden)
+    fn visible_sql(self) -> &'static str {
+        match self {
+            LexicalSource::Chat => " AND c.context_cleared = 0",
+            _ => "",
+        }
+    }
+}
+
+/// Turn free text into an FTS5 query: each word is quoted (so `-`, `:` and
+/// friends are not operators), multi-token words like `CVE-2024-1234` become
+/// phrases, and words are OR-ed so BM25 ranks partial matches too
+pub fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .filter_map(|word| {
+            let tokens: Vec<&str> = word
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|t| !t.is_empty())
+                .collect();
+            (!tokens.is_empty()).then(|| format!("\"{}\"", tokens.join(" ")))
+        })
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+/// BM25 search; returns content-table ids, best match first
+pub async fn search(
+    pool: &SqlitePool,
+    source: LexicalSource,
+    text: &str,
+    limit: usize,
+    scope: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let Some(query) = fts_query(text) else {
+        return Ok(Vec::new());
+    };
+
+    let fts = source.fts_table();
+    let filter = match (scope, source.scope_column()) {
+        (Some(_), Some(column)) => format!(" AND c.{} = ?", column),
+        _ => String::new(),
+    };
+    let sql = format!(
+        "SELECT c.id FROM {fts} JOIN {content} c ON c.rowid = {fts}.rowid \
+         WHERE {fts} MATCH ?{filter}{visible} \
+         ORDER BY bm25({fts}) LIMIT ?",
+        content = source.content_table(),
+        visible = source.visible_sql()
+    );
+
+    let mut q = sqlx::query(&sql).bind(query);
+    if let (Some(scope), Some(_)) = (scope, source.scope_column()) {
+        q = q.bind(scope);
+    }
+    let rows = q
+        .bind(limit as i64)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Full-text search on {} failed: {}", fts, e))?;
+
+    Ok(rows.iter().map(|r| r.get("id")).collect())
+}
+
+/// Fuse several rankings (best first) into one: score = Σ 1 / (k + rank)
+pub fn reciprocal_rank_fusion<T: Clone + Eq + Hash>(rankings: &[Vec<T>]) -> Vec<(T, f32)> {
+    let mut scores: HashMap<T, f32> = HashMap::new();
+    let mut order: Vec<T> = Vec::new();
+    for ranking in rankings {
+        for (rank, item) in ranking.iter().enumerate() {
+            let score = scores.entry(item.clone()).or_insert_with(|| {
+                order.push(item.clone());
+                0.0
+            });
+            *score += 1.0 / (RRF_K + rank as f32 + 1.0);
+        }
+    }
+
+    // Stable on ties: earlier rankings win
+    let mut fused: Vec<(T, f32)> = order
+        .into_iter()
+        .map(|item| {
+            let score = scores[&item];
+            (item, score)
+        })
+        .collect();
+    fused.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    fused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(fts_query("--"), None);
+        assert_eq!(
+            fts_query("verdict on CVE-2024-1234?"),
+            Some("\"verdict\" OR \"on\" OR \"CVE 2024 1234\"".to_string())
+        );
+        assert_eq!(fts_query("NOT \"x\""), Some("\"NOT\" OR \"x\"".to_string()));
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let fused = reciprocal_rank_fusion(&[vec!["a", "b", "c"], vec!["c", "b"]]);
+        let order: Vec<&str> = fused.iter().map(|(item, _)| *item).collect();
+        assert_eq!(order, vec!["c", "b", "a"]);
+        assert!((fused[2].1 - 1.0 / 61.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_triggers_keep_index_in_sync() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::schema::MIGRATOR.run(&pool).await.unwrap();
+        for sql in [
+            "INSERT INTO deliberations VALUES ('d1', 'Ticket review', NULL, 0, 1)",
+            "INSERT INTO text_chunks VALUES ('old', 'd1', 'ticket ABC-123 was approved', 'text', NULL)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        let hits = search(&pool, LexicalSource::Chunks, "ABC-123", 10, None).await.unwrap();
+        assert_eq!(hits, vec!["old"]);
+
+        sqlx::query("INSERT OR REPLACE INTO text_chunks VALUES ('old', 'd1', 'ticket XYZ-9 was rejected', 'text', NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search(&pool, LexicalSource::Chunks, "ABC-123", 10, None).await.unwrap().is_empty());
+        assert_eq!(search(&pool, LexicalSource::Chunks, "XYZ-9", 10, None).await.unwrap(), vec!["old"]);
+
+        for (id, channel) in [("m1", "general"), ("m2", "topic")] {
+            sqlx::query("INSERT INTO chat_logs (id, channel, author, author_type, content, timestamp) VALUES (?, ?, 'a', 'Human', 'budget 2027 approved', 0)")
+                .bind(id)
+                .bind(channel)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let hits = search(&pool, LexicalSource::Chat, "budget", 10, Some("topic")).await.unwrap();
+        assert_eq!(hits, vec!["m2"]);
+
+        sqlx::query("UPDATE chat_logs SET context_cleared = 1 WHERE id = 'm2'").execute(&pool).await.unwrap();
+        assert!(search(&pool, LexicalSource::Chat, "budget", 10, Some("topic")).await.unwrap().is_empty());
+
+        sqlx::query("DELETE FROM chat_logs WHERE id = 'm1'").execute(&pool).await.unwrap();
+        assert!(search(&pool, LexicalSource::Chat, "budget", 10, None).await.unwrap().is_empty());
+    }
+}
@@ -1,29 +1,43 @@
 pub mod agents;
+mod bundle;
 mod chat;
+mod citations;
 pub mod chat_bot;
 pub mod config;
+mod contradiction;
 pub mod council;
-mod crypto;
+pub mod crypto;
 mod deliberation;
+mod embedding_queue;
 mod http_server;
+mod ingest;
 mod knowledge;
+mod lexical_index;
+pub mod ledger;
 mod logger;
 mod mcp;
 mod metrics;
+mod namespaces;
 mod ollama;
+mod ollama_models;
 mod p2p;
 mod p2p_manager;
 mod personalities;
 mod pohv;
 pub mod prompt;
 mod protocol;
+mod reembed;
+mod retention;
 mod benchmarks;
 mod providers;
 pub mod provider_dispatch;
 pub mod reputation;
+mod schema;
+pub mod secrets;
 pub mod state;
 pub mod web_server;
 pub mod topic_manager;
+mod vector_index;
 pub mod constitution;
 
 #[cfg(test)]
@@ -129,22 +143,109 @@ async fn ask_ollama(question: String, state: tauri::State<'_, AppState>) -> Resu
 #[tauri::command]
 fn get_config(state: tauri::State<'_, AppState>) -> AppConfig {
     state.log_debug("get_config", "Fetching config");
-    state.get_config()
+    state.get_config().redacted()
 }
 
 #[tauri::command]
-fn save_config(config: AppConfig, state: tauri::State<'_, AppState>) -> Result<(), String> {
+fn save_config(mut config: AppConfig, state: tauri::State<'_, AppState>) -> Result<(), String> {
     state.log_info("save_config", "Saving configuration");
+
+    // Keys come back redacted; new plaintext keys go into the secret store when it is unlocked
+    config.restore_redacted(&state.get_config());
+    let secrets = secrets::global();
+    if secrets.is_unlocked() {
+        config.seal_api_keys(secrets)?;
+    }
     
     // Update in-memory state
     state.update_config(|c| {
         *c = config.clone();
     });
+    // Agent and channel assignments apply now; new namespaces open via namespace_create or on restart
+    state.namespaces.configure(&config.namespaces);
     
     // Persist to disk
     config.save().map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn secrets_status(state: tauri::State<'_, AppState>) -> secrets::SecretStoreStatus {
+    state.log_debug("secrets", "Fetching secret store status");
+    secrets::global().status()
+}
+
+/// Unlock (or create) the secret store, then move any plaintext API keys into it
+#[tauri::command]
+fn secrets_unlock(
+    passphrase: Option<String>,
+    key_file: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    let store = secrets::global();
+    match (passphrase, key_file) {
+        (Some(passphrase), _) => store.unlock_with_passphrase(&passphrase)?,
+        (None, Some(path)) => store.unlock_with_key_file(std::path::Path::new(&path))?,
+        (None, None) => return Err("Passphrase or key file required".to_string()),
+    }
+
+    let mut config = state.get_config();
+    let moved = config.seal_api_keys(store)?;
+    if moved > 0 {
+        state.update_config(|c| *c = config.clone());
+        // Persist to disk so the plaintext keys don't come back on restart
+        config.save()?;
+    }
+    state.log_success("secrets", &format!("🔓 Secret store unlocked ({} key(s) moved in)", moved));
+    Ok(moved)
+}
+
+#[tauri::command]
+fn secrets_lock(state: tauri::State<'_, AppState>) {
+    secrets::global().lock();
+    state.log_info("secrets", "🔒 Secret store locked");
+}
+
+#[tauri::command]
+fn secrets_list() -> Result<Vec<secrets::SecretInfo>, String> {
+    secrets::global().list()
+}
+
+/// Create or rotate a named secret; returns its `secret:<name>` reference
+#[tauri::command]
+fn secret_set(name: String, value: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let reference = secrets::global().set(&name, &value)?;
+    state.log_success("secrets", &format!("🔐 Stored secret {}", name));
+    Ok(reference)
+}
+
+#[tauri::command]
+fn secret_remove(name: String, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    let removed = secrets::global().remove(&name)?;
+    if removed {
+        state.log_info("secrets", &format!("🗑️ Removed secret {}", name));
+    }
+    Ok(removed)
+}
+
+/// Set or rotate a provider API key without editing app_config.json
+#[tauri::command]
+fn api_key_set(provider: String, value: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let mut config = state.get_config();
+    let reference = config.set_api_key(&provider, &value, secrets::global())?;
+    state.update_config(|c| *c = config.clone());
+    // Persist to disk
+    config.save()?;
+    state.log_success("secrets", &format!("🔐 {} API key updated", provider));
+    Ok(reference)
+}
+
+#[tauri::command]
+fn secrets_generate_key_file(path: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    secrets::generate_key_file(std::path::Path::new(&path))?;
+    state.log_success("secrets", &format!("🔑 Key file written to {}", path));
+    Ok(())
+}
+
 #[tauri::command]
 fn set_debug(enabled: bool, state: tauri::State<'_, AppState>) {
     state.update_config(|config| {
@@ -210,12 +311,17 @@ async fn p2p_status(
 async fn council_create_session(
     state: tauri::State<'_, AppState>,
     question: String,
+    attachments: Option<Vec<providers::Attachment>>,
+    namespace: Option<String>,
 ) -> Result<String, String> {
     state.log_info(
         "council_create_session",
         &format!("Creating session: {}", question),
     );
-    let session_id = state.council_manager.create_session(question).await;
+    let session_id = state
+        .council_manager
+        .create_session_in_namespace(question, attachments.unwrap_or_default(), namespace)
+        .await?;
     state.log_success(
         "council_create_session",
         &format!("Session created: {}", session_id),
@@ -228,6 +334,9 @@ async fn council_create_session_with_agents(
     state: tauri::State<'_, AppState>,
     question: String,
     agent_ids: Vec<String>,
+    attachments: Option<Vec<providers::Attachment>>,
+    namespace: Option<String>,
+    bypass_cache: Option<bool>,
 ) -> Result<String, String> {
     state.log_info(
         "council_agents",
@@ -242,8 +351,11 @@ async fn council_create_session_with_agents(
 
     let session_id = state
         .council_manager
-        .create_session_with_agents(
+        .create_session_with_agents_and_attachments(
             question,
+            attachments.unwrap_or_default(),
+            namespace,
+            bypass_cache.unwrap_or(false),
             state.agent_pool.clone(),
             agent_ids,
             &config.ollama_url,
@@ -277,9 +389,13 @@ async fn council_get_session(
 #[tauri::command]
 async fn council_list_sessions(
     state: tauri::State<'_, AppState>,
+    namespace: Option<String>,
 ) -> Result<Vec<protocol::CouncilSession>, String> {
     state.log_debug("council_list_sessions", "Listing all sessions");
-    Ok(state.council_manager.list_sessions().await)
+    Ok(match namespace {
+        Some(namespace) => state.council_manager.list_sessions_in(Some(namespace.as_str())).await,
+        None => state.council_manager.list_sessions().await,
+    })
 }
 
 #[tauri::command]
@@ -377,6 +493,22 @@ async fn council_calculate_consensus(
                  }
              }
         }
+
+        // Compare with similar earlier verdicts; contradictions are announced in #knowledge
+        if let Some(detector) = &state.contradiction_detector {
+            if let Some(session) = state.council_manager.get_session(&session_id).await {
+                if let Ok(kb) = state.namespaces.get(session.namespace.as_deref()) {
+                    detector.spawn_review(
+                        kb,
+                        session.namespace,
+                        state.get_config(),
+                        session.id,
+                        session.question,
+                        consensus.clone(),
+                    );
+                }
+            }
+        }
     } else {
         state.log_info("council_consensus", "No consensus reached yet");
     }
@@ -471,10 +603,10 @@ async fn start_deliberation(
 
     // Create deliberation engine
     let config = state.get_config();
-    let ollama_client = Arc::new(Mutex::new(ollama::OllamaClient::new(
-        config.clone(),
-        state.logger.clone(),
-    )));
+    let ollama_client = Arc::new(Mutex::new(
+        ollama::OllamaClient::new(config.clone(), state.logger.clone())
+            .with_scheduler(state.scheduler.clone()),
+    ));
 
     let engine = deliberation::DeliberationEngine::new(state.logger.clone(), ollama_client);
 
@@ -544,6 +676,7 @@ async fn create_custom_council(
 #[tauri::command]
 async fn kb_store_deliberation(
     result: deliberation::DeliberationResult,
+    namespace: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
     state.log_info(
@@ -551,64 +684,437 @@ async fn kb_store_deliberation(
         &format!("Storing deliberation: {}", result.session_id),
     );
 
-    if let Some(kb) = &state.knowledge_bank {
-        kb.store_deliberation(&result).await?;
-        state.log_success("kb_store", "Deliberation stored with embeddings");
-        Ok("Stored successfully".to_string())
-    } else {
-        Err("Knowledge bank not initialized".to_string())
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.store_deliberation(&result).await?;
+    state.log_success("kb_store", "Deliberation stored with embeddings");
+
+    if let (true, Some(consensus), Some(detector)) =
+        (result.completed, &result.consensus, &state.contradiction_detector)
+    {
+        detector.spawn_review(
+            kb,
+            namespace,
+            state.get_config(),
+            result.session_id,
+            result.question,
+            consensus.clone(),
+        );
     }
+    Ok("Stored successfully".to_string())
 }
 
 #[tauri::command]
 async fn kb_search(
     query: String,
     limit: usize,
+    mode: Option<knowledge::SearchMode>,
+    namespace: Option<String>,
+    cross_namespace: Option<bool>,
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<knowledge::SearchResult>, String> {
-    state.log_info("kb_search", &format!("Searching: {}", query));
+    let mode = mode.unwrap_or_default();
+    state.log_info("kb_search", &format!("Searching ({:?}): {}", mode, query));
 
-    if let Some(kb) = &state.knowledge_bank {
-        let results = kb.semantic_search(&query, limit).await?;
-        state.log_success("kb_search", &format!("Found {} results", results.len()));
-        Ok(results)
+    // Other councils' debates are only searched when explicitly asked for
+    let results = if cross_namespace.unwrap_or(false) {
+        state.namespaces.search_all(&query, limit, mode).await?
     } else {
-        Err("Knowledge bank not initialized".to_string())
-    }
+        let kb = state.namespaces.get(namespace.as_deref())?;
+        kb.semantic_search(&query, limit, mode).await?
+    };
+    state.log_success("kb_search", &format!("Found {} results", results.len()));
+    Ok(results)
 }
 
 #[tauri::command]
 async fn kb_get_rag_context(
     question: String,
     top_k: usize,
+    mode: Option<knowledge::SearchMode>,
+    namespace: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<knowledge::RAGContext, String> {
     state.log_info("kb_rag", &format!("Building RAG context for: {}", question));
 
-    if let Some(kb) = &state.knowledge_bank {
-        let context = kb.build_rag_context(&question, top_k).await?;
-        state.log_success(
-            "kb_rag",
-            &format!(
-                "Built context with {} decisions",
-                context.relevant_decisions.len()
-            ),
-        );
-        Ok(context)
-    } else {
-        Err("Knowledge bank not initialized".to_string())
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    let context = kb.build_rag_context(&question, top_k, mode.unwrap_or_default()).await?;
+    state.log_success(
+        "kb_rag",
+        &format!(
+            "Built context with {} decisions",
+            context.relevant_decisions.len()
+        ),
+    );
+    Ok(context)
+}
+
+#[tauri::command]
+async fn response_cache_stats(
+    state: tauri::State<'_, AppState>,
+) -> Result<providers::cache::CacheStats, String> {
+    match &state.response_cache {
+        Some(cache) => Ok(cache.stats().await),
+        None => Err("Response cache not enabled".to_string()),
+    }
+}
+
+#[tauri::command]
+fn scheduler_stats(state: tauri::State<'_, AppState>) -> Vec<providers::scheduler::LaneStats> {
+    state.log_debug("scheduler_stats", "Fetching scheduler lanes");
+    state.scheduler.stats()
+}
+
+#[tauri::command]
+fn ollama_pool_status(
+    state: tauri::State<'_, AppState>,
+) -> Vec<providers::ollama_pool::OllamaHostStatus> {
+    state.log_debug("ollama_pool_status", "Fetching Ollama hosts");
+    state.ollama_pool.status()
+}
+
+/// Poll every Ollama host now instead of waiting for the next health check
+#[tauri::command]
+async fn ollama_pool_refresh(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<providers::ollama_pool::OllamaHostStatus>, String> {
+    state.ollama_pool.refresh().await;
+    let status = state.ollama_pool.status();
+    let healthy = status.iter().filter(|h| h.healthy).count();
+    state.log_info(
+        "ollama_pool",
+        &format!("🖧 {}/{} Ollama hosts healthy", healthy, status.len()),
+    );
+    Ok(status)
+}
+
+/// Installed models on one Ollama host, or on every pooled host
+#[tauri::command]
+async fn ollama_models_list(
+    state: tauri::State<'_, AppState>,
+    host: Option<String>,
+) -> Result<Vec<ollama_models::LocalModel>, String> {
+    let hosts = match host {
+        Some(url) => vec![state.ollama_pool.host(Some(&url))?],
+        None => state.ollama_pool.hosts().to_vec(),
+    };
+
+    let mut models = Vec::new();
+    let mut errors = Vec::new();
+    for host in hosts {
+        match ollama_models::list_models(&host).await {
+            Ok(found) => models.extend(found),
+            Err(e) => {
+                state.log_warn("ollama_models", &format!("⚠️ {}: {}", host.url(), e));
+                errors.push(format!("{}: {}", host.url(), e));
+            }
+        }
+    }
+    if models.is_empty() && !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    Ok(models)
+}
+
+/// Download a model; progress is emitted as `ollama-pull-progress` events
+#[tauri::command]
+async fn ollama_model_pull(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    model: String,
+    host: Option<String>,
+) -> Result<(), String> {
+    use tauri::Emitter;
+
+    let host = state.ollama_pool.host(host.as_deref())?;
+    state.log_info("ollama_models", &format!("⬇️ Pulling {} on {}", model, host.url()));
+
+    // Only forward status changes and whole-percent steps
+    let mut last: Option<(String, u32)> = None;
+    ollama_models::pull_model(&host, &model, |progress| {
+        let step = (progress.status.clone(), progress.percent.unwrap_or(0.0) as u32);
+        if last.as_ref() != Some(&step) {
+            let _ = app.emit("ollama-pull-progress", progress.clone());
+            last = Some(step);
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    state.model_catalog.clear();
+    state.ollama_pool.refresh().await;
+    state.log_success("ollama_models", &format!("✅ Pulled {} on {}", model, host.url()));
+    Ok(())
+}
+
+/// Remove a model; refuses models used by active agents unless `force` is set
+#[tauri::command]
+async fn ollama_model_delete(
+    state: tauri::State<'_, AppState>,
+    model: String,
+    host: Option<String>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let host = state.ollama_pool.host(host.as_deref())?;
+
+    if !force.unwrap_or(false) {
+        let target = providers::ollama_pool::normalize_model(&model);
+        let users: Vec<String> = state
+            .agent_pool
+            .list_active_agents()
+            .await
+            .into_iter()
+            .filter(|a| {
+                a.provider.eq_ignore_ascii_case("ollama")
+                    && providers::ollama_pool::normalize_model(&a.model) == target
+            })
+            .map(|a| a.name)
+            .collect();
+        if !users.is_empty() {
+            return Err(format!("{} is used by active agents: {}", model, users.join(", ")));
+        }
+    }
+
+    ollama_models::delete_model(&host, &model)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    state.model_catalog.clear();
+    state.model_warmer.reset();
+    state.ollama_pool.refresh().await;
+    state.log_info("ollama_models", &format!("🗑️ Deleted {} from {}", model, host.url()));
+    Ok(())
+}
+
+/// Load models now (default: every active agent's Ollama model) and extend their keep-alive
+#[tauri::command]
+async fn ollama_models_warm(
+    state: tauri::State<'_, AppState>,
+    models: Option<Vec<String>>,
+) -> Result<Vec<ollama_models::WarmResult>, String> {
+    let models = match models {
+        Some(models) => models,
+        None => ollama_models::agent_models(&state.agent_pool.list_active_agents().await),
+    };
+    Ok(state
+        .model_warmer
+        .warm(&state.ollama_pool, &models, true, &state.logger)
+        .await)
+}
+
+/// Metadata for `model` from the model catalog (cached, fetched on a miss)
+async fn describe_agent_model(
+    state: &AppState,
+    provider: &str,
+    model: &str,
+) -> Result<providers::ModelInfo, String> {
+    let config = state.config.lock().unwrap().clone();
+    let provider_impl =
+        provider_dispatch::build_provider(provider, model, &config, state.logger.clone())?;
+    state
+        .model_catalog
+        .describe(provider, provider_impl.as_ref(), model)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn models_discover(
+    state: tauri::State<'_, AppState>,
+    provider: String,
+) -> Result<Vec<providers::ModelInfo>, String> {
+    let config = state.config.lock().unwrap().clone();
+    let provider_impl =
+        provider_dispatch::build_provider(&provider, "", &config, state.logger.clone())?;
+    let models = state
+        .model_catalog
+        .list(&provider, provider_impl.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+    state.log_info("models", &format!("🔎 {} models available on {}", models.len(), provider));
+    Ok(models)
+}
+
+#[tauri::command]
+async fn model_describe(
+    state: tauri::State<'_, AppState>,
+    provider: String,
+    model: String,
+) -> Result<providers::ModelInfo, String> {
+    describe_agent_model(&state, &provider, &model).await
+}
+
+/// Warnings for an agent whose model is too small or lacks tool calling
+#[tauri::command]
+async fn agent_check_model(
+    state: tauri::State<'_, AppState>,
+    agent_id: String,
+) -> Result<Vec<String>, String> {
+    let agent = state.agent_pool.get_agent(&agent_id).await?;
+    let info = describe_agent_model(&state, &agent.provider, &agent.model).await?;
+    Ok(providers::discovery::check_agent(&agent, &info))
+}
+
+#[tauri::command]
+async fn response_cache_clear(state: tauri::State<'_, AppState>) -> Result<u64, String> {
+    match &state.response_cache {
+        Some(cache) => {
+            let removed = cache.clear().await?;
+            state.log_info("response_cache", &format!("🗑️ Cleared {} cached responses", removed));
+            Ok(removed)
+        }
+        None => Err("Response cache not enabled".to_string()),
+    }
+}
+
+#[tauri::command]
+async fn kb_ingest(path: String, namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<ingest::IngestReport, String> {
+    state.log_info("kb_ingest", &format!("Ingesting: {}", path));
+
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.ingest_path(std::path::Path::new(&path)).await
+}
+
+#[tauri::command]
+async fn kb_list_documents(namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<Vec<knowledge::DocumentInfo>, String> {
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.list_documents().await
+}
+
+#[tauri::command]
+async fn kb_remove_document(id: String, namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.remove_document(&id).await
+}
+
+#[tauri::command]
+async fn kb_export(path: String, namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<bundle::ExportReport, String> {
+    state.log_info("kb_export", &format!("Exporting to: {}", path));
+
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.export_bundle(std::path::Path::new(&path), &state.signing_identity).await
+}
+
+#[tauri::command]
+async fn kb_import(
+    path: String,
+    trusted_key: Option<String>,
+    reembed: Option<bool>,
+    namespace: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<bundle::ImportReport, String> {
+    state.log_info("kb_import", &format!("Importing: {}", path));
+
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.import_bundle(std::path::Path::new(&path), trusted_key.as_deref(), reembed.unwrap_or(true))
+        .await
+}
+
+/// Judged relations between a verdict and similar earlier (or later) verdicts
+#[tauri::command]
+async fn kb_verdict_links(
+    deliberation_id: String,
+    namespace: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<contradiction::VerdictLink>, String> {
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.verdict_links(&deliberation_id).await
+}
+
+/// Configured and open knowledge namespaces
+#[tauri::command]
+fn namespace_list(state: tauri::State<'_, AppState>) -> Vec<namespaces::NamespaceInfo> {
+    state.namespaces.list()
+}
+
+/// Open a namespace's knowledge bank, creating it if it does not exist yet
+#[tauri::command]
+async fn namespace_create(name: String, state: tauri::State<'_, AppState>) -> Result<Vec<namespaces::NamespaceInfo>, String> {
+    state.log_info("namespace_create", &format!("Opening namespace: {}", name));
+    state.namespaces.open(&name).await?;
+    Ok(state.namespaces.list())
+}
+
+/// Clear a channel's RAG context now (the manual "fresh start")
+#[tauri::command]
+async fn kb_clear_channel_context(
+    channel: chat::ChannelType,
+    state: tauri::State<'_, AppState>,
+) -> Result<retention::RetentionEvent, String> {
+    match state.namespaces.for_channel(channel) {
+        Some(kb) => kb.clear_channel_context(channel, retention::Initiator::Manual).await,
+        None => Err("Knowledge bank not initialized".to_string()),
+    }
+}
+
+/// Apply the configured retention policies immediately
+#[tauri::command]
+async fn retention_run(state: tauri::State<'_, AppState>) -> Result<Vec<retention::RetentionEvent>, String> {
+    Ok(retention::run(&state.namespaces, &state.get_config(), retention::Initiator::Manual, &state.logger).await)
+}
+
+/// Logged prunes and context resets, newest first
+#[tauri::command]
+async fn retention_history(
+    channel: Option<String>,
+    limit: Option<usize>,
+    namespace: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<retention::RetentionEvent>, String> {
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.retention_history(channel.as_deref(), limit.unwrap_or(50)).await
+}
+
+#[tauri::command]
+async fn ledger_verify(trusted_key: Option<String>, namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<ledger::LedgerVerification, String> {
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.verify_ledger(trusted_key.as_deref()).await
+}
+
+#[tauri::command]
+async fn ledger_prove(deliberation_id: String, namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<ledger::InclusionProof, String> {
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.prove_verdict(&deliberation_id).await
+}
+
+#[tauri::command]
+async fn ledger_checkpoint(namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<Option<ledger::Checkpoint>, String> {
+    state.log_info("ledger_checkpoint", "Signing ledger head");
+
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.checkpoint_ledger().await
+}
+
+#[tauri::command]
+async fn kb_embedding_queue_status(namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<embedding_queue::QueueStatus, String> {
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.embedding_queue_status().await
+}
+
+#[tauri::command]
+async fn kb_reembed_status(namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<Option<reembed::ReembedJob>, String> {
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.reembed_status().await
+}
+
+#[tauri::command]
+async fn kb_reembed_start(namespace: Option<String>, state: tauri::State<'_, AppState>) -> Result<Option<reembed::ReembedJob>, String> {
+    state.log_info("kb_reembed_start", "Starting re-embedding");
+
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    if kb.is_reembedding() {
+        return Err("Re-embedding already in progress".to_string());
     }
+    kb.spawn_reembedding();
+    kb.reembed_status().await
 }
 
 #[tauri::command]
 async fn kb_list_all(
+    namespace: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<(String, String, bool)>, String> {
-    if let Some(kb) = &state.knowledge_bank {
-        kb.list_all().await
-    } else {
-        Err("Knowledge bank not initialized".to_string())
-    }
+    let kb = state.namespaces.get(namespace.as_deref())?;
+    kb.list_all().await
 }
 
 #[tauri::command]
@@ -662,6 +1168,7 @@ fn chat_send_message(
     author_type: String,
     content: String,
     signature: Option<String>,
+    attachments: Option<Vec<providers::Attachment>>,
     state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
     use chat::{AuthorType, ChannelType, Message};
@@ -676,7 +1183,8 @@ fn chat_send_message(
         _ => return Err(format!("Invalid author type: {}", author_type)),
     };
 
-    let mut message = Message::new(channel_type, author, author_type, content);
+    let mut message = Message::new(channel_type, author, author_type, content)
+        .with_attachments(attachments.unwrap_or_default());
 
     if let Some(sig) = signature {
         message = message.with_signature(sig);
@@ -748,42 +1256,44 @@ fn chat_get_message_count(
 #[tauri::command]
 async fn chat_check_duplicate(
     question: String,
+    namespace: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<chat::DuplicateCheckResult, String> {
     state.log_debug("chat", &format!("🔍 Checking for duplicate: {}", question));
 
-    if let Some(ref duplicate_filter) = state.duplicate_filter {
-        let result = duplicate_filter
-            .check_duplicate(&question)
-            .await
-            .map_err(|e| format!("Failed to check duplicate: {}", e))?;
-
-        if result.is_duplicate {
-            state.log_info(
-                "chat",
-                &format!(
-                    "⛔ Duplicate detected: score={:.2}, session={}",
-                    result.similarity_score,
-                    result
-                        .existing_session_id
-                        .as_ref()
-                        .unwrap_or(&"unknown".to_string())
-                ),
-            );
-        } else if result.similarity_score > 0.70 {
-            state.log_debug(
-                "chat",
-                &format!(
-                    "💡 Related question found: score={:.2}",
-                    result.similarity_score
-                ),
-            );
-        }
+    let kb = state
+        .namespaces
+        .get(namespace.as_deref())
+        .map_err(|e| format!("Duplicate filter not available ({})", e))?;
+    let duplicate_filter = chat::DuplicateFilter::new(kb);
+    let result = duplicate_filter
+        .check_duplicate(&question)
+        .await
+        .map_err(|e| format!("Failed to check duplicate: {}", e))?;
 
-        Ok(result)
-    } else {
-        Err("Duplicate filter not available (Knowledge Bank not initialized)".to_string())
+    if result.is_duplicate {
+        state.log_info(
+            "chat",
+            &format!(
+                "⛔ Duplicate detected: score={:.2}, session={}",
+                result.similarity_score,
+                result
+                    .existing_session_id
+                    .as_ref()
+                    .unwrap_or(&"unknown".to_string())
+            ),
+        );
+    } else if result.similarity_score > 0.70 {
+        state.log_debug(
+            "chat",
+            &format!(
+                "💡 Related question found: score={:.2}",
+                result.similarity_score
+            ),
+        );
     }
+
+    Ok(result)
 }
 
 #[tauri::command]
@@ -906,9 +1416,20 @@ async fn agent_add_full(
         temperature: temperature.unwrap_or(0.7),
         active: true,
         timeout_secs: None, // Use global timeout
+        generation: providers::GenerationParams::default(),
         metadata,
     };
     
+    // Model metadata is advisory: an offline provider must not block setup
+    match describe_agent_model(&state, &agent.provider, &agent.model).await {
+        Ok(info) => {
+            for warning in providers::discovery::check_agent(&agent, &info) {
+                state.log_warn("agent", &format!("⚠️ {}: {}", agent.name, warning));
+            }
+        }
+        Err(e) => state.log_debug("agent", &format!("Model check skipped: {}", e)),
+    }
+
     let agent_id = state.agent_pool.add_agent(agent).await?;
     state.log_success("agent", &format!("Added full agent: {}", agent_id));
     Ok(agent_id)
@@ -970,6 +1491,12 @@ async fn provider_add(
     // Validate config
     providers::config::validate_provider_config(&config)?;
 
+    let mut config = config;
+    let secrets = secrets::global();
+    if secrets.is_unlocked() && config.seal_api_key(secrets)? {
+        state.log_info("provider_add", &format!("🔐 API key for {} stored in secret store", config.id));
+    }
+
     // Load current config
     let config_path = "providers.json";
     let mut providers_config =
@@ -998,7 +1525,7 @@ fn provider_list(
     let providers_config =
         providers::config::ProvidersConfig::load(config_path).unwrap_or_default();
 
-    Ok(providers_config.providers)
+    Ok(providers_config.providers.iter().map(|p| p.redacted()).collect())
 }
 
 #[tauri::command]
@@ -1182,6 +1709,14 @@ pub fn run() {
             set_constitution,
             set_user_handle,
             set_debug,
+            secrets_status,
+            secrets_unlock,
+            secrets_lock,
+            secrets_list,
+            secret_set,
+            secret_remove,
+            api_key_set,
+            secrets_generate_key_file,
             get_metrics,
             p2p_start,
             p2p_stop,
@@ -1205,7 +1740,36 @@ pub fn run() {
             kb_store_deliberation,
             kb_search,
             kb_get_rag_context,
+            response_cache_stats,
+            response_cache_clear,
+            scheduler_stats,
+            ollama_pool_status,
+            ollama_pool_refresh,
+            ollama_models_list,
+            ollama_model_pull,
+            ollama_model_delete,
+            ollama_models_warm,
+            models_discover,
+            model_describe,
+            agent_check_model,
             kb_list_all,
+            kb_ingest,
+            kb_list_documents,
+            kb_remove_document,
+            kb_export,
+            kb_import,
+            kb_verdict_links,
+            kb_clear_channel_context,
+            namespace_list,
+            namespace_create,
+            retention_run,
+            retention_history,
+            ledger_verify,
+            ledger_prove,
+            ledger_checkpoint,
+            kb_embedding_queue_status,
+            kb_reembed_status,
+            kb_reembed_start,
             verdict_list_recent,
             verdict_get,
             pohv_heartbeat,
@@ -8,7 +8,9 @@ use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::Mutex;
 
 use crate::council::CouncilSessionManager;
+use crate::knowledge::SearchMode;
 use crate::logger::Logger;
+use crate::namespaces::NamespaceRegistry;
 
 /// MCP Request from client
 #[derive(Debug, Deserialize)]
@@ -21,7 +23,14 @@ enum McpRequest {
     GetSession { id: u64, params: GetSessionParams },
 
     #[serde(rename = "council/list_sessions")]
-    ListSessions { id: u64 },
+    ListSessions {
+        id: u64,
+        #[serde(default)]
+        params: ListSessionsParams,
+    },
+
+    #[serde(rename = "knowledge/search")]
+    Search { id: u64, params: SearchParams },
 
     #[serde(rename = "tools/list")]
     ListTools { id: u64 },
@@ -33,6 +42,32 @@ struct AskParams {
     #[serde(default)]
     #[allow(dead_code)]
     wait_for_consensus: bool,
+    /// Council (knowledge namespace) to ask; omitted = default
+    #[serde(default)]
+    namespace: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ListSessionsParams {
+    /// Only this namespace's sessions; omitted = all
+    #[serde(default)]
+    namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    query: String,
+    #[serde(default = "default_search_limit")]
+    limit: usize,
+    #[serde(default)]
+    namespace: Option<String>,
+    /// Search every namespace instead of one
+    #[serde(default)]
+    cross_namespace: bool,
+}
+
+fn default_search_limit() -> usize {
+    5
 }
 
 #[derive(Debug, Deserialize)]
@@ -70,6 +105,7 @@ struct McpTool {
 pub struct McpServer {
     port: u16,
     council_manager: Arc<CouncilSessionManager>,
+    namespaces: Option<Arc<NamespaceRegistry>>,
     logger: Arc<Logger>,
     listener: Arc<Mutex<Option<TcpListener>>>,
     #[allow(dead_code)]
@@ -100,12 +136,19 @@ impl McpServer {
         Self {
             port,
             council_manager,
+            namespaces: None,
             logger,
             listener: Arc::new(Mutex::new(None)),
             auth_token: token,
         }
     }
 
+    /// Knowledge banks searched by `knowledge/search`
+    pub fn with_namespaces(mut self, namespaces: Arc<NamespaceRegistry>) -> Self {
+        self.namespaces = Some(namespaces);
+        self
+    }
+
     /// Start MCP server
     pub async fn start(&self) -> Result<String, String> {
         let mut listener_guard = self.listener.lock().await;
@@ -157,11 +200,12 @@ impl McpServer {
                     drop(listener_guard); // Release lock before handling
 
                     let council_manager = self.council_manager.clone();
+                    let namespaces = self.namespaces.clone();
                     let logger = self.logger.clone();
 
                     tokio::spawn(async move {
                         if let Err(e) =
-                            Self::handle_client(stream, council_manager, logger.clone()).await
+                            Self::handle_client(stream, council_manager, namespaces, logger.clone()).await
                         {
                             logger.error("mcp_client", &format!("MCP client error: {}", e));
                         }
@@ -180,6 +224,7 @@ impl McpServer {
     async fn handle_client(
         stream: TcpStream,
         council_manager: Arc<CouncilSessionManager>,
+        namespaces: Option<Arc<NamespaceRegistry>>,
         logger: Arc<Logger>,
     ) -> Result<(), String> {
         let (reader, mut writer) = stream.into_split();
@@ -205,7 +250,13 @@ impl McpServer {
                     // Parse request
                     let response = match serde_json::from_str::<McpRequest>(trimmed) {
                         Ok(req) => {
-                            Self::handle_request(req, council_manager.clone(), logger.clone()).await
+                            Self::handle_request(
+                                req,
+                                council_manager.clone(),
+                                namespaces.clone(),
+                                logger.clone(),
+                            )
+                            .await
                         }
                         Err(e) => McpResponse {
                             jsonrpc: "2.0".to_string(),
@@ -250,15 +301,30 @@ impl McpServer {
     async fn handle_request(
         request: McpRequest,
         council_manager: Arc<CouncilSessionManager>,
+        namespaces: Option<Arc<NamespaceRegistry>>,
         logger: Arc<Logger>,
     ) -> McpResponse {
         match request {
             McpRequest::Ask { id, params } => {
                 logger.info("mcp_handler", &format!("MCP Ask: {}", params.question));
 
-                let session_id = council_manager
-                    .create_session(params.question.clone())
-                    .await;
+                let session_id = match council_manager
+                    .create_session_in_namespace(params.question.clone(), Vec::new(), params.namespace)
+                    .await
+                {
+                    Ok(session_id) => session_id,
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id,
+                            result: None,
+                            error: Some(McpError {
+                                code: -32602,
+                                message: e,
+                            }),
+                        }
+                    }
+                };
 
                 // TODO: If wait_for_consensus, wait for consensus to be reached
                 // For now, just return session ID
@@ -301,10 +367,13 @@ impl McpServer {
                 }
             }
 
-            McpRequest::ListSessions { id } => {
+            McpRequest::ListSessions { id, params } => {
                 logger.debug("mcp_handler", "MCP ListSessions");
 
-                let sessions = council_manager.list_sessions().await;
+                let sessions = match params.namespace {
+                    Some(namespace) => council_manager.list_sessions_in(Some(namespace.as_str())).await,
+                    None => council_manager.list_sessions().await,
+                };
 
                 McpResponse {
                     jsonrpc: "2.0".to_string(),
@@ -314,6 +383,39 @@ impl McpServer {
                 }
             }
 
+            McpRequest::Search { id, params } => {
+                logger.debug("mcp_handler", &format!("MCP Search: {}", params.query));
+
+                let results = match &namespaces {
+                    Some(namespaces) if params.cross_namespace => {
+                        namespaces.search_all(&params.query, params.limit, SearchMode::Hybrid).await
+                    }
+                    Some(namespaces) => match namespaces.get(params.namespace.as_deref()) {
+                        Ok(kb) => kb.semantic_search(&params.query, params.limit, SearchMode::Hybrid).await,
+                        Err(e) => Err(e),
+                    },
+                    None => Err("Knowledge bank not initialized".to_string()),
+                };
+
+                match results {
+                    Ok(results) => McpResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id,
+                        result: Some(serde_json::to_value(&results).unwrap()),
+                        error: None,
+                    },
+                    Err(e) => McpResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id,
+                        result: None,
+                        error: Some(McpError {
+                            code: -32602,
+                            message: e,
+                        }),
+                    },
+                }
+            }
+
             McpRequest::ListTools { id } => {
                 logger.debug("mcp_handler", "MCP ListTools");
 
@@ -328,6 +430,10 @@ impl McpServer {
                                     "type": "string",
                                     "description": "The question to ask the council"
                                 },
+                                "namespace": {
+                                    "type": "string",
+                                    "description": "Council (knowledge namespace) to ask; defaults to the default council"
+                                },
                                 "wait_for_consensus": {
                                     "type": "boolean",
                                     "description": "Wait for consensus to be reached before returning",
@@ -356,7 +462,40 @@ impl McpServer {
                         description: "List all council sessions with their current status.".to_string(),
                         input_schema: serde_json::json!({
                             "type": "object",
-                            "properties": {}
+                            "properties": {
+                                "namespace": {
+                                    "type": "string",
+                                    "description": "Only list this namespace's sessions"
+                                }
+                            }
+                        }),
+                    },
+                    McpTool {
+                        name: "knowledge_search".to_string(),
+                        description: "Search past deliberations, verdicts and documents in one namespace's knowledge bank, or in all of them with cross_namespace.".to_string(),
+                        input_schema: serde_json::json!({
+                            "type": "object",
+                            "properties": {
+                                "query": {
+                                    "type": "string",
+                                    "description": "What to search for"
+                                },
+                                "limit": {
+                                    "type": "integer",
+                                    "description": "Maximum number of results",
+                                    "default": 5
+                                },
+                                "namespace": {
+                                    "type": "string",
+                                    "description": "Namespace to search; defaults to the default council"
+                                },
+                                "cross_namespace": {
+                                    "type": "boolean",
+                                    "description": "Search every namespace (results are tagged with theirs)",
+                                    "default": false
+                                }
+                            },
+                            "required": ["query"]
                         }),
                     },
                 ];
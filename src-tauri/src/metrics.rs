@@ -8,6 +8,12 @@ pub struct PerformanceMetrics {
     pub failed_requests: u64,
     pub average_response_time_ms: f64,
     pub last_request_time_ms: f64,
+    /// Response cache hits (requests answered without calling a provider)
+    #[serde(default)]
+    pub cache_hits: u64,
+    /// Response cache misses
+    #[serde(default)]
+    pub cache_misses: u64,
 }
 
 impl Default for PerformanceMetrics {
@@ -18,6 +24,8 @@ impl Default for PerformanceMetrics {
             failed_requests: 0,
             average_response_time_ms: 0.0,
             last_request_time_ms: 0.0,
+            cache_hits: 0,
+            cache_misses: 0,
         }
     }
 }
@@ -70,6 +78,14 @@ impl MetricsCollector {
         self.metrics.last_request_time_ms = duration_ms;
     }
 
+    pub fn record_cache_hit(&mut self) {
+        self.metrics.cache_hits += 1;
+    }
+
+    pub fn record_cache_miss(&mut self) {
+        self.metrics.cache_misses += 1;
+    }
+
     fn update_average(&mut self) {
         if !self.response_times.is_empty() {
             let sum: f64 = self.response_times.iter().sum();
@@ -0,0 +1,366 @@
+// Namespaces - Separate councils, each with its own knowledge bank
+//
+// A namespace keeps its deliberations, verdicts, chat logs, documents and
+// ledger in its own database (`data/namespaces/<name>/knowledge_bank.sqlite`),
+// so retrieval for one council never sees another's debates. "default" is
+// the original `data/knowledge_bank.sqlite`. `namespaces` in
+// `app_config.json` assigns agents and chat channels to a namespace.
+// Searching several namespaces at once only happens when a caller asks.
+
+use crate::agents::Agent;
+use crate::chat::ChannelType;
+use crate::config::NamespaceConfig;
+use crate::crypto::SigningIdentity;
+use crate::knowledge::{KnowledgeBank, SearchMode, SearchResult};
+use crate::lexical_index;
+use crate::logger::Logger;
+use crate::providers::AIProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// Longest accepted namespace name
+const MAX_NAME_LENGTH: usize = 32;
+
+/// What is needed to open further banks the way the default one was opened
+pub struct BankOpener {
+    /// Parent of `namespaces/<name>/`
+    pub data_dir: PathBuf,
+    pub embedder: Arc<dyn AIProvider>,
+    pub query_embedder: Option<Arc<dyn AIProvider>>,
+    pub fallback_embedder: Option<Arc<dyn AIProvider>>,
+    pub signing_identity: Arc<SigningIdentity>,
+}
+
+/// A namespace as listed to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub agents: Vec<String>,
+    pub channels: Vec<String>,
+    /// Whether its knowledge bank is open
+    pub open: bool,
+}
+
+/// Open knowledge banks by namespace, plus the agent and channel assignments from config
+pub struct NamespaceRegistry {
+    banks: RwLock<HashMap<String, Arc<KnowledgeBank>>>,
+    configs: RwLock<HashMap<String, NamespaceConfig>>,
+    opener: Option<BankOpener>,
+    logger: Arc<Logger>,
+}
+
+/// Lowercase letters, digits, '-' and '_' (used as a directory name)
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.len() > MAX_NAME_LENGTH
+        || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid namespace '{}': use 1-{} lowercase letters, digits, '-' or '_'",
+            name, MAX_NAME_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+impl NamespaceRegistry {
+    /// Registry holding only the default bank (if the knowledge bank is enabled)
+    pub fn new(default_bank: Option<Arc<KnowledgeBank>>, logger: Arc<Logger>) -> Self {
+        let mut banks = HashMap::new();
+        if let Some(bank) = default_bank {
+            banks.insert(DEFAULT_NAMESPACE.to_string(), bank);
+        }
+        Self {
+            banks: RwLock::new(banks),
+            configs: RwLock::new(HashMap::new()),
+            opener: None,
+            logger,
+        }
+    }
+
+    /// Allow opening namespaces other than "default"
+    pub fn with_opener(mut self, opener: BankOpener) -> Self {
+        self.opener = Some(opener);
+        self
+    }
+
+    /// Replace the agent and channel assignments (on startup and config save)
+    pub fn configure(&self, namespaces: &HashMap<String, NamespaceConfig>) {
+        *self.configs.write().unwrap() = namespaces.clone();
+    }
+
+    /// Add an already opened bank
+    pub fn register(&self, name: &str, bank: Arc<KnowledgeBank>) {
+        self.banks.write().unwrap().insert(name.to_string(), bank);
+    }
+
+    /// Open `name`'s bank (creating it on first use) and start its background workers
+    pub async fn open(&self, name: &str) -> Result<Arc<KnowledgeBank>, String> {
+        validate_name(name)?;
+        if let Some(bank) = self.banks.read().unwrap().get(name) {
+            return Ok(bank.clone());
+        }
+        let opener = self
+            .opener
+            .as_ref()
+            .ok_or_else(|| format!("Cannot open namespace '{}': knowledge bank disabled", name))?;
+
+        let dir = opener.data_dir.join("namespaces").join(name);
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create namespace dir: {}", e))?;
+        let url = format!("sqlite://{}?mode=rwc", dir.join("knowledge_bank.sqlite").to_string_lossy());
+
+        let mut bank = KnowledgeBank::new(&url, self.logger.clone(), opener.embedder.clone())
+            .await?
+            .with_signing_identity(opener.signing_identity.clone());
+        if let Some(query_embedder) = &opener.query_embedder {
+            bank = bank.with_query_embedder(query_embedder.clone());
+        }
+        if let Some(fallback) = &opener.fallback_embedder {
+            bank = bank.with_fallback_embedder(fallback.clone());
+        }
+        let bank = Arc::new(bank);
+        bank.start_embedding_worker();
+        bank.spawn_reembedding();
+
+        // Another caller may have opened it meanwhile; keep the first
+        let bank = self.banks.write().unwrap().entry(name.to_string()).or_insert(bank).clone();
+        self.logger.info("namespaces", &format!("📚 Namespace '{}' open", name));
+        Ok(bank)
+    }
+
+    /// Open every configured namespace; failures are logged
+    pub async fn open_configured(&self) {
+        let mut names: Vec<String> = self.configs.read().unwrap().keys().cloned().collect();
+        names.sort();
+        for name in names {
+            if let Err(e) = self.open(&name).await {
+                self.logger.warn("namespaces", &format!("⚠️ Namespace '{}' unavailable: {}", name, e));
+            }
+        }
+    }
+
+    /// Bank of `name` (`None` = default)
+    pub fn get(&self, name: Option<&str>) -> Result<Arc<KnowledgeBank>, String> {
+        let name = name.unwrap_or(DEFAULT_NAMESPACE);
+        match self.banks.read().unwrap().get(name) {
+            Some(bank) => Ok(bank.clone()),
+            None if name == DEFAULT_NAMESPACE => Err("Knowledge bank not initialized".to_string()),
+            None => Err(format!("Unknown namespace '{}'", name)),
+        }
+    }
+
+    /// Open namespaces, sorted by name
+    pub fn open_banks(&self) -> Vec<(String, Arc<KnowledgeBank>)> {
+        let mut banks: Vec<(String, Arc<KnowledgeBank>)> = self
+            .banks
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, bank)| (name.clone(), bank.clone()))
+            .collect();
+        banks.sort_by(|a, b| a.0.cmp(&b.0));
+        banks
+    }
+
+    /// Configured and open namespaces
+    pub fn list(&self) -> Vec<NamespaceInfo> {
+        let configs = self.configs.read().unwrap();
+        let banks = self.banks.read().unwrap();
+        let mut names: Vec<&String> = configs.keys().chain(banks.keys()).collect();
+        names.sort();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| {
+                let config = configs.get(name).cloned().unwrap_or_default();
+                NamespaceInfo {
+                    name: name.clone(),
+                    description: config.description,
+                    agents: config.agents,
+                    channels: config.channels,
+                    open: banks.contains_key(name),
+                }
+            })
+            .collect()
+    }
+
+    /// Namespace that owns `channel` (the first, by name, listing it; else default)
+    pub fn namespace_of_channel(&self, channel: ChannelType) -> String {
+        let configs = self.configs.read().unwrap();
+        let mut owners: Vec<&String> = configs
+            .iter()
+            .filter(|(_, config)| config.channels.iter().any(|c| c == channel.as_str()))
+            .map(|(name, _)| name)
+            .collect();
+        owners.sort();
+        owners.first().map(|name| name.to_string()).unwrap_or_else(|| DEFAULT_NAMESPACE.to_string())
+    }
+
+    /// Bank holding `channel`'s history (`None` if that namespace is not open)
+    pub fn for_channel(&self, channel: ChannelType) -> Option<Arc<KnowledgeBank>> {
+        self.get(Some(self.namespace_of_channel(channel).as_str())).ok()
+    }
+
+    /// The agents of `agents` that sit on `namespace`'s council
+    ///
+    /// A namespace listing agents gets exactly those (by id or name). One that lists none gets
+    /// every agent not listed by another namespace.
+    pub fn members(&self, namespace: Option<&str>, agents: Vec<Agent>) -> Vec<Agent> {
+        let namespace = namespace.unwrap_or(DEFAULT_NAMESPACE);
+        let configs = self.configs.read().unwrap();
+        let matches = |agent: &Agent, wanted: &[String]| {
+            wanted.iter().any(|w| *w == agent.id || w.eq_ignore_ascii_case(&agent.name))
+        };
+
+        match configs.get(namespace).filter(|config| !config.agents.is_empty()) {
+            Some(config) => agents.into_iter().filter(|a| matches(a, &config.agents)).collect(),
+            None => agents
+                .into_iter()
+                .filter(|a| {
+                    !configs
+                        .iter()
+                        .any(|(name, config)| name != namespace && matches(a, &config.agents))
+                })
+                .collect(),
+        }
+    }
+
+    /// Search every open namespace and interleave the results by rank (reciprocal rank fusion)
+    ///
+    /// Hits carry their `namespace`. A namespace whose search fails is skipped with a warning.
+    pub async fn search_all(&self, query: &str, limit: usize, mode: SearchMode) -> Result<Vec<SearchResult>, String> {
+        let mut per_namespace: Vec<Vec<SearchResult>> = Vec::new();
+        for (name, bank) in self.open_banks() {
+            match bank.semantic_search(query, limit, mode).await {
+                Ok(results) => per_namespace.push(
+                    results
+                        .into_iter()
+                        .map(|r| SearchResult { namespace: Some(name.clone()), ..r })
+                        .collect(),
+                ),
+                Err(e) => self.logger.warn("namespaces", &format!("⚠️ Search in '{}' failed: {}", name, e)),
+            }
+        }
+
+        let rankings: Vec<Vec<(usize, usize)>> = per_namespace
+            .iter()
+            .enumerate()
+            .map(|(n, results)| (0..results.len()).map(|i| (n, i)).collect())
+            .collect();
+        Ok(lexical_index::reciprocal_rank_fusion(&rankings)
+            .into_iter()
+            .take(limit)
+            .map(|((n, i), _)| per_namespace[n][i].clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deliberation::DeliberationResult;
+    use crate::providers::LocalEmbeddingProvider;
+
+    async fn bank(logger: &Arc<Logger>) -> Arc<KnowledgeBank> {
+        let embedder = Arc::new(LocalEmbeddingProvider::new(logger.clone()));
+        Arc::new(KnowledgeBank::new("sqlite::memory:", logger.clone(), embedder).await.unwrap())
+    }
+
+    async fn verdict(bank: &KnowledgeBank, id: &str, question: &str, consensus: &str) {
+        bank.store_deliberation(&DeliberationResult {
+            session_id: id.to_string(),
+            question: question.to_string(),
+            rounds: Vec::new(),
+            consensus: Some(consensus.to_string()),
+            completed: true,
+            created_at: 0,
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_channels_and_agents_follow_config() {
+        let registry = NamespaceRegistry::new(None, Arc::new(Logger::new(false)));
+        registry.configure(&HashMap::from([(
+            "infra".to_string(),
+            NamespaceConfig {
+                agents: vec!["Sysadmin".to_string()],
+                channels: vec!["topic".to_string()],
+                ..Default::default()
+            },
+        )]));
+
+        assert_eq!(registry.namespace_of_channel(ChannelType::Topic), "infra");
+        assert_eq!(registry.namespace_of_channel(ChannelType::General), DEFAULT_NAMESPACE);
+
+        let agents = vec![
+            Agent::new("sysadmin".to_string(), "m".to_string(), String::new()),
+            Agent::new("Philosopher".to_string(), "m".to_string(), String::new()),
+        ];
+        let names = |agents: Vec<Agent>| agents.into_iter().map(|a| a.name).collect::<Vec<_>>();
+        assert_eq!(names(registry.members(Some("infra"), agents.clone())), vec!["sysadmin"]);
+        assert_eq!(names(registry.members(None, agents)), vec!["Philosopher"]);
+
+        assert!(validate_name("infra-2").is_ok());
+        assert!(validate_name("../etc").is_err());
+        assert!(registry.get(Some("infra")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_stays_in_namespace_unless_asked() {
+        let logger = Arc::new(Logger::new(false));
+        let default_bank = bank(&logger).await;
+        let infra = bank(&logger).await;
+        verdict(&default_bank, "d1", "Is free will compatible with determinism?", "Yes, compatibilism").await;
+        verdict(&infra, "i1", "Should we migrate the cluster to IPv6?", "Yes, in Q3").await;
+
+        let registry = NamespaceRegistry::new(Some(default_bank), logger);
+        registry.register("infra", infra);
+
+        let infra_hits = registry
+            .get(Some("infra"))
+            .unwrap()
+            .semantic_search("cluster IPv6", 5, SearchMode::Lexical)
+            .await
+            .unwrap();
+        assert_eq!(infra_hits.len(), 1);
+        assert!(registry
+            .get(None)
+            .unwrap()
+            .semantic_search("cluster IPv6", 5, SearchMode::Lexical)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let all = registry.search_all("Yes", 5, SearchMode::Lexical).await.unwrap();
+        let mut namespaces: Vec<String> = all.into_iter().filter_map(|r| r.namespace).collect();
+        namespaces.sort();
+        namespaces.dedup();
+        assert_eq!(namespaces, vec!["default".to_string(), "infra".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_open_creates_bank_on_first_use() {
+        let logger = Arc::new(Logger::new(false));
+        let data_dir = std::env::temp_dir().join(format!("namespaces-{}", uuid::Uuid::new_v4()));
+        let registry = NamespaceRegistry::new(None, logger.clone()).with_opener(BankOpener {
+            data_dir: data_dir.clone(),
+            embedder: Arc::new(LocalEmbeddingProvider::new(logger)),
+            query_embedder: None,
+            fallback_embedder: None,
+            signing_identity: Arc::new(SigningIdentity::generate()),
+        });
+
+        let bank = registry.open("team-a").await.unwrap();
+        verdict(&bank, "t1", "Should team A adopt trunk-based development?", "Yes").await;
+        assert!(data_dir.join("namespaces/team-a/knowledge_bank.sqlite").exists());
+        assert!(registry.list().iter().any(|n| n.name == "team-a" && n.open));
+        let _ = std::fs::remove_dir_all(data_dir);
+    }
+}
@@ -1,5 +1,11 @@
 use crate::config::AppConfig;
 use crate::logger::Logger;
+use crate::providers::retry::{self, retry_with_backoff, RetryOutcome, RetryPolicy};
+use crate::providers::scheduler::{lane_for, SchedulerSlot};
+use crate::providers::ollama as provider_ollama;
+use crate::providers::ollama_pool::OllamaPool;
+use crate::providers::reasoning::split_reasoning;
+use crate::providers::{GenerationRequest, ProviderError, RequestPriority, RequestScheduler};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json;
@@ -13,6 +19,12 @@ pub struct OllamaRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub system: Option<String>,
     pub stream: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,11 +47,18 @@ pub struct OllamaClient {
     config: AppConfig,
     #[allow(dead_code)]
     logger: Arc<Logger>,
+    scheduler: Option<Arc<RequestScheduler>>,
 }
 
 impl OllamaClient {
     pub fn new(config: AppConfig, logger: Arc<Logger>) -> Self {
-        Self { config, logger }
+        Self { config, logger, scheduler: None }
+    }
+
+    /// Queue requests in the shared scheduler (council priority)
+    pub fn with_scheduler(mut self, scheduler: Arc<RequestScheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
     }
 
     pub async fn ask(&self, model: &str, prompt: &str, system: Option<&str>) -> Result<String, String> {
@@ -47,11 +66,23 @@ impl OllamaClient {
         let auth = self.config.ollama_username.as_ref().map(|u| {
             (u.as_str(), self.config.ollama_password.as_deref().unwrap_or(""))
         });
-        ask_ollama_with_auth(&self.config.ollama_url, model, prompt.to_string(), system.map(|s| s.to_string()), auth).await
+        let slot = self.scheduler.as_ref().map(|scheduler| {
+            SchedulerSlot::new(
+                scheduler.clone(),
+                lane_for("ollama", &self.config.ollama_url),
+                RequestPriority::Council,
+                "deliberation",
+            )
+        });
+        let body = plain_body(model, prompt.to_string(), system.map(|s| s.to_string()));
+        run_with_retry(&self.config.ollama_url, body, auth, None, slot.as_ref())
+            .await
+            .result
+            .map_err(|e| e.to_string())
     }
 }
 
-/// Internal function for HTTP API use
+/// Internal function for HTTP API use, routed over the host pool (answer only; `<think>` traces are dropped)
 pub async fn ask_ollama_internal(
     state: &crate::state::AppState,
     model: String,
@@ -59,44 +90,162 @@ pub async fn ask_ollama_internal(
     system: Option<String>,
 ) -> Result<String, String> {
     let config = state.get_config();
-    // Ollama Guardian uses username-only auth (app name), password is optional
-    let auth = config.ollama_username.as_ref().map(|u| {
-        (u.as_str(), config.ollama_password.as_deref().unwrap_or(""))
-    });
-    ask_ollama_with_auth(&config.ollama_url, &model, prompt, system, auth).await
+    let slot = SchedulerSlot::new(
+        state.scheduler.clone(),
+        lane_for("ollama", &config.ollama_url),
+        RequestPriority::Interactive,
+        &config.user_handle,
+    );
+    run_pooled(&state.ollama_pool, plain_body(&model, prompt, system), None, Some(&slot))
+        .await
+        .result
+        .map(|text| split_reasoning(&text).answer)
+        .map_err(|e| e.to_string())
 }
 
+/// One-off question (answer only; `<think>` traces are dropped)
 pub async fn ask_ollama(url: &str, model: &str, prompt: String) -> Result<String, String> {
-    ask_ollama_with_timeout(url, model, prompt, None, None, None).await
+    ask_ollama_with_timeout(url, model, prompt, None, None, None)
+        .await
+        .map(|text| split_reasoning(&text).answer)
 }
 
-pub async fn ask_ollama_with_auth(
+/// Ask Ollama with custom timeout (for slow models like deepseek-r1)
+///
+/// Transient failures are retried with backoff, bounded by the same timeout.
+pub async fn ask_ollama_with_timeout(
     url: &str,
     model: &str,
     prompt: String,
     system: Option<String>,
     basic_auth: Option<(&str, &str)>,
+    timeout_secs: Option<u64>,
 ) -> Result<String, String> {
-    ask_ollama_with_timeout(url, model, prompt, system, basic_auth, None).await
+    run_with_retry(url, plain_body(model, prompt, system), basic_auth, timeout_secs, None)
+        .await
+        .result
+        .map_err(|e| e.to_string())
 }
 
-/// Ask Ollama with custom timeout (for slow models like deepseek-r1)
-pub async fn ask_ollama_with_timeout(
+/// Ask Ollama with the request's sampling options, reporting how many retries were needed
+///
+/// With a `slot`, every attempt waits for its own scheduler permit.
+pub async fn ask_ollama_with_retry(
     url: &str,
-    model: &str,
-    prompt: String,
-    system: Option<String>,
+    request: &GenerationRequest,
     basic_auth: Option<(&str, &str)>,
     timeout_secs: Option<u64>,
-) -> Result<String, String> {
+    slot: Option<&SchedulerSlot>,
+) -> RetryOutcome<String> {
+    run_with_retry(url, request_body(request), basic_auth, timeout_secs, slot).await
+}
+
+/// Ask the least-loaded pooled host that serves the model, failing over between hosts
+///
+/// Every retry picks a host again, so a dropped server costs one attempt
+/// instead of the whole timeout budget. With a `slot`, each attempt queues on
+/// the lane of the host it was routed to, so every host keeps its own limit.
+pub async fn ask_ollama_pooled(
+    pool: &OllamaPool,
+    request: &GenerationRequest,
+    timeout_secs: Option<u64>,
+    slot: Option<&SchedulerSlot>,
+) -> RetryOutcome<String> {
+    run_pooled(pool, request_body(request), timeout_secs, slot).await
+}
+
+/// `ask_ollama_pooled` for a prepared request body
+async fn run_pooled(
+    pool: &OllamaPool,
+    body: OllamaRequest,
+    timeout_secs: Option<u64>,
+    slot: Option<&SchedulerSlot>,
+) -> RetryOutcome<String> {
     let timeout = timeout_secs.unwrap_or(OLLAMA_DEFAULT_TIMEOUT_SECS);
-    
-    println!("🔍 [DEBUG] Asking Ollama: {}", prompt);
+    let policy = RetryPolicy::with_budget_secs(timeout);
+
+    retry_with_backoff(&policy, None, &format!("ollama:{}", body.model), || async {
+        let lease = pool
+            .acquire(&body.model)
+            .ok_or_else(|| ProviderError::InternalError("❌ No Ollama hosts configured".to_string()))?;
+        let host = lease.host();
+        let _permit = match slot {
+            Some(slot) => Some(slot.acquire_on(&lane_for("ollama", host.url())).await),
+            None => None,
+        };
+        let result = try_ask_ollama(host.url(), &body, host.auth(), timeout).await;
+        match &result {
+            Ok(_) => pool.mark_success(host),
+            Err(ProviderError::NetworkError(message)) => pool.mark_failure(host, message),
+            Err(_) => {}
+        }
+        result
+    })
+    .await
+}
+
+/// Prompt-only request with the model's default options
+fn plain_body(model: &str, prompt: String, system: Option<String>) -> OllamaRequest {
+    OllamaRequest {
+        model: model.to_string(),
+        prompt,
+        system,
+        stream: false,
+        options: None,
+        format: None,
+        images: Vec::new(),
+    }
+}
+
+fn request_body(request: &GenerationRequest) -> OllamaRequest {
+    OllamaRequest {
+        model: request.model.clone(),
+        prompt: request.prompt.clone(),
+        system: request.system_prompt.clone(),
+        stream: false,
+        options: Some(provider_ollama::request_options(request)),
+        format: provider_ollama::request_format(&request.params.response_format),
+        images: provider_ollama::request_images(request),
+    }
+}
+
+/// Run a generate call through the shared retry layer, bounded by the timeout
+async fn run_with_retry(
+    url: &str,
+    body: OllamaRequest,
+    basic_auth: Option<(&str, &str)>,
+    timeout_secs: Option<u64>,
+    slot: Option<&SchedulerSlot>,
+) -> RetryOutcome<String> {
+    let timeout = timeout_secs.unwrap_or(OLLAMA_DEFAULT_TIMEOUT_SECS);
+    let policy = RetryPolicy::with_budget_secs(timeout);
+
+    retry_with_backoff(&policy, None, &format!("ollama:{}", body.model), || async {
+        let _permit = match slot {
+            Some(slot) => Some(slot.acquire().await),
+            None => None,
+        };
+        try_ask_ollama(url, &body, basic_auth, timeout).await
+    })
+    .await
+}
+
+/// Single Ollama generate attempt (no retries)
+async fn try_ask_ollama(
+    url: &str,
+    body: &OllamaRequest,
+    basic_auth: Option<(&str, &str)>,
+    timeout: u64,
+) -> Result<String, ProviderError> {
+    let model = body.model.as_str();
+    println!("🔍 [DEBUG] Asking Ollama: {}", body.prompt);
     println!("📡 [DEBUG] URL: {}, Model: {}, Timeout: {}s", url, model, timeout);
 
     let base_url = url.trim_end_matches('/');
-    let client = build_http_client_with_timeout(timeout)?;
-    let resolved_model = resolve_model(&client, base_url, model, basic_auth).await?;
+    let client = build_http_client_with_timeout(timeout).map_err(ProviderError::InternalError)?;
+    let resolved_model = resolve_model(&client, base_url, model, basic_auth)
+        .await
+        .map_err(ProviderError::NetworkError)?;
 
     if resolved_model != model {
         println!(
@@ -108,9 +257,7 @@ pub async fn ask_ollama_with_timeout(
     let endpoint = format!("{}/api/generate", base_url);
     let request_body = OllamaRequest {
         model: resolved_model.clone(),
-        prompt,
-        system,
-        stream: false,
+        ..body.clone()
     };
 
     let mut request = client.post(&endpoint).json(&request_body);
@@ -126,7 +273,7 @@ pub async fn ask_ollama_with_timeout(
         .send()
         .await
         .map_err(|e| {
-            if e.is_timeout() {
+            ProviderError::NetworkError(if e.is_timeout() {
                 format!(
                     "⏱️ Ollama request timed out after {}s. Model '{}' may need more time. \
                     Set timeout_secs in agent config for slower models.",
@@ -136,44 +283,55 @@ pub async fn ask_ollama_with_timeout(
                 format!("❌ Failed to connect to Ollama at {}: Is Ollama running?", url)
             } else {
                 format!("❌ Ollama request failed: {}", e)
-            }
+            })
         })?;
 
-    if !response.status().is_success() {
-        return Err(format!(
-            "❌ Ollama returned error status: {}",
-            response.status()
-        ));
+    let status = response.status();
+    if status.as_u16() == 429 {
+        return Err(ProviderError::RateLimitError {
+            message: format!("❌ Ollama is busy (model '{}')", resolved_model),
+            retry_after_ms: retry::retry_after_from_headers(response.headers())
+                .map(|d| d.as_millis() as u64),
+        });
+    }
+
+    if !status.is_success() {
+        let message = format!("❌ Ollama returned error status: {}", status);
+        return Err(if status.is_server_error() {
+            ProviderError::NetworkError(message)
+        } else {
+            ProviderError::InvalidRequest(message)
+        });
     }
 
     // First get the raw text to check for errors
     let response_text = response
         .text()
         .await
-        .map_err(|e| format!("❌ Failed to read Ollama response body: {}", e))?;
+        .map_err(|e| ProviderError::NetworkError(format!("❌ Failed to read Ollama response body: {}", e)))?;
     
     // Check for common error responses
     if response_text.contains("Not authenticated") {
-        return Err(format!(
+        return Err(ProviderError::AuthenticationError(format!(
             "❌ Ollama requires authentication. Check ollama_username/ollama_password in config. Model: {}",
             model
-        ));
+        )));
     }
     
     // Try to parse the JSON
     let ollama_response: OllamaResponse = serde_json::from_str(&response_text)
-        .map_err(|e| format!(
+        .map_err(|e| ProviderError::InternalError(format!(
             "❌ Failed to parse Ollama response: {}. Raw: {}",
             e, 
             &response_text[..response_text.len().min(200)]
-        ))?;
+        )))?;
     
     // Check for empty response
     if ollama_response.response.trim().is_empty() {
-        return Err(format!(
+        return Err(ProviderError::InternalError(format!(
             "❌ Model '{}' returned empty response. This model may have crashed or doesn't support this prompt type.",
             model
-        ));
+        )));
     }
 
     println!("✅ [DEBUG] Got response from Ollama!");
@@ -237,7 +395,7 @@ async fn resolve_model(
     Ok(available[0].clone())
 }
 
-async fn fetch_available_models(
+pub(crate) async fn fetch_available_models(
     client: &Client,
     base_url: &str,
     basic_auth: Option<(&str, &str)>,
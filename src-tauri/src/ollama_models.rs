@@ -0,0 +1,466 @@
+// Ollama model lifecycle - List, pull, delete and keep-alive warm-up on Ollama hosts
+
+use crate::agents::Agent;
+use crate::logger::Logger;
+use crate::ollama::OLLAMA_DEFAULT_TIMEOUT_SECS;
+use crate::providers::ollama_pool::{normalize_model, OllamaHost, OllamaPool};
+use crate::providers::ProviderError;
+use crate::state::AppState;
+use crate::topic_manager::TopicStatus;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often the warm-up loop checks for upcoming topic rounds
+const WARMER_TICK_SECS: u64 = 15;
+
+/// A model installed on an Ollama host
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalModel {
+    pub host: String,
+    pub name: String,
+    pub size: u64,
+    pub modified_at: Option<String>,
+    pub digest: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization_level: Option<String>,
+    /// Currently loaded into memory (`/api/ps`)
+    pub loaded: bool,
+    /// When Ollama unloads it again, if loaded
+    pub expires_at: Option<String>,
+}
+
+/// One `/api/pull` status line (sent to the UI as `ollama-pull-progress`)
+#[derive(Debug, Clone, Serialize)]
+pub struct PullProgress {
+    pub host: String,
+    pub model: String,
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+    /// 0-100 for download steps
+    pub percent: Option<f32>,
+}
+
+/// Outcome of warming one model
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmResult {
+    pub model: String,
+    pub host: String,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagModel {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    modified_at: Option<String>,
+    #[serde(default)]
+    digest: Option<String>,
+    #[serde(default)]
+    details: Option<TagDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagDetails {
+    #[serde(default)]
+    parameter_size: Option<String>,
+    #[serde(default)]
+    quantization_level: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PsResponse {
+    #[serde(default)]
+    models: Vec<PsModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PsModel {
+    name: String,
+    #[serde(default)]
+    expires_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullLine {
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    digest: Option<String>,
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    completed: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+fn client(timeout_secs: Option<u64>) -> Result<reqwest::Client, ProviderError> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(secs) = timeout_secs {
+        builder = builder.timeout(Duration::from_secs(secs));
+    }
+    builder
+        .build()
+        .map_err(|e| ProviderError::InternalError(e.to_string()))
+}
+
+fn endpoint(host: &OllamaHost, path: &str) -> String {
+    format!("{}{}", host.url().trim_end_matches('/'), path)
+}
+
+fn with_auth(request: reqwest::RequestBuilder, host: &OllamaHost) -> reqwest::RequestBuilder {
+    match host.auth() {
+        Some((username, password)) => request.basic_auth(username, Some(password)),
+        None => request,
+    }
+}
+
+/// Map a non-success status to the matching provider error
+async fn check_status(
+    response: reqwest::Response,
+    action: &str,
+) -> Result<reqwest::Response, ProviderError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("{} failed ({}): {}", action, status, body.trim());
+    Err(match status.as_u16() {
+        401 | 403 => ProviderError::AuthenticationError(message),
+        404 => ProviderError::ModelNotFound(message),
+        _ if status.is_server_error() => ProviderError::NetworkError(message),
+        _ => ProviderError::InvalidRequest(message),
+    })
+}
+
+fn network_error(e: reqwest::Error) -> ProviderError {
+    ProviderError::NetworkError(e.to_string())
+}
+
+/// Installed models on `host`, marked with what is loaded right now
+pub async fn list_models(host: &OllamaHost) -> Result<Vec<LocalModel>, ProviderError> {
+    let client = client(Some(10))?;
+
+    let tags: TagsResponse = check_status(
+        with_auth(client.get(endpoint(host, "/api/tags")), host)
+            .send()
+            .await
+            .map_err(network_error)?,
+        "List models",
+    )
+    .await?
+    .json()
+    .await
+    .map_err(|e| ProviderError::InternalError(e.to_string()))?;
+
+    // Older Ollama versions have no /api/ps; treat that as "nothing loaded"
+    let loaded: HashMap<String, Option<String>> =
+        match with_auth(client.get(endpoint(host, "/api/ps")), host).send().await {
+            Ok(response) if response.status().is_success() => response
+                .json::<PsResponse>()
+                .await
+                .map(|ps| {
+                    ps.models
+                        .into_iter()
+                        .map(|m| (normalize_model(&m.name), m.expires_at))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => HashMap::new(),
+        };
+
+    Ok(tags
+        .models
+        .into_iter()
+        .map(|m| {
+            let expires_at = loaded.get(&normalize_model(&m.name)).cloned();
+            let details = m.details;
+            LocalModel {
+                host: host.url().to_string(),
+                loaded: expires_at.is_some(),
+                expires_at: expires_at.flatten(),
+                parameter_size: details.as_ref().and_then(|d| d.parameter_size.clone()),
+                quantization_level: details.and_then(|d| d.quantization_level),
+                name: m.name,
+                size: m.size,
+                modified_at: m.modified_at,
+                digest: m.digest,
+            }
+        })
+        .collect())
+}
+
+/// Download `model` to `host`, reporting each status line to `on_progress`
+pub async fn pull_model<F>(host: &OllamaHost, model: &str, mut on_progress: F) -> Result<(), ProviderError>
+where
+    F: FnMut(&PullProgress),
+{
+    // No overall timeout: large models take a long time to download
+    let client = client(None)?;
+    let mut response = check_status(
+        with_auth(client.post(endpoint(host, "/api/pull")), host)
+            .json(&serde_json::json!({ "model": model, "stream": true }))
+            .send()
+            .await
+            .map_err(network_error)?,
+        "Pull",
+    )
+    .await?;
+
+    let mut buffer = Vec::new();
+    let mut last_status = String::new();
+    while let Some(chunk) = response.chunk().await.map_err(network_error)? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed: PullLine = serde_json::from_str(line.trim())
+                .map_err(|e| ProviderError::InternalError(format!("Bad pull status '{}': {}", line.trim(), e)))?;
+            if let Some(error) = parsed.error {
+                return Err(ProviderError::InvalidRequest(format!("Pull {} failed: {}", model, error)));
+            }
+            last_status = parsed.status.clone();
+            on_progress(&PullProgress {
+                host: host.url().to_string(),
+                model: model.to_string(),
+                percent: match (parsed.completed, parsed.total) {
+                    (Some(done), Some(total)) if total > 0 => Some(done as f32 * 100.0 / total as f32),
+                    _ => None,
+                },
+                status: parsed.status,
+                digest: parsed.digest,
+                total: parsed.total,
+                completed: parsed.completed,
+            });
+        }
+    }
+
+    if last_status == "success" {
+        Ok(())
+    } else {
+        Err(ProviderError::NetworkError(format!(
+            "Pull {} ended early (last status: '{}')",
+            model, last_status
+        )))
+    }
+}
+
+/// Remove `model` from `host`
+pub async fn delete_model(host: &OllamaHost, model: &str) -> Result<(), ProviderError> {
+    let client = client(Some(30))?;
+    check_status(
+        with_auth(client.delete(endpoint(host, "/api/delete")), host)
+            .json(&serde_json::json!({ "model": model }))
+            .send()
+            .await
+            .map_err(network_error)?,
+        "Delete",
+    )
+    .await?;
+    Ok(())
+}
+
+/// Load `model` into memory and keep it there for `keep_alive` (Ollama duration, e.g. "30m")
+///
+/// A generate call without a prompt only loads the model; on an already
+/// loaded model it just extends the keep-alive.
+pub async fn warm_model(host: &OllamaHost, model: &str, keep_alive: &str) -> Result<(), ProviderError> {
+    let client = client(Some(OLLAMA_DEFAULT_TIMEOUT_SECS))?;
+    check_status(
+        with_auth(client.post(endpoint(host, "/api/generate")), host)
+            .json(&serde_json::json!({ "model": model, "keep_alive": keep_alive, "stream": false }))
+            .send()
+            .await
+            .map_err(network_error)?,
+        "Warm-up",
+    )
+    .await?;
+    Ok(())
+}
+
+/// Ollama models used by `agents` (deduplicated, in agent order)
+pub fn agent_models(agents: &[Agent]) -> Vec<String> {
+    let mut models: Vec<String> = Vec::new();
+    for agent in agents {
+        if agent.active
+            && agent.provider.eq_ignore_ascii_case("ollama")
+            && !agent.model.is_empty()
+            && !models.iter().any(|m| normalize_model(m) == normalize_model(&agent.model))
+        {
+            models.push(agent.model.clone());
+        }
+    }
+    models
+}
+
+/// Pre-loads agent models shortly before topic rounds so the first reply isn't a cold start
+pub struct ModelWarmer {
+    keep_alive: String,
+    lead: Duration,
+    last_warmed: Mutex<HashMap<String, Instant>>,
+}
+
+impl ModelWarmer {
+    /// `lead`: how long before a topic round to warm (zero disables the loop)
+    pub fn new(keep_alive: String, lead: Duration) -> Self {
+        Self {
+            keep_alive,
+            lead,
+            last_warmed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn keep_alive(&self) -> &str {
+        &self.keep_alive
+    }
+
+    /// Whether the next topic round starts within the lead time
+    pub fn is_due(&self, topic: &TopicStatus) -> bool {
+        !self.lead.is_zero() && topic.is_running && topic.next_run_in_secs <= self.lead.as_secs()
+    }
+
+    /// Warm `models` on the host each would be routed to
+    ///
+    /// Unless `force` is set, models warmed within the lead time are skipped
+    /// (their keep-alive is longer than that).
+    pub async fn warm(
+        &self,
+        pool: &OllamaPool,
+        models: &[String],
+        force: bool,
+        logger: &Logger,
+    ) -> Vec<WarmResult> {
+        let mut results = Vec::new();
+        for model in models {
+            let key = normalize_model(model);
+            if !force {
+                let recent = self
+                    .last_warmed
+                    .lock()
+                    .unwrap()
+                    .get(&key)
+                    .is_some_and(|at| at.elapsed() < self.lead);
+                if recent {
+                    continue;
+                }
+            }
+
+            let Some(lease) = pool.acquire(model) else {
+                break;
+            };
+            let host = lease.host();
+            let started = Instant::now();
+            let result = warm_model(host, model, &self.keep_alive).await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+
+            match &result {
+                Ok(()) => {
+                    self.last_warmed.lock().unwrap().insert(key, Instant::now());
+                    logger.info(
+                        "model_warmer",
+                        &format!("🔥 {} warm on {} ({} ms, keep-alive {})", model, host.url(), elapsed_ms, self.keep_alive),
+                    );
+                }
+                Err(e) => logger.warn(
+                    "model_warmer",
+                    &format!("⚠️ Could not warm {} on {}: {}", model, host.url(), e),
+                ),
+            }
+            results.push(WarmResult {
+                model: model.clone(),
+                host: host.url().to_string(),
+                elapsed_ms,
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+        results
+    }
+
+    /// Forget warm-up times (e.g. after models were pulled or deleted)
+    pub fn reset(&self) {
+        self.last_warmed.lock().unwrap().clear();
+    }
+}
+
+/// Warm active agents' models ahead of every topic round
+pub fn start_model_warmer(app_state: Arc<AppState>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(WARMER_TICK_SECS)).await;
+            if app_state.pohv_system.is_locked() {
+                continue;
+            }
+            if !app_state.model_warmer.is_due(&app_state.topic_manager.get_status()) {
+                continue;
+            }
+            let agents = app_state.agent_pool.list_active_agents().await;
+            let models = agent_models(&agents);
+            app_state
+                .model_warmer
+                .warm(&app_state.ollama_pool, &models, false, &app_state.logger)
+                .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(model: &str, provider: &str, active: bool) -> Agent {
+        let mut agent = Agent::new("A".to_string(), model.to_string(), String::new());
+        agent.provider = provider.to_string();
+        agent.active = active;
+        agent
+    }
+
+    fn topic(is_running: bool, next_run_in_secs: u64) -> TopicStatus {
+        TopicStatus {
+            current_topic: Some("t".to_string()),
+            queue_length: 0,
+            next_run_in_secs,
+            is_running,
+            next_agent: None,
+        }
+    }
+
+    #[test]
+    fn test_agent_models_dedupes_active_ollama_agents() {
+        let agents = vec![
+            agent("deepseek-r1:32b", "ollama", true),
+            agent("llama3", "ollama", true),
+            agent("llama3:latest", "Ollama", true),
+            agent("qwen2.5:7b", "ollama", false),
+            agent("gpt-4o", "openai", true),
+        ];
+        assert_eq!(agent_models(&agents), vec!["deepseek-r1:32b", "llama3"]);
+    }
+
+    #[test]
+    fn test_warmer_due_before_topic_round() {
+        let warmer = ModelWarmer::new("30m".to_string(), Duration::from_secs(120));
+        assert!(warmer.is_due(&topic(true, 90)));
+        assert!(!warmer.is_due(&topic(true, 300)));
+        assert!(!warmer.is_due(&topic(false, 10)));
+
+        let disabled = ModelWarmer::new("30m".to_string(), Duration::ZERO);
+        assert!(!disabled.is_due(&topic(true, 0)));
+    }
+}
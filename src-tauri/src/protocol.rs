@@ -1,6 +1,7 @@
 // Council message protocol for P2P communication
 
 use crate::crypto::SignedMessage;
+use crate::providers::Attachment;
 use crate::reputation::AgentReputation;
 use serde::{Deserialize, Serialize};
 
@@ -129,6 +130,12 @@ pub struct CouncilSession {
     pub consensus: Option<String>,
     pub status: SessionStatus,
     pub created_at: u64,
+    /// Images the council is asked to judge (kept in memory, not persisted)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Knowledge namespace the session belongs to (`None` is the default council)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -139,6 +146,9 @@ pub struct CouncilResponse {
     pub timestamp: u64,
     pub signature: Option<String>,  // Base64 encoded Ed25519 signature
     pub public_key: Option<String>, // Base64 encoded public key
+    /// `<think>` trace split from `response` (debugging only, not shown to other agents)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -220,6 +230,8 @@ mod tests {
             consensus: None,
             status: SessionStatus::GatheringResponses,
             created_at: 0,
+            attachments: vec![],
+            namespace: None,
         };
 
         assert_eq!(session.status, SessionStatus::GatheringResponses);
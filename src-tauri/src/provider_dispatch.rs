@@ -2,14 +2,24 @@
 
 use crate::config::AppConfig;
 use crate::ollama;
-use crate::providers::{AIProvider, GenerationRequest, GoogleProvider, OpenAIProvider};
+use crate::agents::Agent;
+use crate::providers::{
+    AIProvider, Attachment, GenerationRequest, GoogleProvider, MockProvider, OllamaPool,
+    OllamaProvider, OpenAIProvider, RequestPriority, RequestScheduler, ResponseCache, RetryPolicy,
+    ScheduledProvider, SchedulerSlot,
+};
+use crate::providers::discovery::{fit_attachments, ModelCatalog};
+use crate::providers::mock::{append_fixture, Fixture};
+use crate::providers::reasoning::{split_reasoning, SplitReply};
+use crate::providers::retry::retry_with_backoff;
+use crate::providers::scheduler::lane_for;
 use crate::logger::Logger;
 use std::sync::Arc;
 
 /// Generate text using the specified provider
 /// 
 /// # Arguments
-/// * `provider` - Provider name: "ollama", "openai", "openrouter", "google"
+/// * `provider` - Provider name: "ollama", "openai", "openrouter", "google", "mock"
 /// * `model` - Model name (e.g., "gpt-4o", "gemini-1.5-flash", "qwen2.5:7b")
 /// * `prompt` - The user prompt/message
 /// * `system_prompt` - Optional system prompt
@@ -36,102 +46,267 @@ pub async fn generate_with_timeout(
     logger: Option<Arc<Logger>>,
     timeout_secs: Option<u64>,
 ) -> Result<String, String> {
-    match provider.to_lowercase().as_str() {
-        "ollama" => {
-            // Ollama Guardian uses username-only auth (app name), password is optional
-            let auth = config.ollama_username.as_ref().map(|u| {
-                (u.as_str(), config.ollama_password.as_deref().unwrap_or(""))
-            });
-
-            ollama::ask_ollama_with_timeout(
-                &config.ollama_url,
-                model,
-                prompt,
-                system_prompt,
-                auth,
-                timeout_secs,
-            )
-            .await
+    let request = GenerationRequest {
+        model: model.to_string(),
+        prompt,
+        system_prompt,
+        temperature: 0.7,
+        max_tokens: None,
+        stream: false,
+        params: Default::default(),
+        bypass_cache: false,
+        attachments: Vec::new(),
+    };
+
+    dispatch(provider, request, config, logger, timeout_secs, None, None)
+        .await
+        .result
+}
+
+/// Result of a dispatched generation plus the retries spent on it
+pub struct DispatchOutcome {
+    /// Final answer (reasoning trace removed)
+    pub result: Result<String, String>,
+    pub retries: u32,
+    /// `<think>` trace split from the answer, if the model produced one
+    pub reasoning: Option<String>,
+}
+
+/// Optional cache, scheduling and attachments for an agent request
+#[derive(Default)]
+pub struct DispatchOptions<'a> {
+    pub cache: Option<&'a ResponseCache>,
+    pub scheduler: Option<&'a Arc<RequestScheduler>>,
+    pub priority: RequestPriority,
+    /// Fair-queuing key (the human the request is for)
+    pub user: Option<&'a str>,
+    /// Images sent with the prompt (described in text for models without vision)
+    pub attachments: &'a [Attachment],
+    /// Model metadata used to decide whether the agent's model can see images
+    pub catalog: Option<&'a ModelCatalog>,
+    /// Ollama hosts to route between (the single `ollama_url` when unset)
+    pub ollama_pool: Option<&'a OllamaPool>,
+}
+
+/// Generate text as a specific agent (provider, model, sampling and `timeout_secs` come from the agent)
+///
+/// The agent's timeout bounds the whole request including retries. When a
+/// response cache is given, identical requests are answered from it; cache
+/// misses wait for a scheduler slot on the agent's provider lane before each
+/// attempt, and give it back while backing off between retries.
+pub async fn generate_for_agent(
+    agent: &Agent,
+    prompt: String,
+    system_prompt: Option<String>,
+    config: &AppConfig,
+    logger: Option<Arc<Logger>>,
+    options: DispatchOptions<'_>,
+) -> DispatchOutcome {
+    let cache = options.cache;
+    let mut request = agent.generation_request(prompt, system_prompt);
+    if !options.attachments.is_empty() {
+        request.attachments = options.attachments.to_vec();
+        let log = logger.clone().unwrap_or_else(|| Arc::new(Logger::new(false)));
+        let provider = build_provider(&agent.provider, &agent.model, config, log).ok();
+        request = fit_attachments(options.catalog, &agent.provider, provider.as_deref(), request).await;
+    }
+
+    if let Some(reply) = match cache {
+        Some(cache) => cache.get_reply(&agent.provider, &request).await,
+        None => None,
+    } {
+        return DispatchOutcome {
+            result: Ok(reply.answer),
+            retries: 0,
+            reasoning: reply.reasoning,
+        };
+    }
+
+    let slot = options.scheduler.map(|scheduler| {
+        SchedulerSlot::new(
+            scheduler.clone(),
+            lane_for(&agent.provider, &config.ollama_url),
+            options.priority,
+            options.user.unwrap_or(&agent.id),
+        )
+    });
+
+    let outcome = dispatch(
+        &agent.provider,
+        request.clone(),
+        config,
+        logger,
+        agent.timeout_secs,
+        options.ollama_pool,
+        slot.as_ref(),
+    )
+    .await;
+
+    if let (Some(cache), Ok(text)) = (cache, &outcome.result) {
+        let reply = SplitReply {
+            answer: text.clone(),
+            reasoning: outcome.reasoning.clone(),
+        };
+        cache.put_reply(&agent.provider, &request, &reply).await;
+    }
+
+    outcome
+}
+
+async fn dispatch(
+    provider: &str,
+    request: GenerationRequest,
+    config: &AppConfig,
+    logger: Option<Arc<Logger>>,
+    timeout_secs: Option<u64>,
+    ollama_pool: Option<&OllamaPool>,
+    slot: Option<&SchedulerSlot>,
+) -> DispatchOutcome {
+    let provider_name = provider.to_lowercase();
+    let fixture_request = config.record_fixtures_path.as_ref().map(|_| request.clone());
+
+    let outcome = dispatch_live(
+        &provider_name,
+        request,
+        config,
+        logger.clone(),
+        timeout_secs,
+        ollama_pool,
+        slot,
+    )
+    .await;
+
+    // Record live traffic so it can be replayed through the mock provider
+    if let (Some(path), Some(request), Ok(text)) =
+        (&config.record_fixtures_path, fixture_request, &outcome.result)
+    {
+        if provider_name != "mock" {
+            if let Err(e) = append_fixture(path, &Fixture::new(&provider_name, &request, text)) {
+                if let Some(log) = &logger {
+                    log.warn("provider_dispatch", &format!("⚠️ Fixture not recorded: {}", e));
+                }
+            }
         }
+    }
 
-        "openai" => {
-            let api_key = config.openai_api_key.as_ref()
-                .ok_or_else(|| "OpenAI API key not configured".to_string())?;
+    outcome
+}
 
-            let log = logger.unwrap_or_else(|| Arc::new(Logger::new(false)));
-            let provider = OpenAIProvider::new(
-                api_key.clone(),
-                model.to_string(),
-                log,
-            );
-
-            let request = GenerationRequest {
-                model: model.to_string(),
-                prompt,
-                system_prompt,
-                temperature: 0.7,
-                max_tokens: None,
-                stream: false,
-            };
-
-            match provider.generate(request).await {
-                Ok(response) => Ok(response.text),
-                Err(e) => Err(e.to_string()),
+async fn dispatch_live(
+    provider_name: &str,
+    request: GenerationRequest,
+    config: &AppConfig,
+    logger: Option<Arc<Logger>>,
+    timeout_secs: Option<u64>,
+    ollama_pool: Option<&OllamaPool>,
+    slot: Option<&SchedulerSlot>,
+) -> DispatchOutcome {
+    if provider_name == "ollama" {
+        // Ollama Guardian uses username-only auth (app name), password is optional
+        let auth = config.ollama_username.as_ref().map(|u| {
+            (u.as_str(), config.ollama_password.as_deref().unwrap_or(""))
+        });
+
+        let outcome = match ollama_pool {
+            Some(pool) => ollama::ask_ollama_pooled(pool, &request, timeout_secs, slot).await,
+            None => {
+                ollama::ask_ollama_with_retry(&config.ollama_url, &request, auth, timeout_secs, slot)
+                    .await
+            }
+        };
+
+        let (result, reasoning) = match outcome.result {
+            Ok(raw) => {
+                let split = split_reasoning(&raw);
+                (Ok(split.answer), split.reasoning)
+            }
+            Err(e) => (Err(e.to_string()), None),
+        };
+        return DispatchOutcome {
+            result,
+            retries: outcome.retries,
+            reasoning,
+        };
+    }
+
+    let log = logger.unwrap_or_else(|| Arc::new(Logger::new(false)));
+    let inner: Arc<dyn AIProvider> = match build_provider(provider_name, &request.model, config, log.clone()) {
+        Ok(p) => p,
+        Err(e) => {
+            return DispatchOutcome {
+                result: Err(e),
+                retries: 0,
+                reasoning: None,
             }
         }
+    };
+
+    // Each attempt takes its own slot, as topic replies do via `ScheduledProvider`
+    let inner: Arc<dyn AIProvider> = match slot {
+        Some(slot) => Arc::new(ScheduledProvider::new(
+            inner,
+            slot.scheduler.clone(),
+            slot.lane.clone(),
+            slot.priority,
+            slot.user.clone(),
+        )),
+        None => inner,
+    };
+
+    let policy = timeout_secs
+        .map(RetryPolicy::with_budget_secs)
+        .unwrap_or_default();
+
+    let label = format!("{}:{}", provider_name, request.model);
+    let outcome = retry_with_backoff(&policy, Some(&log), &label, || {
+        inner.generate(request.clone())
+    })
+    .await;
+
+    let reasoning = outcome.result.as_ref().ok().and_then(|r| r.reasoning.clone());
+    DispatchOutcome {
+        result: outcome.result.map(|r| r.text).map_err(|e| e.to_string()),
+        retries: outcome.retries,
+        reasoning,
+    }
+}
+
+/// Instantiate the named provider for `model` from app configuration
+pub fn build_provider(
+    provider: &str,
+    model: &str,
+    config: &AppConfig,
+    log: Arc<Logger>,
+) -> Result<Arc<dyn AIProvider>, String> {
+    match provider.to_lowercase().as_str() {
+        "ollama" => Ok(Arc::new(
+            OllamaProvider::new(config.ollama_url.clone(), model.to_string(), log)
+                .with_auth(config.ollama_username.clone(), config.ollama_password.clone()),
+        )),
+
+        "openai" => {
+            let api_key = config.api_key("openai")?
+                .ok_or_else(|| "OpenAI API key not configured".to_string())?;
+            Ok(Arc::new(OpenAIProvider::new(api_key, model.to_string(), log)))
+        }
 
         "openrouter" => {
-            let api_key = config.openrouter_api_key.as_ref()
+            let api_key = config.api_key("openrouter")?
                 .ok_or_else(|| "OpenRouter API key not configured".to_string())?;
-
-            let log = logger.unwrap_or_else(|| Arc::new(Logger::new(false)));
-            let provider = OpenAIProvider::openrouter(
-                api_key.clone(),
-                model.to_string(),
-                log,
-            );
-
-            let request = GenerationRequest {
-                model: model.to_string(),
-                prompt,
-                system_prompt,
-                temperature: 0.7,
-                max_tokens: None,
-                stream: false,
-            };
-
-            match provider.generate(request).await {
-                Ok(response) => Ok(response.text),
-                Err(e) => Err(e.to_string()),
-            }
+            Ok(Arc::new(OpenAIProvider::openrouter(api_key, model.to_string(), log)))
         }
 
         "google" => {
-            let api_key = config.google_api_key.as_ref()
+            let api_key = config.api_key("google")?
                 .ok_or_else(|| "Google API key not configured".to_string())?;
-
-            let log = logger.unwrap_or_else(|| Arc::new(Logger::new(false)));
-            let provider = GoogleProvider::new(
-                api_key.clone(),
-                model.to_string(),
-                log,
-            );
-
-            let request = GenerationRequest {
-                model: model.to_string(),
-                prompt,
-                system_prompt,
-                temperature: 0.7,
-                max_tokens: None,
-                stream: false,
-            };
-
-            match provider.generate(request).await {
-                Ok(response) => Ok(response.text),
-                Err(e) => Err(e.to_string()),
-            }
+            Ok(Arc::new(GoogleProvider::new(api_key, model.to_string(), log)))
         }
 
+        "mock" => Ok(Arc::new(MockProvider::from_optional_path(
+            config.mock_script_path.as_deref(),
+            log,
+        ))),
+
         _ => Err(format!("Unknown provider: {}", provider)),
     }
 }
@@ -143,6 +318,7 @@ pub fn is_provider_configured(provider: &str, config: &AppConfig) -> bool {
         "openai" => config.openai_api_key.is_some(),
         "openrouter" => config.openrouter_api_key.is_some(),
         "google" => config.google_api_key.is_some(),
+        "mock" => true, // Scripted, no server needed
         _ => false,
     }
 }
@@ -159,6 +335,7 @@ mod tests {
         assert!(!is_provider_configured("openai", &config));
         assert!(!is_provider_configured("google", &config));
         assert!(!is_provider_configured("openrouter", &config));
+        assert!(is_provider_configured("mock", &config));
     }
 
     #[test]
@@ -171,4 +348,17 @@ mod tests {
         assert!(is_provider_configured("google", &config));
         assert!(!is_provider_configured("openrouter", &config));
     }
+
+    #[tokio::test]
+    async fn test_generate_with_mock_provider_needs_no_server() {
+        let config = AppConfig {
+            ollama_url: "http://127.0.0.1:9".into(),
+            ..Default::default()
+        };
+
+        let text = generate("mock", "any-model", "Is consensus possible?".to_string(), None, &config, None)
+            .await
+            .unwrap();
+        assert!(text.contains("Is consensus possible?"));
+    }
 }
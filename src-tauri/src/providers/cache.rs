@@ -0,0 +1,470 @@
+use crate::logger::{LogLevel, Logger};
+use crate::metrics::MetricsCollector;
+use crate::providers::reasoning::SplitReply;
+use crate::providers::{
+    AIProvider, FinishReason, GenerationParams, GenerationRequest, GenerationResponse, ModelInfo,
+    ProviderError, ProviderHealth, ProviderType,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Cache statistics (exposed via `response_cache_stats`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+}
+
+/// SQLite-backed generation cache
+///
+/// Entries are keyed by provider, model, system prompt, prompt, temperature
+/// and sampling parameters (seed, top_p, stop, format...), so a re-asked question (benchmarks, duplicate questions, replayed
+/// tests) is answered without touching the model server.
+pub struct ResponseCache {
+    pool: SqlitePool,
+    logger: Arc<Logger>,
+    default_ttl: Option<Duration>,
+    metrics: Option<Arc<Mutex<MetricsCollector>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    /// Open (or create) the cache database
+    pub async fn new(db_path: &str, logger: Arc<Logger>) -> Result<Self, String> {
+        let options = SqliteConnectOptions::from_str(db_path)
+            .map_err(|e| format!("Invalid cache database path: {}", e))?
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .map_err(|e| format!("Failed to open response cache: {}", e))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS response_cache (
+                cache_key TEXT PRIMARY KEY,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                response TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER,
+                hit_count INTEGER NOT NULL DEFAULT 0
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to create response_cache table: {}", e))?;
+
+        let cache = Self {
+            pool,
+            logger,
+            default_ttl: None,
+            metrics: None,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
+
+        let purged = cache.purge_expired().await?;
+        cache.logger.log(
+            LogLevel::Info,
+            "response_cache",
+            &format!("💾 Response cache ready ({} expired entries purged)", purged),
+        );
+
+        Ok(cache)
+    }
+
+    /// Expire entries after `ttl` unless a per-call TTL is given
+    pub fn with_default_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Report hits/misses into the app-wide performance metrics
+    pub fn with_metrics(mut self, metrics: Arc<Mutex<MetricsCollector>>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Stable cache key for a request against a provider
+    pub fn cache_key(provider: &str, request: &GenerationRequest) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            provider.to_lowercase().as_str(),
+            request.model.as_str(),
+            request.system_prompt.as_deref().unwrap_or(""),
+            request.prompt.as_str(),
+        ] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hasher.update(request.temperature.to_bits().to_le_bytes());
+        match request.params.seed {
+            Some(seed) => hasher.update(seed.to_le_bytes()),
+            None => hasher.update(b"noseed"),
+        }
+        // Other sampling params only change the key when set, keeping older keys valid
+        let sampling = GenerationParams {
+            seed: None,
+            ..request.params.clone()
+        };
+        if sampling != GenerationParams::default() {
+            hasher.update(serde_json::to_vec(&sampling).unwrap_or_default());
+        }
+        for attachment in &request.attachments {
+            hasher.update(attachment.mime_type.as_bytes());
+            hasher.update(Sha256::digest(attachment.data.as_bytes()));
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Look up a cached response; honours `request.bypass_cache`
+    pub async fn get(&self, provider: &str, request: &GenerationRequest) -> Option<GenerationResponse> {
+        if request.bypass_cache {
+            return None;
+        }
+
+        let key = Self::cache_key(provider, request);
+        let now = chrono::Utc::now().timestamp();
+
+        let row = sqlx::query(
+            "SELECT response FROM response_cache WHERE cache_key = ? AND (expires_at IS NULL OR expires_at > ?)",
+        )
+        .bind(&key)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .unwrap_or_else(|e| {
+            self.logger.warn("response_cache", &format!("⚠️ Cache lookup failed: {}", e));
+            None
+        });
+
+        let cached = row
+            .map(|r| r.get::<String, _>("response"))
+            .and_then(|json| serde_json::from_str::<GenerationResponse>(&json).ok());
+
+        match cached {
+            Some(mut response) => {
+                let _ = sqlx::query("UPDATE response_cache SET hit_count = hit_count + 1 WHERE cache_key = ?")
+                    .bind(&key)
+                    .execute(&self.pool)
+                    .await;
+                self.record(true);
+                self.logger.debug(
+                    "response_cache",
+                    &format!("🎯 Cache hit for {}:{}", provider, request.model),
+                );
+                response.retries = 0;
+                Some(response)
+            }
+            None => {
+                self.record(false);
+                None
+            }
+        }
+    }
+
+    /// Store a response using the default TTL
+    pub async fn put(&self, provider: &str, request: &GenerationRequest, response: &GenerationResponse) {
+        self.put_with_ttl(provider, request, response, self.default_ttl).await
+    }
+
+    /// Store a response with an explicit TTL (`None` = never expires)
+    pub async fn put_with_ttl(
+        &self,
+        provider: &str,
+        request: &GenerationRequest,
+        response: &GenerationResponse,
+        ttl: Option<Duration>,
+    ) {
+        let json = match serde_json::to_string(response) {
+            Ok(json) => json,
+            Err(e) => {
+                self.logger.warn("response_cache", &format!("⚠️ Could not serialize response: {}", e));
+                return;
+            }
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let expires_at = ttl.map(|t| now + t.as_secs() as i64);
+
+        let result = sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO response_cache
+                (cache_key, provider, model, response, created_at, expires_at, hit_count)
+            VALUES (?, ?, ?, ?, ?, ?, 0)
+            "#,
+        )
+        .bind(Self::cache_key(provider, request))
+        .bind(provider.to_lowercase())
+        .bind(&request.model)
+        .bind(json)
+        .bind(now)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await;
+
+        if let Err(e) = result {
+            self.logger.warn("response_cache", &format!("⚠️ Could not store response: {}", e));
+        }
+    }
+
+    /// Answer + trace lookup for callers that don't use `AIProvider` (legacy Ollama path)
+    pub async fn get_reply(&self, provider: &str, request: &GenerationRequest) -> Option<SplitReply> {
+        self.get(provider, request).await.map(|r| SplitReply {
+            answer: r.text,
+            reasoning: r.reasoning,
+        })
+    }
+
+    /// Answer + trace store for callers that don't use `AIProvider`
+    pub async fn put_reply(&self, provider: &str, request: &GenerationRequest, reply: &SplitReply) {
+        let response = GenerationResponse {
+            text: reply.answer.clone(),
+            model: request.model.clone(),
+            tokens_used: 0,
+            input_tokens: None,
+            output_tokens: None,
+            finish_reason: FinishReason::Stop,
+            retries: 0,
+            reasoning: reply.reasoning.clone(),
+        };
+        self.put(provider, request, &response).await
+    }
+
+    /// Delete expired entries, returns how many were removed
+    pub async fn purge_expired(&self) -> Result<u64, String> {
+        sqlx::query("DELETE FROM response_cache WHERE expires_at IS NOT NULL AND expires_at <= ?")
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+            .map_err(|e| format!("Failed to purge response cache: {}", e))
+    }
+
+    /// Drop every cached response
+    pub async fn clear(&self) -> Result<u64, String> {
+        sqlx::query("DELETE FROM response_cache")
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+            .map_err(|e| format!("Failed to clear response cache: {}", e))
+    }
+
+    /// Hit/miss counters since startup plus the number of stored entries
+    pub async fn stats(&self) -> CacheStats {
+        let entries: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM response_cache")
+            .fetch_one(&self.pool)
+            .await
+            .unwrap_or(0);
+
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: entries as u64,
+        }
+    }
+
+    fn record(&self, hit: bool) {
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        if let Some(metrics) = &self.metrics {
+            if let Ok(mut metrics) = metrics.lock() {
+                if hit {
+                    metrics.record_cache_hit();
+                } else {
+                    metrics.record_cache_miss();
+                }
+            }
+        }
+    }
+}
+
+/// Decorator that serves repeated requests from a `ResponseCache`
+pub struct CachingProvider {
+    inner: Arc<dyn AIProvider>,
+    cache: Arc<ResponseCache>,
+}
+
+impl CachingProvider {
+    pub fn new(inner: Arc<dyn AIProvider>, cache: Arc<ResponseCache>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl AIProvider for CachingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        self.inner.provider_type()
+    }
+
+    async fn generate(
+        &self,
+        request: GenerationRequest,
+    ) -> Result<GenerationResponse, ProviderError> {
+        if let Some(cached) = self.cache.get(self.inner.name(), &request).await {
+            return Ok(cached);
+        }
+
+        let response = self.inner.generate(request.clone()).await?;
+        self.cache.put(self.inner.name(), &request, &response).await;
+        Ok(response)
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, ProviderError> {
+        self.inner.embed(text).await
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        self.inner.embed_batch(texts).await
+    }
+
+    fn embedding_model(&self) -> &str {
+        self.inner.embedding_model()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        self.inner.list_models().await
+    }
+
+    async fn describe_model(&self, model: &str) -> Result<ModelInfo, ProviderError> {
+        self.inner.describe_model(model).await
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth, ProviderError> {
+        self.inner.health_check().await
+    }
+
+    fn is_available(&self) -> bool {
+        self.inner.is_available()
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn max_context_length(&self) -> usize {
+        self.inner.max_context_length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ResponseFormat;
+
+    fn request(prompt: &str, seed: Option<u64>) -> GenerationRequest {
+        GenerationRequest {
+            model: "test-model".to_string(),
+            prompt: prompt.to_string(),
+            system_prompt: Some("You are a test".to_string()),
+            temperature: 0.7,
+            max_tokens: None,
+            stream: false,
+            params: GenerationParams {
+                seed,
+                ..Default::default()
+            },
+            bypass_cache: false,
+            attachments: Vec::new(),
+        }
+    }
+
+    async fn cache() -> ResponseCache {
+        ResponseCache::new("sqlite::memory:", Arc::new(Logger::new(false)))
+            .await
+            .expect("cache")
+    }
+
+    #[test]
+    fn test_cache_key_covers_all_inputs() {
+        let base = ResponseCache::cache_key("ollama", &request("hi", Some(1)));
+        assert_eq!(base, ResponseCache::cache_key("Ollama", &request("hi", Some(1))));
+        assert_ne!(base, ResponseCache::cache_key("openai", &request("hi", Some(1))));
+        assert_ne!(base, ResponseCache::cache_key("ollama", &request("hi", Some(2))));
+        assert_ne!(base, ResponseCache::cache_key("ollama", &request("hi", None)));
+
+        let mut hotter = request("hi", Some(1));
+        hotter.temperature = 0.9;
+        assert_ne!(base, ResponseCache::cache_key("ollama", &hotter));
+
+        let mut json = request("hi", Some(1));
+        json.params.response_format = ResponseFormat::Json;
+        assert_ne!(base, ResponseCache::cache_key("ollama", &json));
+
+        let mut with_image = request("hi", Some(1));
+        with_image.attachments.push(crate::providers::Attachment {
+            mime_type: "image/png".to_string(),
+            data: "iVBORw0KGgo=".to_string(),
+            name: None,
+        });
+        assert_ne!(base, ResponseCache::cache_key("ollama", &with_image));
+    }
+
+    #[tokio::test]
+    async fn test_hit_miss_and_bypass() {
+        let cache = cache().await;
+        let req = request("What is 2+2?", Some(42));
+
+        assert!(cache.get_reply("ollama", &req).await.is_none());
+        let reply = SplitReply {
+            answer: "4".to_string(),
+            reasoning: Some("2+2 is 4".to_string()),
+        };
+        cache.put_reply("ollama", &req, &reply).await;
+        assert_eq!(cache.get_reply("ollama", &req).await, Some(reply));
+
+        let mut bypass = req.clone();
+        bypass.bypass_cache = true;
+        assert!(cache.get_reply("ollama", &bypass).await.is_none());
+
+        let stats = cache.stats().await;
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_ignored() {
+        let cache = cache().await;
+        let req = request("stale?", None);
+        let response = GenerationResponse {
+            text: "old".to_string(),
+            model: req.model.clone(),
+            tokens_used: 0,
+            input_tokens: None,
+            output_tokens: None,
+            finish_reason: FinishReason::Stop,
+            retries: 0,
+            reasoning: None,
+        };
+
+        cache.put_with_ttl("ollama", &req, &response, Some(Duration::ZERO)).await;
+        assert!(cache.get("ollama", &req).await.is_none());
+        assert_eq!(cache.purge_expired().await.unwrap(), 1);
+    }
+}
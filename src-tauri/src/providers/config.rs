@@ -1,6 +1,14 @@
+use crate::logger::Logger;
+use crate::providers::registry::ProviderRegistry;
+use crate::secrets::{is_secret_ref, redact, SecretStore};
+use crate::providers::{
+    AIProvider, GoogleProvider, LocalEmbeddingProvider, MockProvider, OllamaProvider,
+    OpenAIProvider,
+};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 
 /// Available roles an AI agent can choose
 pub const AVAILABLE_ROLES: &[&str] = &[
@@ -45,6 +53,7 @@ pub enum ProviderTypeConfig {
     Google,
     Anthropic,
     LocalEmbeddings,
+    Mock,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +88,114 @@ pub enum ProviderSpecificConfig {
     LocalEmbeddings {
         model_path: Option<String>,
     },
+    Mock {
+        script_path: Option<String>,
+    },
+}
+
+impl ProviderSpecificConfig {
+    fn api_key_mut(&mut self) -> Option<&mut String> {
+        match self {
+            ProviderSpecificConfig::OpenAI { api_key, .. }
+            | ProviderSpecificConfig::OpenRouter { api_key, .. }
+            | ProviderSpecificConfig::Google { api_key, .. }
+            | ProviderSpecificConfig::Anthropic { api_key, .. } => Some(api_key),
+            _ => None,
+        }
+    }
+}
+
+impl ProviderConfig {
+    /// Secret store entry holding this provider's API key
+    fn secret_name(&self) -> String {
+        format!("provider_{}_api_key", self.id)
+    }
+
+    /// Move a plaintext API key into the secret store, keeping only the reference
+    pub fn seal_api_key(&mut self, store: &SecretStore) -> Result<bool, String> {
+        let name = self.secret_name();
+        match self.config.api_key_mut() {
+            Some(api_key) if !is_secret_ref(api_key) => {
+                *api_key = store.set(&name, api_key)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Copy for listings: plaintext API keys become previews
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        if let Some(api_key) = config.config.api_key_mut() {
+            *api_key = redact(api_key);
+        }
+        config
+    }
+
+    /// Instantiate the provider described by this config
+    pub fn build(&self, logger: Arc<Logger>) -> Result<Arc<dyn AIProvider>, String> {
+        let mut resolved = self.config.clone();
+        if let Some(api_key) = resolved.api_key_mut() {
+            *api_key = crate::secrets::global().resolve(api_key)?;
+        }
+
+        let provider: Arc<dyn AIProvider> = match &resolved {
+            ProviderSpecificConfig::Ollama {
+                base_url,
+                default_model,
+                embedding_model,
+                ..
+            } => Arc::new(
+                OllamaProvider::new(base_url.clone(), default_model.clone(), logger)
+                    .with_embedding_model(embedding_model.clone()),
+            ),
+            ProviderSpecificConfig::OpenAI {
+                api_key,
+                base_url,
+                default_model,
+                ..
+            } => match base_url {
+                Some(url) => Arc::new(OpenAIProvider::with_base_url(
+                    api_key.clone(),
+                    url.clone(),
+                    default_model.clone(),
+                    "OpenAI".to_string(),
+                    logger,
+                )),
+                None => Arc::new(OpenAIProvider::new(api_key.clone(), default_model.clone(), logger)),
+            },
+            ProviderSpecificConfig::OpenRouter {
+                api_key,
+                default_model,
+            } => Arc::new(OpenAIProvider::openrouter(
+                api_key.clone(),
+                default_model.clone(),
+                logger,
+            )),
+            ProviderSpecificConfig::Google {
+                api_key,
+                default_model,
+                embedding_model,
+            } => {
+                let provider = GoogleProvider::new(api_key.clone(), default_model.clone(), logger);
+                match embedding_model {
+                    Some(model) => Arc::new(provider.with_embedding_model(model.clone())),
+                    None => Arc::new(provider),
+                }
+            }
+            ProviderSpecificConfig::LocalEmbeddings { model_path } => Arc::new(
+                LocalEmbeddingProvider::from_optional_path(model_path.as_deref(), logger),
+            ),
+            ProviderSpecificConfig::Mock { script_path } => Arc::new(
+                MockProvider::from_optional_path(script_path.as_deref(), logger),
+            ),
+            ProviderSpecificConfig::Anthropic { .. } => {
+                return Err("Anthropic provider is not implemented yet".to_string())
+            }
+        };
+
+        Ok(provider)
+    }
 }
 
 /// Complete provider configuration file
@@ -154,6 +271,31 @@ impl ProvidersConfig {
         self.providers.iter().map(|p| p.id.clone()).collect()
     }
 
+    /// Build a registry with every enabled provider and the configured defaults
+    pub fn build_registry(&self, logger: Arc<Logger>) -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new(logger.clone());
+
+        for provider in self.providers.iter().filter(|p| p.enabled) {
+            match provider.build(logger.clone()) {
+                Ok(instance) => registry.register(provider.id.clone(), instance),
+                Err(e) => logger.warn("registry", &format!("⚠️ Skipping provider {}: {}", provider.id, e)),
+            }
+        }
+
+        if let Some(id) = &self.default_generation_provider {
+            if let Err(e) = registry.set_default_generation(id.clone()) {
+                logger.warn("registry", &format!("⚠️ {}", e));
+            }
+        }
+        if let Some(id) = &self.default_embedding_provider {
+            if let Err(e) = registry.set_default_embedding(id.clone()) {
+                logger.warn("registry", &format!("⚠️ {}", e));
+            }
+        }
+
+        registry
+    }
+
     /// Get providers by type
     #[allow(dead_code)]
     pub fn providers_by_type(&self, provider_type: &ProviderTypeConfig) -> Vec<&ProviderConfig> {
@@ -344,7 +486,7 @@ pub fn validate_provider_config(config: &ProviderConfig) -> Result<(), String> {
             if api_key.is_empty() {
                 return Err("OpenAI API key cannot be empty".to_string());
             }
-            if !api_key.starts_with("sk-") {
+            if !is_secret_ref(api_key) && !api_key.starts_with("sk-") {
                 return Err("OpenAI API key must start with 'sk-'".to_string());
             }
         }
@@ -352,7 +494,7 @@ pub fn validate_provider_config(config: &ProviderConfig) -> Result<(), String> {
             if api_key.is_empty() {
                 return Err("OpenRouter API key cannot be empty".to_string());
             }
-            if !api_key.starts_with("sk-or-") {
+            if !is_secret_ref(api_key) && !api_key.starts_with("sk-or-") {
                 return Err("OpenRouter API key must start with 'sk-or-'".to_string());
             }
         }
@@ -360,7 +502,7 @@ pub fn validate_provider_config(config: &ProviderConfig) -> Result<(), String> {
             if api_key.is_empty() {
                 return Err("Google API key cannot be empty".to_string());
             }
-            if !api_key.starts_with("AIza") {
+            if !is_secret_ref(api_key) && !api_key.starts_with("AIza") {
                 return Err("Google API key must start with 'AIza'".to_string());
             }
         }
@@ -369,8 +511,8 @@ pub fn validate_provider_config(config: &ProviderConfig) -> Result<(), String> {
                 return Err("Anthropic API key cannot be empty".to_string());
             }
         }
-        ProviderSpecificConfig::LocalEmbeddings { .. } => {
-            // Local embeddings always valid
+        ProviderSpecificConfig::LocalEmbeddings { .. } | ProviderSpecificConfig::Mock { .. } => {
+            // In-process providers are always valid
         }
     }
 
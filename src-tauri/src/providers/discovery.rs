@@ -0,0 +1,269 @@
+// Model discovery - Cached model metadata (context length, capabilities) per provider
+
+use super::{AIProvider, GenerationRequest, ModelInfo, ProviderError};
+use crate::agents::Agent;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Smallest context window that fits a council round (system prompt, question, peer responses)
+pub const COUNCIL_MIN_CONTEXT_TOKENS: usize = 8192;
+
+/// How long discovered metadata stays fresh
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Tools handled through the prompt rather than native function calling
+const PROMPT_TOOLS: &[&str] = &["send_message", "vote"];
+
+struct Entry<T> {
+    fetched_at: Instant,
+    value: T,
+}
+
+/// In-memory cache of `list_models` / `describe_model` results
+pub struct ModelCatalog {
+    ttl: Duration,
+    models: Mutex<HashMap<(String, String), Entry<ModelInfo>>>,
+    lists: Mutex<HashMap<String, Entry<Vec<ModelInfo>>>>,
+}
+
+impl Default for ModelCatalog {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+impl ModelCatalog {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            models: Mutex::new(HashMap::new()),
+            lists: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Metadata for one model, fetched from the provider on a cache miss
+    pub async fn describe(
+        &self,
+        provider_name: &str,
+        provider: &dyn AIProvider,
+        model: &str,
+    ) -> Result<ModelInfo, ProviderError> {
+        let key = (provider_name.to_lowercase(), model.to_string());
+        if let Some(entry) = self.models.lock().unwrap().get(&key) {
+            if entry.fetched_at.elapsed() < self.ttl {
+                return Ok(entry.value.clone());
+            }
+        }
+
+        let info = provider.describe_model(model).await?;
+        self.models.lock().unwrap().insert(
+            key,
+            Entry {
+                fetched_at: Instant::now(),
+                value: info.clone(),
+            },
+        );
+        Ok(info)
+    }
+
+    /// All models a provider offers
+    ///
+    /// List entries don't seed the per-model cache: some providers (Ollama's
+    /// `/api/tags`) return placeholder context lengths and capabilities there,
+    /// so `describe` always asks the provider for the real metadata.
+    pub async fn list(
+        &self,
+        provider_name: &str,
+        provider: &dyn AIProvider,
+    ) -> Result<Vec<ModelInfo>, ProviderError> {
+        let key = provider_name.to_lowercase();
+        if let Some(entry) = self.lists.lock().unwrap().get(&key) {
+            if entry.fetched_at.elapsed() < self.ttl {
+                return Ok(entry.value.clone());
+            }
+        }
+
+        let models = provider.list_models().await?;
+        self.lists.lock().unwrap().insert(
+            key,
+            Entry {
+                fetched_at: Instant::now(),
+                value: models.clone(),
+            },
+        );
+        Ok(models)
+    }
+
+    /// Whether `model` accepts images; falls back to the model name when discovery fails
+    pub async fn supports_vision(
+        &self,
+        provider_name: &str,
+        provider: &dyn AIProvider,
+        model: &str,
+    ) -> bool {
+        match self.describe(provider_name, provider, model).await {
+            Ok(info) => info.supports_vision,
+            Err(_) => guess_vision_support(model),
+        }
+    }
+
+    /// Drop everything (e.g. after pulling a new model)
+    pub fn clear(&self) {
+        self.models.lock().unwrap().clear();
+        self.lists.lock().unwrap().clear();
+    }
+}
+
+/// Name-based guess for models discovery can't describe
+pub fn guess_vision_support(model: &str) -> bool {
+    const VISION_MARKERS: &[&str] = &[
+        "llava", "vision", "-vl", "vl:", "moondream", "minicpm-v", "pixtral", "gemma3",
+        "gpt-4o", "gpt-4.1", "gpt-5", "gemini", "claude-3", "claude-sonnet-4", "claude-opus-4",
+    ];
+    let model = model.to_lowercase();
+    VISION_MARKERS.iter().any(|marker| model.contains(marker))
+}
+
+/// Keep images for vision models; otherwise replace them with a text description
+///
+/// Without a catalog or provider instance the decision is made from the model name.
+pub async fn fit_attachments(
+    catalog: Option<&ModelCatalog>,
+    provider_name: &str,
+    provider: Option<&dyn AIProvider>,
+    request: GenerationRequest,
+) -> GenerationRequest {
+    if request.images().next().is_none() {
+        return request;
+    }
+    let vision = match (catalog, provider) {
+        (Some(catalog), Some(provider)) => {
+            catalog.supports_vision(provider_name, provider, &request.model).await
+        }
+        _ => guess_vision_support(&request.model),
+    };
+    if vision {
+        request
+    } else {
+        request.without_images()
+    }
+}
+
+/// Problems running `agent` on `model`; empty when the model fits
+pub fn check_agent(agent: &Agent, model: &ModelInfo) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    // Rough estimate: ~4 characters per token
+    let needed = COUNCIL_MIN_CONTEXT_TOKENS + agent.system_prompt.len() / 4;
+    if model.context_length < needed {
+        warnings.push(format!(
+            "{} has a {}-token context window; council rounds need about {}",
+            model.id, model.context_length, needed
+        ));
+    }
+
+    let native_tools: Vec<&str> = agent
+        .enabled_tools
+        .iter()
+        .map(String::as_str)
+        .filter(|t| !PROMPT_TOOLS.contains(t))
+        .collect();
+    if !native_tools.is_empty() && !model.supports_function_calling {
+        warnings.push(format!(
+            "{} does not support tool calling (agent uses: {})",
+            model.id,
+            native_tools.join(", ")
+        ));
+    }
+
+    if model.supports_embeddings && !model.supports_function_calling && model.context_length <= 8192 {
+        warnings.push(format!("{} looks like an embedding-only model", model.id));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logger::Logger;
+    use crate::providers::MockProvider;
+    use std::sync::Arc;
+
+    fn info(context_length: usize, tools: bool) -> ModelInfo {
+        ModelInfo {
+            id: "test-model".to_string(),
+            name: "test-model".to_string(),
+            context_length,
+            supports_embeddings: false,
+            supports_function_calling: tools,
+            supports_vision: false,
+            parameter_count: None,
+        }
+    }
+
+    fn agent(tools: &[&str]) -> Agent {
+        let mut agent = Agent::new(
+            "Tester".to_string(),
+            "test-model".to_string(),
+            "You are a careful reviewer.".to_string(),
+        );
+        agent.enabled_tools = tools.iter().map(|t| t.to_string()).collect();
+        agent
+    }
+
+    #[test]
+    fn test_check_agent_warnings() {
+        assert!(check_agent(&agent(&["send_message", "vote"]), &info(32768, false)).is_empty());
+
+        let small = check_agent(&agent(&["send_message"]), &info(2048, true));
+        assert_eq!(small.len(), 1);
+        assert!(small[0].contains("2048-token"));
+
+        let no_tools = check_agent(&agent(&["vote", "search_knowledge"]), &info(32768, false));
+        assert_eq!(no_tools.len(), 1);
+        assert!(no_tools[0].contains("search_knowledge"));
+    }
+
+    #[tokio::test]
+    async fn test_fit_attachments_describes_images_for_text_models() {
+        let provider = MockProvider::from_optional_path(None, Arc::new(Logger::new(false)));
+        let catalog = ModelCatalog::default();
+        let mut request = agent(&[]).generation_request("Judge this diagram".to_string(), None);
+        request.model = "mock".to_string();
+        request.attachments.push(crate::providers::Attachment {
+            mime_type: "image/png".to_string(),
+            data: "iVBORw0KGgo=".to_string(),
+            name: Some("diagram.png".to_string()),
+        });
+
+        let fitted = fit_attachments(Some(&catalog), "mock", Some(&provider), request.clone()).await;
+        assert!(fitted.attachments.is_empty());
+        assert!(fitted.prompt.contains("diagram.png"));
+
+        request.model = "llava:13b".to_string();
+        let kept = fit_attachments(None, "ollama", None, request).await;
+        assert_eq!(kept.attachments.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_caches_lists_and_models() {
+        let provider = MockProvider::from_optional_path(None, Arc::new(Logger::new(false)));
+        let catalog = ModelCatalog::default();
+
+        let models = catalog.list("mock", &provider).await.unwrap();
+        assert!(!models.is_empty());
+
+        // Listing leaves per-model metadata to `describe`
+        assert!(catalog.models.lock().unwrap().is_empty());
+
+        let first = &models[0];
+        let described = catalog.describe("Mock", &provider, &first.id).await.unwrap();
+        assert_eq!(described.id, first.id);
+        assert_eq!(catalog.models.lock().unwrap().len(), 1);
+
+        catalog.clear();
+        assert!(catalog.lists.lock().unwrap().is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 use crate::logger::{LogLevel, Logger};
 use crate::providers::{
-    AIProvider, FinishReason, GenerationRequest, GenerationResponse, ModelInfo, ProviderError,
-    ProviderHealth, ProviderType,
+    AIProvider, Attachment, FinishReason, GenerationRequest, GenerationResponse, ModelInfo, ProviderError,
+    ProviderHealth, ProviderType, ResponseFormat,
 };
+use crate::providers::reasoning::split_reasoning;
+use crate::providers::retry;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -38,7 +40,43 @@ struct GeminiContent {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct GeminiPart {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     text: String,
+    #[serde(rename = "inlineData", default, skip_serializing_if = "Option::is_none")]
+    inline_data: Option<GeminiInlineData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GeminiInlineData {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    data: String,
+}
+
+impl GeminiPart {
+    fn text(text: String) -> Self {
+        Self {
+            text,
+            inline_data: None,
+        }
+    }
+
+    fn image(image: &Attachment) -> Self {
+        Self {
+            text: String::new(),
+            inline_data: Some(GeminiInlineData {
+                mime_type: image.mime_type.clone(),
+                data: image.data.clone(),
+            }),
+        }
+    }
+}
+
+/// User turn: the prompt followed by any images as inline data
+fn user_parts(request: &GenerationRequest) -> Vec<GeminiPart> {
+    let mut parts = vec![GeminiPart::text(request.prompt.clone())];
+    parts.extend(request.images().map(GeminiPart::image));
+    parts
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -47,6 +85,48 @@ struct GeminiGenerationConfig {
     temperature: Option<f32>,
     #[serde(rename = "maxOutputTokens", skip_serializing_if = "Option::is_none")]
     max_output_tokens: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(rename = "topP", skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(rename = "topK", skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(rename = "stopSequences", skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+    #[serde(rename = "presencePenalty", skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(rename = "frequencyPenalty", skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(rename = "responseJsonSchema", skip_serializing_if = "Option::is_none")]
+    response_json_schema: Option<serde_json::Value>,
+}
+
+impl GeminiGenerationConfig {
+    fn from_request(request: &GenerationRequest) -> Self {
+        let params = &request.params;
+        let (response_mime_type, response_json_schema) = match &params.response_format {
+            ResponseFormat::Text => (None, None),
+            ResponseFormat::Json => (Some("application/json".to_string()), None),
+            ResponseFormat::JsonSchema { schema } => {
+                (Some("application/json".to_string()), Some(schema.clone()))
+            }
+        };
+
+        Self {
+            temperature: Some(request.temperature),
+            max_output_tokens: request.max_tokens,
+            seed: params.seed,
+            top_p: params.top_p,
+            top_k: params.top_k,
+            stop_sequences: params.stop.clone(),
+            presence_penalty: params.presence_penalty,
+            frequency_penalty: params.frequency_penalty,
+            response_mime_type,
+            response_json_schema,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -131,6 +211,32 @@ struct GeminiModelInfo {
     supported_generation_methods: Option<Vec<String>>,
 }
 
+impl GeminiModelInfo {
+    /// Generation and embedding models only (skips e.g. AQA / image models)
+    fn into_model_info(self) -> Option<ModelInfo> {
+        let methods = self.supported_generation_methods.unwrap_or_default();
+        let generates = methods.iter().any(|m| m == "generateContent");
+        let embeds = methods.iter().any(|m| m == "embedContent");
+        if !generates && !embeds {
+            return None;
+        }
+
+        // Strip "models/" prefix from name
+        let id = self.name.strip_prefix("models/").unwrap_or(&self.name).to_string();
+        Some(ModelInfo {
+            name: self.display_name.unwrap_or_else(|| id.clone()),
+            id,
+            context_length: self.input_token_limit.unwrap_or(32000),
+            supports_embeddings: embeds,
+            // Gemma models on the Gemini API don't accept function declarations
+            supports_function_calling: generates && !self.name.contains("gemma"),
+            // Gemini models are natively multimodal
+            supports_vision: generates && self.name.contains("gemini"),
+            parameter_count: None,
+        })
+    }
+}
+
 // ============================================================================
 // Google Gemini Provider Implementation
 // ============================================================================
@@ -241,22 +347,17 @@ impl AIProvider for GoogleProvider {
         // Build system instruction if provided
         let system_instruction = request.system_prompt.as_ref().map(|s| {
             GeminiSystemInstruction {
-                parts: vec![GeminiPart { text: s.clone() }],
+                parts: vec![GeminiPart::text(s.clone())],
             }
         });
 
         let gemini_request = GeminiRequest {
             contents: vec![GeminiContent {
                 role: "user".to_string(),
-                parts: vec![GeminiPart {
-                    text: request.prompt.clone(),
-                }],
+                parts: user_parts(&request),
             }],
             system_instruction,
-            generation_config: Some(GeminiGenerationConfig {
-                temperature: Some(request.temperature),
-                max_output_tokens: request.max_tokens,
-            }),
+            generation_config: Some(GeminiGenerationConfig::from_request(&request)),
         };
 
         let client = reqwest::Client::builder()
@@ -274,12 +375,19 @@ impl AIProvider for GoogleProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let header_hint = retry::retry_after_from_headers(response.headers());
             let error_text = response.text().await.unwrap_or_default();
             
             return Err(match status.as_u16() {
                 401 | 403 => ProviderError::AuthenticationError(format!("[Google] {}", error_text)),
-                429 => ProviderError::RateLimitError(format!("[Google] {}", error_text)),
+                429 => ProviderError::RateLimitError {
+                    retry_after_ms: header_hint
+                        .or_else(|| retry::retry_after_from_body(&error_text))
+                        .map(|d| d.as_millis() as u64),
+                    message: format!("[Google] {}", error_text),
+                },
                 404 => ProviderError::ModelNotFound(format!("[Google] {}", error_text)),
+                400..=499 => ProviderError::InvalidRequest(format!("[Google] Status {}: {}", status, error_text)),
                 _ => ProviderError::NetworkError(format!("[Google] Status {}: {}", status, error_text)),
             });
         }
@@ -339,13 +447,16 @@ impl AIProvider for GoogleProvider {
                 text.len(), tokens_used, preview.replace('\n', " "), suffix),
         );
 
+        let split = split_reasoning(&text);
         Ok(GenerationResponse {
-            text,
+            text: split.answer,
             model: request.model,
             tokens_used,
             input_tokens,
             output_tokens,
             finish_reason,
+            retries: 0,
+            reasoning: split.reasoning,
         })
     }
 
@@ -364,9 +475,7 @@ impl AIProvider for GoogleProvider {
         let embed_request = GeminiEmbedRequest {
             model: format!("models/{}", self.embedding_model),
             content: GeminiEmbedContent {
-                parts: vec![GeminiPart {
-                    text: text.to_string(),
-                }],
+                parts: vec![GeminiPart::text(text.to_string())],
             },
         };
 
@@ -385,10 +494,16 @@ impl AIProvider for GoogleProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let header_hint = retry::retry_after_from_headers(response.headers());
             let error_text = response.text().await.unwrap_or_default();
             return Err(match status.as_u16() {
                 401 | 403 => ProviderError::AuthenticationError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
+                429 => ProviderError::RateLimitError {
+                    retry_after_ms: header_hint
+                        .or_else(|| retry::retry_after_from_body(&error_text))
+                        .map(|d| d.as_millis() as u64),
+                    message: error_text,
+                },
                 _ => ProviderError::NetworkError(format!("Status {}: {}", status, error_text)),
             });
         }
@@ -416,6 +531,10 @@ impl AIProvider for GoogleProvider {
         Ok(embedding)
     }
 
+    fn embedding_model(&self) -> &str {
+        &self.embedding_model
+    }
+
     async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
         self.logger.log(
             LogLevel::Debug,
@@ -453,30 +572,13 @@ impl AIProvider for GoogleProvider {
             .models
             .unwrap_or_default()
             .into_iter()
-            .filter(|m| {
-                // Only include generateContent-capable models
-                m.supported_generation_methods
-                    .as_ref()
-                    .map(|methods| methods.iter().any(|m| m == "generateContent"))
-                    .unwrap_or(false)
-            })
-            .map(|m| {
-                // Strip "models/" prefix from name
-                let id = m.name.strip_prefix("models/").unwrap_or(&m.name).to_string();
-                ModelInfo {
-                    id: id.clone(),
-                    name: m.display_name.unwrap_or(id),
-                    context_length: m.input_token_limit.unwrap_or(32000),
-                    supports_embeddings: false,
-                    supports_function_calling: true,
-                }
-            })
+            .filter_map(GeminiModelInfo::into_model_info)
             .collect();
 
         self.logger.log(
             LogLevel::Success,
             "google_provider",
-            &format!("✅ [Google] Found {} generative models", models.len()),
+            &format!("✅ [Google] Found {} generative/embedding models", models.len()),
         );
 
         Ok(models)
@@ -560,4 +662,69 @@ mod tests {
         assert!(url.contains("generateContent"));
         assert!(url.contains("key=test-key"));
     }
+
+    #[test]
+    fn test_generation_config_mapping() {
+        let request = GenerationRequest {
+            model: "gemini-1.5-flash".to_string(),
+            prompt: "hi".to_string(),
+            system_prompt: None,
+            temperature: 0.5,
+            max_tokens: None,
+            stream: false,
+            params: crate::providers::GenerationParams {
+                top_p: Some(0.9),
+                stop: vec!["END".to_string()],
+                response_format: ResponseFormat::JsonSchema {
+                    schema: serde_json::json!({ "type": "object" }),
+                },
+                ..Default::default()
+            },
+            bypass_cache: false,
+            attachments: Vec::new(),
+        };
+
+        let config = serde_json::to_value(GeminiGenerationConfig::from_request(&request)).unwrap();
+        assert_eq!(config["topP"], 0.9f32 as f64);
+        assert_eq!(config["stopSequences"][0], "END");
+        assert_eq!(config["responseMimeType"], "application/json");
+        assert_eq!(config["responseJsonSchema"]["type"], "object");
+        assert!(config.get("seed").is_none());
+
+        let mut with_image = request.clone();
+        with_image.attachments.push(Attachment {
+            mime_type: "image/jpeg".to_string(),
+            data: "/9j/4AAQ".to_string(),
+            name: None,
+        });
+        let parts = serde_json::to_value(user_parts(&with_image)).unwrap();
+        assert_eq!(parts[0], serde_json::json!({ "text": "hi" }));
+        assert_eq!(parts[1]["inlineData"]["mimeType"], "image/jpeg");
+    }
+
+    #[test]
+    fn test_model_metadata_mapping() {
+        let models: GeminiModelsResponse = serde_json::from_value(serde_json::json!({
+            "models": [
+                { "name": "models/gemini-1.5-pro", "inputTokenLimit": 2097152,
+                  "supportedGenerationMethods": ["generateContent", "countTokens"] },
+                { "name": "models/text-embedding-004", "inputTokenLimit": 2048,
+                  "supportedGenerationMethods": ["embedContent"] },
+                { "name": "models/aqa", "supportedGenerationMethods": ["generateAnswer"] }
+            ]
+        }))
+        .unwrap();
+
+        let infos: Vec<ModelInfo> = models
+            .models
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(GeminiModelInfo::into_model_info)
+            .collect();
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].id, "gemini-1.5-pro");
+        assert_eq!(infos[0].context_length, 2097152);
+        assert!(infos[0].supports_function_calling);
+        assert!(infos[1].supports_embeddings && !infos[1].supports_function_calling);
+    }
 }
@@ -0,0 +1,284 @@
+use crate::logger::{LogLevel, Logger};
+use crate::providers::{
+    AIProvider, GenerationRequest, GenerationResponse, ModelInfo, ProviderError, ProviderHealth,
+    ProviderType,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+/// Default vector size for the hashed vectoriser
+pub const LOCAL_EMBEDDING_DIMENSION: usize = 384;
+
+/// Model name recorded for locally generated vectors
+pub const LOCAL_EMBEDDING_MODEL: &str = "local-hashed-ngrams";
+
+/// Optional weights file loaded from `model_path`
+///
+/// ```json
+/// { "dimension": 384, "idf": { "council": 2.3, "consensus": 1.7 } }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalEmbeddingModel {
+    #[serde(default = "default_dimension")]
+    pub dimension: usize,
+    /// Inverse document frequency per lowercase word (unknown words weigh 1.0)
+    #[serde(default)]
+    pub idf: HashMap<String, f32>,
+}
+
+fn default_dimension() -> usize {
+    LOCAL_EMBEDDING_DIMENSION
+}
+
+impl Default for LocalEmbeddingModel {
+    fn default() -> Self {
+        Self {
+            dimension: LOCAL_EMBEDDING_DIMENSION,
+            idf: HashMap::new(),
+        }
+    }
+}
+
+/// In-process embedding provider (no network)
+///
+/// Hashes word unigrams, word bigrams and character trigrams into a fixed-size
+/// vector (signed feature hashing, sublinear TF, optional IDF weights), then
+/// L2-normalises it. Much weaker than a neural model, but good enough to keep
+/// duplicate detection and RAG working when Ollama is unreachable.
+pub struct LocalEmbeddingProvider {
+    model: LocalEmbeddingModel,
+    model_name: String,
+    logger: Arc<Logger>,
+}
+
+impl LocalEmbeddingProvider {
+    pub fn new(logger: Arc<Logger>) -> Self {
+        Self {
+            model: LocalEmbeddingModel::default(),
+            model_name: LOCAL_EMBEDDING_MODEL.to_string(),
+            logger,
+        }
+    }
+
+    /// Load IDF weights / dimension from a JSON model file
+    pub fn from_model_path(path: &str, logger: Arc<Logger>) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read local embedding model {}: {}", path, e))?;
+        let model: LocalEmbeddingModel = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse local embedding model {}: {}", path, e))?;
+
+        if model.dimension == 0 {
+            return Err("Local embedding model dimension must be > 0".to_string());
+        }
+
+        logger.log(
+            LogLevel::Info,
+            "local_embeddings",
+            &format!(
+                "📦 Loaded local embedding model {} ({} dims, {} idf terms)",
+                path,
+                model.dimension,
+                model.idf.len()
+            ),
+        );
+
+        Ok(Self {
+            model,
+            model_name: format!("{}:{}", LOCAL_EMBEDDING_MODEL, path),
+            logger,
+        })
+    }
+
+    /// Build from an optional `model_path` (falls back to defaults if loading fails)
+    pub fn from_optional_path(model_path: Option<&str>, logger: Arc<Logger>) -> Self {
+        match model_path {
+            Some(path) => Self::from_model_path(path, logger.clone()).unwrap_or_else(|e| {
+                logger.warn("local_embeddings", &format!("⚠️ {} - using built-in vectoriser", e));
+                Self::new(logger)
+            }),
+            None => Self::new(logger),
+        }
+    }
+
+    /// Override the vector size (vectors of different sizes are never compared)
+    #[allow(dead_code)]
+    pub fn with_dimension(mut self, dimension: usize) -> Self {
+        self.model.dimension = dimension.max(1);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn dimension(&self) -> usize {
+        self.model.dimension
+    }
+
+    /// Embed text synchronously (cheap, CPU-only)
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let dim = self.model.dimension;
+        let mut vector = vec![0f32; dim];
+
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase())
+            .collect();
+
+        let mut counts: HashMap<String, (u32, f32)> = HashMap::new();
+        let mut add = |feature: String, weight: f32| {
+            let entry = counts.entry(feature).or_insert((0, weight));
+            entry.0 += 1;
+        };
+
+        for word in &words {
+            let idf = self.model.idf.get(word).copied().unwrap_or(1.0);
+            add(format!("w:{}", word), idf);
+
+            // Character trigrams make the vector robust to inflections and typos
+            let padded: Vec<char> = format!("^{}$", word).chars().collect();
+            for tri in padded.windows(3) {
+                add(format!("c:{}", tri.iter().collect::<String>()), 0.5 * idf);
+            }
+        }
+
+        for pair in words.windows(2) {
+            add(format!("b:{} {}", pair[0], pair[1]), 0.75);
+        }
+
+        for (feature, (count, weight)) in counts {
+            let hash = fnv1a(feature.as_bytes());
+            let index = (hash % dim as u64) as usize;
+            let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+            vector[index] += sign * weight * (1.0 + (count as f32).ln());
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in &mut vector {
+                *v /= norm;
+            }
+        }
+
+        vector
+    }
+}
+
+/// FNV-1a (stable across builds, unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[async_trait]
+impl AIProvider for LocalEmbeddingProvider {
+    fn name(&self) -> &str {
+        "local_embeddings"
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::Local { bundled: true }
+    }
+
+    async fn generate(
+        &self,
+        _request: GenerationRequest,
+    ) -> Result<GenerationResponse, ProviderError> {
+        Err(ProviderError::NotSupported(
+            "Local embeddings provider cannot generate text".to_string(),
+        ))
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, ProviderError> {
+        self.logger.log(
+            LogLevel::Debug,
+            "local_embeddings",
+            &format!("🔢 Embedding {} chars locally", text.len()),
+        );
+        Ok(self.embed_text(text))
+    }
+
+    fn embedding_model(&self) -> &str {
+        &self.model_name
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        Ok(vec![ModelInfo {
+            id: self.model_name.clone(),
+            name: "Local hashed n-gram embeddings".to_string(),
+            context_length: usize::MAX,
+            supports_embeddings: true,
+            supports_function_calling: false,
+            supports_vision: false,
+            parameter_count: None,
+        }])
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth, ProviderError> {
+        Ok(ProviderHealth {
+            healthy: true,
+            latency_ms: Some(0),
+            error: None,
+        })
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    fn max_context_length(&self) -> usize {
+        usize::MAX
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    fn provider() -> LocalEmbeddingProvider {
+        LocalEmbeddingProvider::new(Arc::new(Logger::new(false)))
+    }
+
+    #[test]
+    fn test_embedding_is_deterministic_and_normalised() {
+        let p = provider();
+        let a = p.embed_text("Should AI have rights?");
+        let b = p.embed_text("Should AI have rights?");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), LOCAL_EMBEDDING_DIMENSION);
+        assert!((cosine(&a, &a) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_similar_text_scores_higher() {
+        let p = provider();
+        let q = p.embed_text("Should artificial intelligence have legal rights?");
+        let close = p.embed_text("Should artificial intelligences get legal rights");
+        let far = p.embed_text("What is the best recipe for banana bread?");
+        assert!(cosine(&q, &close) > cosine(&q, &far));
+        assert!(cosine(&q, &close) > 0.5);
+    }
+
+    #[test]
+    fn test_empty_text_gives_zero_vector() {
+        let v = provider().embed_text("   ");
+        assert!(v.iter().all(|x| *x == 0.0));
+    }
+}
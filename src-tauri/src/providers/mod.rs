@@ -1,23 +1,42 @@
+pub mod cache;
 pub mod config;
+pub mod discovery;
 pub mod google;
+pub mod local_embeddings;
+pub mod mock;
 pub mod ollama;
+pub mod ollama_pool;
 pub mod openai;
+pub mod reasoning;
 pub mod registry;
+pub mod retry;
+pub mod scheduler;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
 
+pub use cache::{CachingProvider, ResponseCache};
 pub use google::GoogleProvider;
+pub use local_embeddings::LocalEmbeddingProvider;
+pub use mock::MockProvider;
 pub use ollama::OllamaProvider;
+pub use ollama_pool::{OllamaPool, OllamaPoolProvider};
 pub use openai::OpenAIProvider;
+pub use retry::{RetryPolicy, RetryingProvider};
+pub use scheduler::{RequestPriority, RequestScheduler, ScheduledProvider, SchedulerSlot};
 
 /// AI Provider error types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProviderError {
     NetworkError(String),
     AuthenticationError(String),
-    RateLimitError(String),
+    RateLimitError {
+        message: String,
+        /// Wait hint from `Retry-After` / rate-limit reset headers
+        retry_after_ms: Option<u64>,
+    },
     ModelNotFound(String),
     InvalidRequest(String),
     NotSupported(String),
@@ -30,7 +49,13 @@ impl fmt::Display for ProviderError {
         match self {
             ProviderError::NetworkError(msg) => write!(f, "Network error: {}", msg),
             ProviderError::AuthenticationError(msg) => write!(f, "Authentication failed: {}", msg),
-            ProviderError::RateLimitError(msg) => write!(f, "Rate limit exceeded: {}", msg),
+            ProviderError::RateLimitError {
+                message,
+                retry_after_ms,
+            } => match retry_after_ms {
+                Some(ms) => write!(f, "Rate limit exceeded: {} (retry after {}ms)", message, ms),
+                None => write!(f, "Rate limit exceeded: {}", message),
+            },
             ProviderError::ModelNotFound(msg) => write!(f, "Model not found: {}", msg),
             ProviderError::InvalidRequest(msg) => write!(f, "Invalid request: {}", msg),
             ProviderError::NotSupported(msg) => write!(f, "Not supported: {}", msg),
@@ -42,6 +67,27 @@ impl fmt::Display for ProviderError {
 
 impl std::error::Error for ProviderError {}
 
+impl ProviderError {
+    /// Whether the retry layer should try again (429s and transient network failures)
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::RateLimitError { .. } | ProviderError::NetworkError(_)
+        )
+    }
+
+    /// Provider-supplied wait hint, if any
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ProviderError::RateLimitError {
+                retry_after_ms: Some(ms),
+                ..
+            } => Some(Duration::from_millis(*ms)),
+            _ => None,
+        }
+    }
+}
+
 /// Provider type classification
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[allow(dead_code)]
@@ -51,6 +97,81 @@ pub enum ProviderType {
     Hybrid,
 }
 
+/// Output format constraint
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    #[default]
+    Text,
+    /// Any valid JSON object
+    Json,
+    /// JSON matching a JSON Schema
+    JsonSchema { schema: serde_json::Value },
+}
+
+impl ResponseFormat {
+    pub fn is_text(&self) -> bool {
+        *self == ResponseFormat::Text
+    }
+}
+
+/// Optional sampling parameters (per request, or per agent in `agents.json`)
+///
+/// Unset values are left to the provider's defaults. Providers that lack a
+/// parameter ignore it (e.g. Gemini has no `top_k` for every model).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationParams {
+    /// Sampling seed for reproducible output; also part of the response cache key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(default, skip_serializing_if = "ResponseFormat::is_text")]
+    pub response_format: ResponseFormat,
+}
+
+/// File sent alongside a prompt (currently images for vision models)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// MIME type, e.g. "image/png"
+    pub mime_type: String,
+    /// Base64-encoded bytes (no `data:` prefix)
+    pub data: String,
+    /// Original file name, shown to models that can't see the image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Attachment {
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
+
+    /// `data:` URL (OpenAI image_url parts)
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
+
+    /// Text stand-in for models without vision
+    pub fn describe(&self) -> String {
+        let size_kb = self.data.len() * 3 / 4 / 1024;
+        format!(
+            "[Attached {} '{}' ({} KB) - not visible to this model]",
+            self.mime_type,
+            self.name.as_deref().unwrap_or("unnamed"),
+            size_kb
+        )
+    }
+}
+
 /// Text generation request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationRequest {
@@ -60,6 +181,32 @@ pub struct GenerationRequest {
     pub temperature: f32,
     pub max_tokens: Option<usize>,
     pub stream: bool,
+    /// Seed, nucleus/top-k sampling, stop sequences, penalties and output format
+    #[serde(default, flatten)]
+    pub params: GenerationParams,
+    /// Skip the response cache lookup (a fresh response still refreshes the entry)
+    #[serde(default)]
+    pub bypass_cache: bool,
+    /// Images for vision-capable models
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+impl GenerationRequest {
+    pub fn images(&self) -> impl Iterator<Item = &Attachment> {
+        self.attachments.iter().filter(|a| a.is_image())
+    }
+
+    /// For models without vision: drop the images and mention them in the prompt instead
+    pub fn without_images(mut self) -> Self {
+        let descriptions: Vec<String> = self.images().map(Attachment::describe).collect();
+        if descriptions.is_empty() {
+            return self;
+        }
+        self.attachments.retain(|a| !a.is_image());
+        self.prompt = format!("{}\n\n{}", self.prompt, descriptions.join("\n"));
+        self
+    }
 }
 
 /// Text generation response
@@ -75,6 +222,12 @@ pub struct GenerationResponse {
     #[serde(default)]
     pub output_tokens: Option<usize>,
     pub finish_reason: FinishReason,
+    /// Retries spent by the retry layer before this response succeeded
+    #[serde(default)]
+    pub retries: u32,
+    /// Reasoning trace (`<think>` blocks) split from `text`; kept out of other agents' context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -95,6 +248,12 @@ pub struct ModelInfo {
     pub context_length: usize,
     pub supports_embeddings: bool,
     pub supports_function_calling: bool,
+    /// Accepts image attachments
+    #[serde(default)]
+    pub supports_vision: bool,
+    /// Parameter count when the provider reports it (e.g. 7_600_000_000)
+    #[serde(default)]
+    pub parameter_count: Option<u64>,
 }
 
 /// Provider health status
@@ -119,8 +278,32 @@ pub trait AIProvider: Send + Sync {
         request: GenerationRequest,
     ) -> Result<GenerationResponse, ProviderError>;
     async fn embed(&self, text: &str) -> Result<Vec<f32>, ProviderError>;
+
+    /// Embed several texts, in order; providers with a batch endpoint override this
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for text in texts {
+            vectors.push(self.embed(text).await?);
+        }
+        Ok(vectors)
+    }
+
     async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError>;
 
+    /// Metadata for one model; providers with a per-model endpoint override this
+    async fn describe_model(&self, model: &str) -> Result<ModelInfo, ProviderError> {
+        self.list_models()
+            .await?
+            .into_iter()
+            .find(|m| m.id == model)
+            .ok_or_else(|| ProviderError::ModelNotFound(model.to_string()))
+    }
+
+    /// Model used by `embed` (recorded alongside stored vectors)
+    fn embedding_model(&self) -> &str {
+        self.name()
+    }
+
     /// Health & status
     async fn health_check(&self) -> Result<ProviderHealth, ProviderError>;
     fn is_available(&self) -> bool;
@@ -2,10 +2,13 @@ use crate::logger::{LogLevel, Logger};
 use crate::prompt;
 use crate::providers::{
     AIProvider, FinishReason, GenerationRequest, GenerationResponse, ModelInfo, ProviderError,
-    ProviderHealth, ProviderType,
+    ProviderHealth, ProviderType, ResponseFormat,
 };
+use crate::providers::reasoning::{merge_reasoning, split_reasoning};
+use crate::providers::retry;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -16,11 +19,64 @@ struct OllamaRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     system: Option<String>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
+    /// Base64 images for multimodal models (llava, llama3.2-vision, ...)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
+}
+
+/// Base64 payloads for Ollama's `images` field
+pub fn request_images(request: &GenerationRequest) -> Vec<String> {
+    request.images().map(|image| image.data.clone()).collect()
+}
+
+/// Map temperature, max_tokens and sampling params to Ollama `options`
+pub fn request_options(request: &GenerationRequest) -> serde_json::Value {
+    let params = &request.params;
+    let mut options = serde_json::Map::new();
+    options.insert("temperature".to_string(), json!(request.temperature));
+    if let Some(max_tokens) = request.max_tokens {
+        options.insert("num_predict".to_string(), json!(max_tokens));
+    }
+    if let Some(seed) = params.seed {
+        options.insert("seed".to_string(), json!(seed));
+    }
+    if let Some(top_p) = params.top_p {
+        options.insert("top_p".to_string(), json!(top_p));
+    }
+    if let Some(top_k) = params.top_k {
+        options.insert("top_k".to_string(), json!(top_k));
+    }
+    if !params.stop.is_empty() {
+        options.insert("stop".to_string(), json!(params.stop));
+    }
+    if let Some(penalty) = params.presence_penalty {
+        options.insert("presence_penalty".to_string(), json!(penalty));
+    }
+    if let Some(penalty) = params.frequency_penalty {
+        options.insert("frequency_penalty".to_string(), json!(penalty));
+    }
+    serde_json::Value::Object(options)
+}
+
+/// Ollama `format`: "json" or a JSON Schema (structured outputs)
+pub fn request_format(format: &ResponseFormat) -> Option<serde_json::Value> {
+    match format {
+        ResponseFormat::Text => None,
+        ResponseFormat::Json => Some(json!("json")),
+        ResponseFormat::JsonSchema { schema } => Some(schema.clone()),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct OllamaResponse {
     response: String,
+    /// Separate trace from Ollama's `think` mode
+    #[serde(default)]
+    thinking: Option<String>,
     #[serde(default)]
     prompt_eval_count: Option<usize>,
     #[serde(default)]
@@ -40,6 +96,89 @@ struct OllamaEmbeddingResponse {
     embedding: Vec<f32>,
 }
 
+/// `/api/embed` (Ollama 0.3+) takes a list of inputs
+#[derive(Debug, Clone, Serialize)]
+struct OllamaBatchEmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OllamaBatchEmbeddingResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Parse Ollama's `parameter_size` ("7.6B", "137M") into a count
+pub fn parse_parameter_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let (number, multiplier) = match size.chars().last()?.to_ascii_uppercase() {
+        'B' => (&size[..size.len() - 1], 1e9),
+        'M' => (&size[..size.len() - 1], 1e6),
+        'K' => (&size[..size.len() - 1], 1e3),
+        _ => (size, 1.0),
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|n| (n * multiplier).round() as u64)
+}
+
+/// Build `ModelInfo` from an `/api/show` response
+///
+/// Context length is the `num_ctx` the model is configured with when set
+/// (that is what requests actually get), else the architecture maximum.
+fn model_info_from_show(model: &str, show: &serde_json::Value) -> ModelInfo {
+    let model_info = show["model_info"].as_object();
+
+    let arch_context = model_info.and_then(|info| {
+        info.iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+    });
+    let num_ctx = show["parameters"].as_str().and_then(|params| {
+        params.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some("num_ctx"), Some(value)) => value.parse::<u64>().ok(),
+                _ => None,
+            }
+        })
+    });
+
+    let capabilities: Vec<&str> = show["capabilities"]
+        .as_array()
+        .map(|caps| caps.iter().filter_map(|c| c.as_str()).collect())
+        .unwrap_or_default();
+    let template = show["template"].as_str().unwrap_or("");
+
+    let parameter_count = model_info
+        .and_then(|info| info.get("general.parameter_count"))
+        .and_then(|value| value.as_u64())
+        .or_else(|| {
+            show["details"]["parameter_size"]
+                .as_str()
+                .and_then(parse_parameter_size)
+        });
+
+    ModelInfo {
+        id: model.to_string(),
+        name: model.to_string(),
+        context_length: num_ctx.or(arch_context).unwrap_or(8192) as usize,
+        supports_embeddings: capabilities.contains(&"embedding")
+            || model_info.is_some_and(|info| info.keys().any(|k| k.ends_with(".pooling_type"))),
+        // Older Ollama versions lack `capabilities`; tool-capable templates mention .Tools
+        supports_function_calling: capabilities.contains(&"tools") || template.contains(".Tools"),
+        supports_vision: capabilities.contains(&"vision")
+            || !show["projector_info"].is_null()
+            || model_info.is_some_and(|info| info.keys().any(|k| k.contains(".vision."))),
+        parameter_count,
+    }
+}
+
+/// Embedding model used unless one is configured
+pub const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+
 /// Ollama AI provider implementation
 pub struct OllamaProvider {
     base_url: String,
@@ -63,7 +202,7 @@ impl OllamaProvider {
         Self {
             base_url,
             default_model,
-            embedding_model: "nomic-embed-text".to_string(),
+            embedding_model: DEFAULT_EMBEDDING_MODEL.to_string(),
             timeout: Duration::from_secs(120),
             logger,
             auth: None,
@@ -109,6 +248,10 @@ impl AIProvider for OllamaProvider {
 
         let endpoint = format!("{}/api/generate", self.base_url);
 
+        let options = request_options(&request);
+        let format = request_format(&request.params.response_format);
+        let images = request_images(&request);
+
         // Use system prompt if provided, otherwise default to TCOD context
         let system_prompt = match request.system_prompt {
             Some(system) => system, // Caller is responsible for composing it (e.g. topic_manager calls compose_system_prompt)
@@ -120,6 +263,9 @@ impl AIProvider for OllamaProvider {
             prompt: request.prompt,
             system: Some(system_prompt),
             stream: false,
+            options: Some(options),
+            format,
+            images,
         };
 
         let client = reqwest::Client::builder()
@@ -138,6 +284,15 @@ impl AIProvider for OllamaProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        if response.status().as_u16() == 429 {
+            // Ollama answers 429 when its request queue (OLLAMA_MAX_QUEUE) is full
+            return Err(ProviderError::RateLimitError {
+                message: "Ollama server busy".to_string(),
+                retry_after_ms: retry::retry_after_from_headers(response.headers())
+                    .map(|d| d.as_millis() as u64),
+            });
+        }
+
         if !response.status().is_success() {
             return Err(ProviderError::NetworkError(format!(
                 "Ollama returned status: {}",
@@ -164,13 +319,16 @@ impl AIProvider for OllamaProvider {
             &format!("✅ Generated {} chars ({} tokens): '{}{}'", ollama_response.response.len(), tokens_used, preview.replace('\n', " "), suffix),
         );
 
+        let split = split_reasoning(&ollama_response.response);
         Ok(GenerationResponse {
-            text: ollama_response.response,
+            text: split.answer,
             model: request.model,
             tokens_used,
             input_tokens,
             output_tokens,
             finish_reason: FinishReason::Stop,
+            retries: 0,
+            reasoning: merge_reasoning(ollama_response.thinking, split.reasoning),
         })
     }
 
@@ -228,6 +386,69 @@ impl AIProvider for OllamaProvider {
         Ok(embed_response.embedding)
     }
 
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        self.logger.log(
+            LogLevel::Debug,
+            "ollama_provider",
+            &format!("🔢 Generating {} embeddings in one batch", texts.len()),
+        );
+
+        let endpoint = format!("{}/api/embed", self.base_url);
+        let embed_request = OllamaBatchEmbeddingRequest {
+            model: &self.embedding_model,
+            input: texts,
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| ProviderError::InternalError(e.to_string()))?;
+
+        let mut request_builder = client.post(&endpoint).json(&embed_request);
+
+        if let Some((username, password)) = &self.auth {
+            request_builder = request_builder.basic_auth(username, Some(password));
+        }
+
+        let response = request_builder
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        // Servers older than 0.3 only have the single-text endpoint
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            let mut vectors = Vec::with_capacity(texts.len());
+            for text in texts {
+                vectors.push(self.embed(text).await?);
+            }
+            return Ok(vectors);
+        }
+        if !response.status().is_success() {
+            return Err(ProviderError::NetworkError(format!(
+                "Ollama embeddings returned status: {}",
+                response.status()
+            )));
+        }
+
+        let embed_response: OllamaBatchEmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::InternalError(e.to_string()))?;
+        if embed_response.embeddings.len() != texts.len() {
+            return Err(ProviderError::InternalError(format!(
+                "Ollama returned {} embeddings for {} inputs",
+                embed_response.embeddings.len(),
+                texts.len()
+            )));
+        }
+
+        Ok(embed_response.embeddings)
+    }
+
+    fn embedding_model(&self) -> &str {
+        &self.embedding_model
+    }
+
     async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
         self.logger.log(
             LogLevel::Debug,
@@ -268,9 +489,16 @@ impl AIProvider for OllamaProvider {
                 m["name"].as_str().map(|name| ModelInfo {
                     id: name.to_string(),
                     name: name.to_string(),
-                    context_length: 8192, // Default, Ollama doesn't expose this
+                    context_length: 8192, // Tags don't carry it; see describe_model
                     supports_embeddings: name.contains("embed"),
                     supports_function_calling: false,
+                    // CLIP / mllama projector families mark multimodal models
+                    supports_vision: m["details"]["families"].as_array().is_some_and(|families| {
+                        families.iter().any(|f| matches!(f.as_str(), Some("clip" | "mllama")))
+                    }),
+                    parameter_count: m["details"]["parameter_size"]
+                        .as_str()
+                        .and_then(parse_parameter_size),
                 })
             })
             .collect();
@@ -284,6 +512,42 @@ impl AIProvider for OllamaProvider {
         Ok(models)
     }
 
+    async fn describe_model(&self, model: &str) -> Result<ModelInfo, ProviderError> {
+        let endpoint = format!("{}/api/show", self.base_url);
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| ProviderError::InternalError(e.to_string()))?;
+
+        let mut request_builder = client.post(&endpoint).json(&json!({ "model": model }));
+        if let Some((username, password)) = &self.auth {
+            request_builder = request_builder.basic_auth(username, Some(password));
+        }
+
+        let response = request_builder
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+
+        if response.status().as_u16() == 404 {
+            return Err(ProviderError::ModelNotFound(model.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(ProviderError::NetworkError(format!(
+                "Ollama show returned status: {}",
+                response.status()
+            )));
+        }
+
+        let show: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::InternalError(e.to_string()))?;
+
+        Ok(model_info_from_show(model, &show))
+    }
+
     async fn health_check(&self) -> Result<ProviderHealth, ProviderError> {
         let start = Instant::now();
 
@@ -358,4 +622,69 @@ mod tests {
         assert!(provider.supports_embeddings());
         assert!(provider.is_available());
     }
+
+    #[test]
+    fn test_request_options_mapping() {
+        let request = GenerationRequest {
+            model: "qwen2.5".to_string(),
+            prompt: "hi".to_string(),
+            system_prompt: None,
+            temperature: 0.2,
+            max_tokens: Some(128),
+            stream: false,
+            params: crate::providers::GenerationParams {
+                seed: Some(7),
+                top_k: Some(40),
+                stop: vec!["###".to_string()],
+                response_format: ResponseFormat::Json,
+                ..Default::default()
+            },
+            bypass_cache: false,
+            attachments: Vec::new(),
+        };
+
+        let options = request_options(&request);
+        assert_eq!(options["seed"], 7);
+        assert_eq!(options["num_predict"], 128);
+        assert_eq!(options["top_k"], 40);
+        assert_eq!(options["stop"][0], "###");
+        assert!(options.get("top_p").is_none());
+        assert_eq!(request_format(&request.params.response_format), Some(json!("json")));
+        assert_eq!(request_format(&ResponseFormat::Text), None);
+    }
+
+    #[test]
+    fn test_parse_parameter_size() {
+        assert_eq!(parse_parameter_size("7.6B"), Some(7_600_000_000));
+        assert_eq!(parse_parameter_size("137M"), Some(137_000_000));
+        assert_eq!(parse_parameter_size("large"), None);
+    }
+
+    #[test]
+    fn test_model_info_from_show() {
+        let show = json!({
+            "parameters": "num_ctx                        32768\nstop                           \"<|im_end|>\"",
+            "template": "{{ if .Tools }}...{{ end }}",
+            "details": { "parameter_size": "7.6B" },
+            "model_info": {
+                "general.parameter_count": 7615616512u64,
+                "qwen2.context_length": 131072
+            },
+            "capabilities": ["completion", "tools"]
+        });
+        let info = model_info_from_show("qwen2.5:7b", &show);
+        assert_eq!(info.context_length, 32768);
+        assert!(info.supports_function_calling);
+        assert!(!info.supports_embeddings);
+        assert_eq!(info.parameter_count, Some(7615616512));
+
+        let embed = json!({
+            "model_info": { "nomic-bert.context_length": 2048, "nomic-bert.pooling_type": 1 },
+            "capabilities": ["embedding"]
+        });
+        let info = model_info_from_show("nomic-embed-text", &embed);
+        assert_eq!(info.context_length, 2048);
+        assert!(info.supports_embeddings);
+        assert!(!info.supports_function_calling);
+    }
 }
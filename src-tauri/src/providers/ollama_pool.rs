@@ -0,0 +1,534 @@
+// Ollama host pool - Routes requests to the least-loaded healthy host serving the model
+
+use super::ollama::DEFAULT_EMBEDDING_MODEL;
+use super::scheduler::{lane_for, SchedulerPermit, SchedulerSlot};
+use super::{
+    AIProvider, GenerationRequest, GenerationResponse, ModelInfo, OllamaProvider, ProviderError,
+    ProviderHealth, ProviderType,
+};
+use crate::config::{AppConfig, OllamaHostConfig};
+use crate::logger::Logger;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Timeout for one /api/tags poll
+const TAGS_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Debug, Default)]
+struct HostState {
+    healthy: bool,
+    /// Models from the last successful /api/tags poll (normalised, see `normalize_model`)
+    models: HashSet<String>,
+    /// Whether `models` reflects a real poll yet
+    polled: bool,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    last_checked: Option<DateTime<Utc>>,
+}
+
+/// One Ollama server in the pool
+#[derive(Debug)]
+pub struct OllamaHost {
+    config: OllamaHostConfig,
+    in_flight: AtomicUsize,
+    state: Mutex<HostState>,
+}
+
+impl OllamaHost {
+    fn new(config: OllamaHostConfig) -> Self {
+        Self {
+            config,
+            in_flight: AtomicUsize::new(0),
+            state: Mutex::new(HostState {
+                healthy: true, // optimistic until the first poll
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.config.url
+    }
+
+    /// Basic auth for this host (username-only auth is allowed, as with Ollama Guardian)
+    pub fn auth(&self) -> Option<(&str, &str)> {
+        self.config
+            .username
+            .as_deref()
+            .map(|u| (u, self.config.password.as_deref().unwrap_or("")))
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// 0 = serves the model, 1 = unknown (not polled yet), 2 = doesn't have it
+    fn model_rank(&self, model: &str) -> u8 {
+        let state = self.state.lock().unwrap();
+        if !state.polled {
+            1
+        } else if state.models.contains(&normalize_model(model)) {
+            0
+        } else {
+            2
+        }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.state.lock().unwrap().healthy
+    }
+}
+
+/// Marks a request as running on a host; dropping it frees the slot
+pub struct HostLease {
+    host: Arc<OllamaHost>,
+}
+
+impl HostLease {
+    pub fn host(&self) -> &OllamaHost {
+        &self.host
+    }
+}
+
+impl Drop for HostLease {
+    fn drop(&mut self) {
+        self.host.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Snapshot of one host for the UI / `ollama_pool_status`
+#[derive(Debug, Clone, Serialize)]
+pub struct OllamaHostStatus {
+    pub url: String,
+    pub healthy: bool,
+    pub models: Vec<String>,
+    pub in_flight: usize,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_checked: Option<DateTime<Utc>>,
+}
+
+/// Set of Ollama servers with health and load tracking
+pub struct OllamaPool {
+    hosts: Vec<Arc<OllamaHost>>,
+    /// Serialises pick + increment so concurrent requests spread out
+    select_lock: Mutex<()>,
+    logger: Arc<Logger>,
+}
+
+impl OllamaPool {
+    pub fn new(hosts: Vec<OllamaHostConfig>, logger: Arc<Logger>) -> Self {
+        Self {
+            hosts: hosts.into_iter().map(|h| Arc::new(OllamaHost::new(h))).collect(),
+            select_lock: Mutex::new(()),
+            logger,
+        }
+    }
+
+    /// Pool of `ollama_url` plus `ollama_hosts`
+    pub fn from_config(config: &AppConfig, logger: Arc<Logger>) -> Self {
+        Self::new(config.ollama_host_list(), logger)
+    }
+
+    pub fn len(&self) -> usize {
+        self.hosts.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    pub fn hosts(&self) -> &[Arc<OllamaHost>] {
+        &self.hosts
+    }
+
+    /// Host with this URL, or the primary host (`ollama_url`) when none is given
+    pub fn host(&self, url: Option<&str>) -> Result<Arc<OllamaHost>, String> {
+        match url {
+            Some(url) => {
+                let url = url.trim_end_matches('/');
+                self.hosts
+                    .iter()
+                    .find(|h| h.url().trim_end_matches('/') == url)
+                    .cloned()
+                    .ok_or_else(|| format!("Unknown Ollama host: {}", url))
+            }
+            None => self
+                .hosts
+                .first()
+                .cloned()
+                .ok_or_else(|| "No Ollama hosts configured".to_string()),
+        }
+    }
+
+    /// Reserve the best host for `model`
+    ///
+    /// Prefers hosts that serve the model, then healthy hosts, then the fewest
+    /// requests in flight (ties go to the earlier host in the config). A host
+    /// is never ruled out entirely, so a pool that is fully down still reports
+    /// the real connection error.
+    pub fn acquire(&self, model: &str) -> Option<HostLease> {
+        let _guard = self.select_lock.lock().unwrap();
+        let host = self
+            .hosts
+            .iter()
+            .enumerate()
+            .min_by_key(|(index, host)| {
+                (host.model_rank(model), !host.is_healthy(), host.in_flight(), *index)
+            })
+            .map(|(_, host)| host.clone())?;
+        host.in_flight.fetch_add(1, Ordering::SeqCst);
+        Some(HostLease { host })
+    }
+
+    /// Record a network failure; the host is skipped until a request or health poll succeeds
+    pub fn mark_failure(&self, host: &OllamaHost, error: &str) {
+        let mut state = host.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        state.last_error = Some(error.to_string());
+        if state.healthy {
+            state.healthy = false;
+            self.logger.warn(
+                "ollama_pool",
+                &format!("🔴 Ollama host {} marked down: {}", host.url(), error),
+            );
+        }
+    }
+
+    pub fn mark_success(&self, host: &OllamaHost) {
+        let mut state = host.state.lock().unwrap();
+        state.consecutive_failures = 0;
+        if !state.healthy {
+            state.healthy = true;
+            self.logger.success(
+                "ollama_pool",
+                &format!("🟢 Ollama host {} is back", host.url()),
+            );
+        }
+    }
+
+    /// Poll every host's /api/tags for health and the models it serves
+    pub async fn refresh(&self) {
+        let client = match reqwest::Client::builder()
+            .timeout(Duration::from_secs(TAGS_TIMEOUT_SECS))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                self.logger.error("ollama_pool", &format!("❌ HTTP client: {}", e));
+                return;
+            }
+        };
+
+        let polls = self.hosts.iter().map(|host| {
+            let client = client.clone();
+            async move {
+                let base_url = host.url().trim_end_matches('/');
+                let result = crate::ollama::fetch_available_models(&client, base_url, host.auth()).await;
+                (host, result)
+            }
+        });
+
+        for (host, result) in futures::future::join_all(polls).await {
+            let mut state = host.state.lock().unwrap();
+            state.last_checked = Some(Utc::now());
+            match result {
+                Ok(models) => {
+                    state.models = models.iter().map(|m| normalize_model(m)).collect();
+                    state.polled = true;
+                    state.consecutive_failures = 0;
+                    state.last_error = None;
+                    if !state.healthy {
+                        self.logger.success(
+                            "ollama_pool",
+                            &format!("🟢 Ollama host {} is back ({} models)", host.url(), models.len()),
+                        );
+                    }
+                    state.healthy = true;
+                }
+                Err(e) => {
+                    if state.healthy {
+                        self.logger.warn(
+                            "ollama_pool",
+                            &format!("🔴 Ollama host {} failed health check: {}", host.url(), e),
+                        );
+                    }
+                    state.healthy = false;
+                    state.consecutive_failures += 1;
+                    state.last_error = Some(e);
+                }
+            }
+        }
+    }
+
+    /// Poll all hosts now and then every `interval`
+    pub fn start_health_checks(self: &Arc<Self>, interval: Duration) {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            loop {
+                pool.refresh().await;
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+
+    pub fn status(&self) -> Vec<OllamaHostStatus> {
+        self.hosts
+            .iter()
+            .map(|host| {
+                let state = host.state.lock().unwrap();
+                let mut models: Vec<String> = state.models.iter().cloned().collect();
+                models.sort();
+                OllamaHostStatus {
+                    url: host.url().to_string(),
+                    healthy: state.healthy,
+                    models,
+                    in_flight: host.in_flight(),
+                    consecutive_failures: state.consecutive_failures,
+                    last_error: state.last_error.clone(),
+                    last_checked: state.last_checked,
+                }
+            })
+            .collect()
+    }
+}
+
+/// `AIProvider` that runs every call on the pool's best host for the model
+///
+/// Wrap it in a `RetryingProvider` for failover: each retry picks a host again.
+pub struct OllamaPoolProvider {
+    pool: Arc<OllamaPool>,
+    default_model: String,
+    embedding_model: String,
+    slot: Option<SchedulerSlot>,
+    logger: Arc<Logger>,
+}
+
+impl OllamaPoolProvider {
+    pub fn new(pool: Arc<OllamaPool>, default_model: String, logger: Arc<Logger>) -> Self {
+        Self {
+            pool,
+            default_model,
+            embedding_model: DEFAULT_EMBEDDING_MODEL.to_string(),
+            slot: None,
+            logger,
+        }
+    }
+
+    /// Model used (and leased a host for) by `embed`
+    #[allow(dead_code)]
+    pub fn with_embedding_model(mut self, model: String) -> Self {
+        self.embedding_model = model;
+        self
+    }
+
+    /// Queue every call on the lane of the host it is routed to
+    pub fn with_slot(mut self, slot: SchedulerSlot) -> Self {
+        self.slot = Some(slot);
+        self
+    }
+
+    /// Reserve a host for `model`, wait for its scheduler lane, and build a provider pointed at it
+    async fn lease(
+        &self,
+        model: &str,
+    ) -> Result<(HostLease, Option<SchedulerPermit>, OllamaProvider), ProviderError> {
+        let lease = self
+            .pool
+            .acquire(model)
+            .ok_or_else(|| ProviderError::InternalError("No Ollama hosts configured".to_string()))?;
+        let host = lease.host();
+        let permit = match &self.slot {
+            Some(slot) => Some(slot.acquire_on(&lane_for("ollama", host.url())).await),
+            None => None,
+        };
+        let provider = OllamaProvider::new(host.url().to_string(), model.to_string(), self.logger.clone())
+            .with_auth(host.config.username.clone(), host.config.password.clone())
+            .with_embedding_model(self.embedding_model.clone());
+        Ok((lease, permit, provider))
+    }
+
+    fn record<T>(&self, host: &OllamaHost, result: &Result<T, ProviderError>) {
+        match result {
+            Ok(_) => self.pool.mark_success(host),
+            Err(ProviderError::NetworkError(message)) => self.pool.mark_failure(host, message),
+            Err(_) => {}
+        }
+    }
+}
+
+#[async_trait]
+impl AIProvider for OllamaPoolProvider {
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::Network {
+            requires_internet: false,
+        }
+    }
+
+    async fn generate(
+        &self,
+        request: GenerationRequest,
+    ) -> Result<GenerationResponse, ProviderError> {
+        let (lease, _permit, provider) = self.lease(&request.model).await?;
+        let result = provider.generate(request).await;
+        self.record(lease.host(), &result);
+        result
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, ProviderError> {
+        let (lease, _permit, provider) = self.lease(&self.embedding_model).await?;
+        let result = provider.embed(text).await;
+        self.record(lease.host(), &result);
+        result
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let (lease, _permit, provider) = self.lease(&self.embedding_model).await?;
+        let result = provider.embed_batch(texts).await;
+        self.record(lease.host(), &result);
+        result
+    }
+
+    fn embedding_model(&self) -> &str {
+        &self.embedding_model
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        let (lease, _permit, provider) = self.lease(&self.default_model).await?;
+        let result = provider.list_models().await;
+        self.record(lease.host(), &result);
+        result
+    }
+
+    async fn describe_model(&self, model: &str) -> Result<ModelInfo, ProviderError> {
+        let (lease, _permit, provider) = self.lease(model).await?;
+        let result = provider.describe_model(model).await;
+        self.record(lease.host(), &result);
+        result
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth, ProviderError> {
+        let status = self.pool.status();
+        let healthy = status.iter().filter(|h| h.healthy).count();
+        Ok(ProviderHealth {
+            healthy: healthy > 0,
+            latency_ms: None,
+            error: (healthy == 0).then(|| "No healthy Ollama hosts".to_string()),
+        })
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn max_context_length(&self) -> usize {
+        8192
+    }
+}
+
+/// Ollama treats "llama3" and "llama3:latest" as the same model
+pub fn normalize_model(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("{}:latest", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(url: &str) -> OllamaHostConfig {
+        OllamaHostConfig {
+            url: url.to_string(),
+            username: None,
+            password: None,
+        }
+    }
+
+    fn pool() -> OllamaPool {
+        OllamaPool::new(
+            vec![host("http://a:11434"), host("http://b:11434"), host("http://c:11434")],
+            Arc::new(Logger::new(false)),
+        )
+    }
+
+    fn serve(pool: &OllamaPool, index: usize, models: &[&str]) {
+        let mut state = pool.hosts[index].state.lock().unwrap();
+        state.models = models.iter().map(|m| normalize_model(m)).collect();
+        state.polled = true;
+    }
+
+    #[test]
+    fn test_routes_to_least_loaded_host_with_model() {
+        let pool = pool();
+        serve(&pool, 0, &["llama3"]);
+        serve(&pool, 1, &["llama3:latest", "qwen2.5:7b"]);
+        serve(&pool, 2, &["qwen2.5:7b"]);
+
+        let first = pool.acquire("llama3:latest").unwrap();
+        assert_eq!(first.host().url(), "http://a:11434");
+        let second = pool.acquire("llama3").unwrap();
+        assert_eq!(second.host().url(), "http://b:11434");
+
+        // Only the hosts serving qwen are considered; b is busier than c
+        let third = pool.acquire("qwen2.5:7b").unwrap();
+        assert_eq!(third.host().url(), "http://c:11434");
+
+        drop(first);
+        assert_eq!(pool.hosts[0].in_flight(), 0);
+        assert_eq!(pool.acquire("llama3").unwrap().host().url(), "http://a:11434");
+    }
+
+    #[test]
+    fn test_fails_over_when_host_goes_down() {
+        let pool = pool();
+        serve(&pool, 0, &["llama3"]);
+        serve(&pool, 1, &["llama3"]);
+
+        let lease = pool.acquire("llama3").unwrap();
+        assert_eq!(lease.host().url(), "http://a:11434");
+        pool.mark_failure(lease.host(), "connection refused");
+        drop(lease);
+        assert!(!pool.hosts[0].is_healthy());
+
+        assert_eq!(pool.acquire("llama3").unwrap().host().url(), "http://b:11434");
+
+        pool.mark_success(&pool.hosts[0]);
+        assert!(pool.hosts[0].is_healthy());
+        assert_eq!(pool.status()[0].consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_host_list_puts_primary_first_and_dedupes() {
+        let config = AppConfig {
+            ollama_url: "http://a:11434".to_string(),
+            ollama_username: Some("council".to_string()),
+            ollama_hosts: vec![host("http://a:11434/"), host("http://b:11434")],
+            ..Default::default()
+        };
+        let hosts = config.ollama_host_list();
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].username.as_deref(), Some("council"));
+        assert_eq!(hosts[1].url, "http://b:11434");
+    }
+}
@@ -1,8 +1,10 @@
 use crate::logger::{LogLevel, Logger};
 use crate::providers::{
     AIProvider, FinishReason, GenerationRequest, GenerationResponse, ModelInfo, ProviderError,
-    ProviderHealth, ProviderType,
+    ProviderHealth, ProviderType, ResponseFormat,
 };
+use crate::providers::reasoning::{merge_reasoning, split_reasoning};
+use crate::providers::retry;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -15,7 +17,48 @@ use std::time::{Duration, Instant};
 #[derive(Debug, Clone, Serialize)]
 struct ChatMessage {
     role: String,
-    content: String,
+    content: MessageContent,
+}
+
+/// Plain string, or content parts when images are attached
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ImageUrl {
+    url: String,
+}
+
+/// User message content: the prompt followed by any images as data URLs
+fn user_content(request: &GenerationRequest) -> MessageContent {
+    let images: Vec<ContentPart> = request
+        .images()
+        .map(|image| ContentPart::ImageUrl {
+            image_url: ImageUrl {
+                url: image.data_url(),
+            },
+        })
+        .collect();
+    if images.is_empty() {
+        return MessageContent::Text(request.prompt.clone());
+    }
+
+    let mut parts = vec![ContentPart::Text {
+        text: request.prompt.clone(),
+    }];
+    parts.extend(images);
+    MessageContent::Parts(parts)
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -27,6 +70,33 @@ struct ChatCompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     max_tokens: Option<usize>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    /// Not part of the OpenAI API; OpenRouter forwards it to models that support it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+}
+
+/// Map `ResponseFormat` to the Chat Completions `response_format` object
+fn response_format_json(format: &ResponseFormat) -> Option<serde_json::Value> {
+    match format {
+        ResponseFormat::Text => None,
+        ResponseFormat::Json => Some(serde_json::json!({ "type": "json_object" })),
+        ResponseFormat::JsonSchema { schema } => Some(serde_json::json!({
+            "type": "json_schema",
+            "json_schema": { "name": "response", "schema": schema, "strict": true }
+        })),
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -44,6 +114,23 @@ struct ChatChoice {
 #[derive(Debug, Clone, Deserialize)]
 struct ChatMessageResponse {
     content: Option<String>,
+    /// Trace from reasoning models (DeepSeek)
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    /// Trace from reasoning models (OpenRouter); some gateways send both fields
+    #[serde(default)]
+    reasoning: Option<String>,
+}
+
+impl ChatMessageResponse {
+    /// Reported trace from either field, once if both carry the same text
+    fn reported_reasoning(&self) -> Option<String> {
+        let non_empty = |r: &Option<String>| r.clone().filter(|r| !r.trim().is_empty());
+        match (non_empty(&self.reasoning_content), non_empty(&self.reasoning)) {
+            (Some(a), Some(b)) if a.trim() != b.trim() => Some(format!("{}\n\n{}", a.trim(), b.trim())),
+            (a, b) => a.or(b),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -60,9 +147,10 @@ struct Usage {
 // ============================================================================
 
 #[derive(Debug, Clone, Serialize)]
-struct EmbeddingRequest {
+struct EmbeddingRequest<'a> {
     model: String,
-    input: String,
+    /// One or more texts; the API embeds a list in one call
+    input: &'a [String],
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -73,6 +161,8 @@ struct EmbeddingResponse {
 #[derive(Debug, Clone, Deserialize)]
 struct EmbeddingData {
     embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
 }
 
 // ============================================================================
@@ -87,6 +177,91 @@ struct ModelsResponse {
 #[derive(Debug, Clone, Deserialize)]
 struct ModelData {
     id: String,
+    #[serde(default)]
+    name: Option<String>,
+    /// OpenRouter only
+    #[serde(default)]
+    context_length: Option<usize>,
+    /// OpenRouter only (e.g. "tools", "response_format")
+    #[serde(default)]
+    supported_parameters: Option<Vec<String>>,
+    /// OpenRouter only
+    #[serde(default)]
+    architecture: Option<ModelArchitecture>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModelArchitecture {
+    #[serde(default)]
+    input_modalities: Vec<String>,
+    #[serde(default)]
+    output_modalities: Vec<String>,
+}
+
+/// Known limits for OpenAI models (their `/models` endpoint has no metadata)
+///
+/// Returns (context length, embeddings, tool calling).
+fn openai_model_limits(id: &str) -> (usize, bool, bool) {
+    let id = id.to_lowercase();
+    if id.contains("embedding") {
+        (8191, true, false)
+    } else if id.starts_with("gpt-5") {
+        (400_000, false, true)
+    } else if id.starts_with("gpt-4.1") {
+        (1_047_576, false, true)
+    } else if id.starts_with("o1") || id.starts_with("o3") || id.starts_with("o4") {
+        (200_000, false, true)
+    } else if id.starts_with("gpt-4o") || id.starts_with("gpt-4-turbo") {
+        (128_000, false, true)
+    } else if id.starts_with("gpt-4") {
+        (8192, false, true)
+    } else if id.starts_with("gpt-3.5-turbo") {
+        (16_385, false, true)
+    } else {
+        // Audio, image and moderation models
+        (4096, false, false)
+    }
+}
+
+/// OpenAI models that accept image input
+fn openai_supports_vision(id: &str) -> bool {
+    let id = id.to_lowercase();
+    let multimodal = ["gpt-4o", "gpt-4.1", "gpt-4-turbo", "gpt-5", "o1", "o3", "o4"];
+    multimodal.iter().any(|prefix| id.starts_with(prefix))
+        && !id.contains("audio")
+        && !id.contains("realtime")
+        && !id.starts_with("o1-mini")
+        && !id.starts_with("o3-mini")
+}
+
+impl ModelData {
+    fn into_model_info(self) -> ModelInfo {
+        let (known_context, known_embeddings, known_tools) = openai_model_limits(&self.id);
+
+        // OpenRouter reports real metadata; plain OpenAI falls back to the table
+        let supports_function_calling = match &self.supported_parameters {
+            Some(params) => params.iter().any(|p| p == "tools"),
+            None => known_tools,
+        };
+        let supports_embeddings = match &self.architecture {
+            Some(arch) => arch.output_modalities.iter().any(|m| m == "embeddings"),
+            None => known_embeddings,
+        };
+        let supports_vision = match &self.architecture {
+            Some(arch) => arch.input_modalities.iter().any(|m| m == "image"),
+            None => openai_supports_vision(&self.id),
+        };
+
+        ModelInfo {
+            name: self.name.unwrap_or_else(|| self.id.clone()),
+            id: self.id,
+            context_length: self.context_length.unwrap_or(known_context),
+            supports_embeddings,
+            supports_function_calling,
+            supports_vision,
+            parameter_count: None,
+        }
+    }
 }
 
 // ============================================================================
@@ -210,13 +385,13 @@ impl AIProvider for OpenAIProvider {
         if let Some(system) = &request.system_prompt {
             messages.push(ChatMessage {
                 role: "system".to_string(),
-                content: system.clone(),
+                content: MessageContent::Text(system.clone()),
             });
         }
 
         messages.push(ChatMessage {
             role: "user".to_string(),
-            content: request.prompt.clone(),
+            content: user_content(&request),
         });
 
         let chat_request = ChatCompletionRequest {
@@ -225,6 +400,13 @@ impl AIProvider for OpenAIProvider {
             temperature: Some(request.temperature),
             max_tokens: request.max_tokens,
             stream: false,
+            seed: request.params.seed,
+            top_p: request.params.top_p,
+            top_k: if self.provider_name == "OpenAI" { None } else { request.params.top_k },
+            stop: request.params.stop.clone(),
+            presence_penalty: request.params.presence_penalty,
+            frequency_penalty: request.params.frequency_penalty,
+            response_format: response_format_json(&request.params.response_format),
         };
 
         let client = reqwest::Client::builder()
@@ -252,12 +434,17 @@ impl AIProvider for OpenAIProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = retry::retry_after_from_headers(response.headers());
             let error_text = response.text().await.unwrap_or_default();
             
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthenticationError(format!("[{}] {}", self.provider_name, error_text)),
-                429 => ProviderError::RateLimitError(format!("[{}] {}", self.provider_name, error_text)),
+                429 => ProviderError::RateLimitError {
+                    message: format!("[{}] {}", self.provider_name, error_text),
+                    retry_after_ms: retry_after.map(|d| d.as_millis() as u64),
+                },
                 404 => ProviderError::ModelNotFound(format!("[{}] {}", self.provider_name, error_text)),
+                400..=499 => ProviderError::InvalidRequest(format!("[{}] Status {}: {}", self.provider_name, status, error_text)),
                 _ => ProviderError::NetworkError(format!("[{}] Status {}: {}", self.provider_name, status, error_text)),
             });
         }
@@ -267,11 +454,20 @@ impl AIProvider for OpenAIProvider {
             .await
             .map_err(|e| ProviderError::InternalError(format!("Failed to parse response: {}", e)))?;
 
-        let text = chat_response
+        let raw_text = chat_response
             .choices
             .first()
             .and_then(|c| c.message.content.clone())
             .unwrap_or_default();
+        let split = split_reasoning(&raw_text);
+        let text = split.answer;
+        let reasoning = merge_reasoning(
+            chat_response
+                .choices
+                .first()
+                .and_then(|c| c.message.reported_reasoning()),
+            split.reasoning,
+        );
 
         let tokens_used = chat_response.usage.as_ref().map(|u| u.total_tokens).unwrap_or(0);
         let input_tokens = chat_response.usage.as_ref().map(|u| u.prompt_tokens);
@@ -308,10 +504,19 @@ impl AIProvider for OpenAIProvider {
             input_tokens,
             output_tokens,
             finish_reason,
+            retries: 0,
+            reasoning,
         })
     }
 
     async fn embed(&self, text: &str) -> Result<Vec<f32>, ProviderError> {
+        self.embed_batch(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| ProviderError::InternalError("No embedding data returned".to_string()))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
         // OpenRouter doesn't support embeddings
         if self.is_openrouter() {
             return Err(ProviderError::NotSupported(
@@ -322,14 +527,14 @@ impl AIProvider for OpenAIProvider {
         self.logger.log(
             LogLevel::Debug,
             "openai_provider",
-            &format!("🔢 [{}] Generating embedding for {} chars", self.provider_name, text.len()),
+            &format!("🔢 [{}] Generating {} embeddings", self.provider_name, texts.len()),
         );
 
         let endpoint = format!("{}/embeddings", self.base_url);
 
         let embed_request = EmbeddingRequest {
             model: self.embedding_model.clone(),
-            input: text.to_string(),
+            input: texts,
         };
 
         let client = reqwest::Client::builder()
@@ -348,10 +553,14 @@ impl AIProvider for OpenAIProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = retry::retry_after_from_headers(response.headers());
             let error_text = response.text().await.unwrap_or_default();
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthenticationError(error_text),
-                429 => ProviderError::RateLimitError(error_text),
+                429 => ProviderError::RateLimitError {
+                    message: error_text,
+                    retry_after_ms: retry_after.map(|d| d.as_millis() as u64),
+                },
                 _ => ProviderError::NetworkError(format!("Status {}: {}", status, error_text)),
             });
         }
@@ -361,19 +570,33 @@ impl AIProvider for OpenAIProvider {
             .await
             .map_err(|e| ProviderError::InternalError(format!("Failed to parse embedding response: {}", e)))?;
 
-        let embedding = embed_response
-            .data
-            .first()
-            .map(|d| d.embedding.clone())
-            .ok_or_else(|| ProviderError::InternalError("No embedding data returned".to_string()))?;
+        // Entries carry their input index; order is not guaranteed
+        let mut data = embed_response.data;
+        if data.len() != texts.len() {
+            return Err(ProviderError::InternalError(format!(
+                "Expected {} embeddings, got {}",
+                texts.len(),
+                data.len()
+            )));
+        }
+        data.sort_by_key(|d| d.index);
 
         self.logger.log(
             LogLevel::Success,
             "openai_provider",
-            &format!("✅ [{}] Generated embedding with {} dimensions", self.provider_name, embedding.len()),
+            &format!(
+                "✅ [{}] Generated {} embeddings with {} dimensions",
+                self.provider_name,
+                data.len(),
+                data.first().map(|d| d.embedding.len()).unwrap_or(0)
+            ),
         );
 
-        Ok(embedding)
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+
+    fn embedding_model(&self) -> &str {
+        &self.embedding_model
     }
 
     async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
@@ -410,13 +633,7 @@ impl AIProvider for OpenAIProvider {
         let models: Vec<ModelInfo> = models_response
             .data
             .into_iter()
-            .map(|m| ModelInfo {
-                id: m.id.clone(),
-                name: m.id,
-                context_length: 128000, // Default, varies by model
-                supports_embeddings: false,
-                supports_function_calling: true,
-            })
+            .map(ModelData::into_model_info)
             .collect();
 
         self.logger.log(
@@ -507,4 +724,92 @@ mod tests {
 
         assert!(!provider.is_available());
     }
+
+    #[test]
+    fn test_response_format_mapping() {
+        assert!(response_format_json(&ResponseFormat::Text).is_none());
+        assert_eq!(
+            response_format_json(&ResponseFormat::Json).unwrap()["type"],
+            "json_object"
+        );
+
+        let schema = serde_json::json!({ "type": "object" });
+        let format = response_format_json(&ResponseFormat::JsonSchema { schema: schema.clone() }).unwrap();
+        assert_eq!(format["type"], "json_schema");
+        assert_eq!(format["json_schema"]["schema"], schema);
+    }
+
+    #[test]
+    fn test_model_metadata_mapping() {
+        let openai: ModelsResponse = serde_json::from_value(serde_json::json!({
+            "data": [{ "id": "text-embedding-3-small" }, { "id": "gpt-4o-mini" }]
+        }))
+        .unwrap();
+        let models: Vec<ModelInfo> = openai.data.into_iter().map(ModelData::into_model_info).collect();
+        assert!(models[0].supports_embeddings && !models[0].supports_function_calling);
+        assert_eq!(models[1].context_length, 128_000);
+        assert!(models[1].supports_function_calling);
+
+        let openrouter: ModelsResponse = serde_json::from_value(serde_json::json!({
+            "data": [{
+                "id": "meta-llama/llama-3-8b-instruct",
+                "name": "Llama 3 8B",
+                "context_length": 8192,
+                "supported_parameters": ["temperature", "top_p"],
+                "architecture": { "output_modalities": ["text"] }
+            }]
+        }))
+        .unwrap();
+        let model = openrouter.data.into_iter().next().unwrap().into_model_info();
+        assert_eq!(model.name, "Llama 3 8B");
+        assert_eq!(model.context_length, 8192);
+        assert!(!model.supports_function_calling);
+    }
+
+    #[test]
+    fn test_reasoning_fields_are_merged() {
+        let message = |value: serde_json::Value| -> ChatMessageResponse { serde_json::from_value(value).unwrap() };
+
+        let both = message(serde_json::json!({
+            "content": "Yes.",
+            "reasoning_content": "Checked the ledger.",
+            "reasoning": "Checked the ledger."
+        }));
+        assert_eq!(both.reported_reasoning().as_deref(), Some("Checked the ledger."));
+
+        let openrouter = message(serde_json::json!({ "content": "Yes.", "reasoning": "Weighed it." }));
+        assert_eq!(openrouter.reported_reasoning().as_deref(), Some("Weighed it."));
+
+        let plain = message(serde_json::json!({ "content": "Yes.", "reasoning": null }));
+        assert_eq!(plain.reported_reasoning(), None);
+    }
+
+    #[test]
+    fn test_image_attachments_become_content_parts() {
+        let mut request = GenerationRequest {
+            model: "gpt-4o".to_string(),
+            prompt: "What does this chart show?".to_string(),
+            system_prompt: None,
+            temperature: 0.7,
+            max_tokens: None,
+            stream: false,
+            params: Default::default(),
+            bypass_cache: false,
+            attachments: Vec::new(),
+        };
+        assert_eq!(
+            serde_json::to_value(user_content(&request)).unwrap(),
+            serde_json::json!("What does this chart show?")
+        );
+
+        request.attachments.push(crate::providers::Attachment {
+            mime_type: "image/png".to_string(),
+            data: "iVBORw0KGgo=".to_string(),
+            name: Some("chart.png".to_string()),
+        });
+        let content = serde_json::to_value(user_content(&request)).unwrap();
+        assert_eq!(content[0]["type"], "text");
+        assert_eq!(content[1]["type"], "image_url");
+        assert_eq!(content[1]["image_url"]["url"], "data:image/png;base64,iVBORw0KGgo=");
+    }
 }
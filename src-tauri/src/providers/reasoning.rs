@@ -0,0 +1,112 @@
+// Reasoning traces - Split `<think>` blocks (deepseek-r1, qwq, ...) from the final answer
+
+const OPEN_TAGS: [&str; 2] = ["<think>", "<thinking>"];
+const CLOSE_TAGS: [&str; 2] = ["</think>", "</thinking>"];
+
+/// A model reply with its reasoning trace separated out
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SplitReply {
+    pub answer: String,
+    pub reasoning: Option<String>,
+}
+
+/// Split `text` into the final answer and the reasoning trace(s)
+///
+/// Handles several blocks, a missing opening tag (some chat templates put
+/// `<think>` in the prompt, so only `</think>` comes back) and an unclosed
+/// block from a truncated reply.
+pub fn split_reasoning(text: &str) -> SplitReply {
+    let mut answer = String::new();
+    let mut traces: Vec<String> = Vec::new();
+    let mut rest = text;
+
+    // Leading trace without an opening tag
+    if let Some((close_at, close_len)) = find_any(rest, &CLOSE_TAGS) {
+        if find_any(&rest[..close_at], &OPEN_TAGS).is_none() {
+            traces.push(rest[..close_at].to_string());
+            rest = &rest[close_at + close_len..];
+        }
+    }
+
+    while let Some((open_at, open_len)) = find_any(rest, &OPEN_TAGS) {
+        answer.push_str(&rest[..open_at]);
+        let inner = &rest[open_at + open_len..];
+        match find_any(inner, &CLOSE_TAGS) {
+            Some((close_at, close_len)) => {
+                traces.push(inner[..close_at].to_string());
+                rest = &inner[close_at + close_len..];
+            }
+            None => {
+                traces.push(inner.to_string());
+                rest = "";
+            }
+        }
+    }
+    answer.push_str(rest);
+
+    let traces: Vec<&str> = traces.iter().map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+    SplitReply {
+        answer: if traces.is_empty() && answer.trim() == text.trim() {
+            text.to_string()
+        } else {
+            answer.trim().to_string()
+        },
+        reasoning: (!traces.is_empty()).then(|| traces.join("\n\n")),
+    }
+}
+
+/// Combine a provider-reported trace (e.g. `reasoning_content`) with one split from the text
+pub fn merge_reasoning(reported: Option<String>, split: Option<String>) -> Option<String> {
+    match (reported.filter(|r| !r.trim().is_empty()), split) {
+        (Some(a), Some(b)) => Some(format!("{}\n\n{}", a.trim(), b)),
+        (a, b) => a.map(|r| r.trim().to_string()).or(b),
+    }
+}
+
+/// First occurrence of any of `tags` (case-insensitive): (byte offset, tag length)
+fn find_any(haystack: &str, tags: &[&str]) -> Option<(usize, usize)> {
+    let lower = haystack.to_ascii_lowercase();
+    tags.iter()
+        .filter_map(|tag| lower.find(tag).map(|at| (at, tag.len())))
+        .min_by_key(|(at, _)| *at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_reasoning_variants() {
+        let plain = split_reasoning("Just an answer.");
+        assert_eq!(plain.answer, "Just an answer.");
+        assert_eq!(plain.reasoning, None);
+
+        let tagged = split_reasoning("<think>\nWeigh both sides.\n</think>\n\nApprove it.");
+        assert_eq!(tagged.answer, "Approve it.");
+        assert_eq!(tagged.reasoning.as_deref(), Some("Weigh both sides."));
+
+        let no_open = split_reasoning("First, the risks...\n</think>\nReject.");
+        assert_eq!(no_open.answer, "Reject.");
+        assert_eq!(no_open.reasoning.as_deref(), Some("First, the risks..."));
+
+        let multiple = split_reasoning("<Think>a</Think>Yes<thinking>b</thinking>, because c.");
+        assert_eq!(multiple.answer, "Yes, because c.");
+        assert_eq!(multiple.reasoning.as_deref(), Some("a\n\nb"));
+
+        let truncated = split_reasoning("Preface <think>still going");
+        assert_eq!(truncated.answer, "Preface");
+        assert_eq!(truncated.reasoning.as_deref(), Some("still going"));
+
+        let empty = split_reasoning("<think>\n\n</think>\n\nHello");
+        assert_eq!(empty.answer, "Hello");
+        assert_eq!(empty.reasoning, None);
+    }
+
+    #[test]
+    fn test_merge_reasoning() {
+        assert_eq!(merge_reasoning(None, None), None);
+        assert_eq!(merge_reasoning(Some(" a ".into()), None).as_deref(), Some("a"));
+        assert_eq!(merge_reasoning(Some("a".into()), Some("b".into())).as_deref(), Some("a\n\nb"));
+        assert_eq!(merge_reasoning(Some("  ".into()), Some("b".into())).as_deref(), Some("b"));
+    }
+}
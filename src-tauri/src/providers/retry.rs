@@ -0,0 +1,514 @@
+use crate::logger::{LogLevel, Logger};
+use crate::providers::{
+    AIProvider, GenerationRequest, GenerationResponse, ModelInfo, ProviderError, ProviderHealth,
+    ProviderType,
+};
+use async_trait::async_trait;
+use rand::Rng;
+use reqwest::header::HeaderMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default number of retries after the first attempt
+const DEFAULT_MAX_RETRIES: u32 = 4;
+
+/// First backoff step (doubled on every retry)
+const DEFAULT_BASE_DELAY_MS: u64 = 500;
+
+/// Upper bound for a single backoff sleep
+const DEFAULT_MAX_DELAY_MS: u64 = 30_000;
+
+/// Longest provider-requested wait honoured when the policy has no budget
+const MAX_UNBUDGETED_HINT_MS: u64 = 120_000;
+
+/// Retry policy shared by all providers
+///
+/// Uses exponential backoff with equal jitter, unless the provider told us
+/// exactly how long to wait (`Retry-After` and friends), in which case that
+/// hint wins, even past `max_delay`. The optional budget bounds the total time
+/// spent on a request, including all retries and hinted waits (normally the
+/// agent's `timeout_secs`).
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub budget: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            budget: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy bounded by a total time budget in seconds (e.g. agent `timeout_secs`)
+    pub fn with_budget_secs(secs: u64) -> Self {
+        Self {
+            budget: Some(Duration::from_secs(secs)),
+            ..Self::default()
+        }
+    }
+
+    /// Policy that never retries (single attempt)
+    #[allow(dead_code)]
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (0-based), honouring a provider hint if present
+    pub fn backoff_delay(&self, retry: u32, hint: Option<Duration>) -> Duration {
+        if let Some(hint) = hint {
+            // Small jitter on top of the hint so parallel agents don't stampede together
+            let jitter_ms = rand::thread_rng().gen_range(0..=hint.as_millis().min(1000) as u64 / 10);
+            return hint + Duration::from_millis(jitter_ms);
+        }
+
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let ceiling_ms = exp.as_millis() as u64;
+        // Equal jitter: uniform in [ceiling/2, ceiling] keeps some minimum spacing
+        let delay_ms = rand::thread_rng().gen_range(ceiling_ms / 2..=ceiling_ms);
+        Duration::from_millis(delay_ms)
+    }
+}
+
+/// Result of a retried operation plus how many retries it took
+pub struct RetryOutcome<T> {
+    pub result: Result<T, ProviderError>,
+    pub retries: u32,
+}
+
+/// Run `op` until it succeeds, fails with a non-retryable error, runs out of
+/// retries, or would exceed the policy's time budget.
+pub async fn retry_with_backoff<T, F, Fut>(
+    policy: &RetryPolicy,
+    logger: Option<&Logger>,
+    label: &str,
+    mut op: F,
+) -> RetryOutcome<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ProviderError>>,
+{
+    let started = Instant::now();
+    let mut retries = 0;
+
+    loop {
+        let attempt = match policy.budget {
+            Some(budget) => {
+                let remaining = budget.saturating_sub(started.elapsed());
+                match tokio::time::timeout(remaining, op()).await {
+                    Ok(result) => result,
+                    Err(_) => Err(ProviderError::NetworkError(format!(
+                        "{} exceeded time budget of {}s",
+                        label,
+                        budget.as_secs()
+                    ))),
+                }
+            }
+            None => op().await,
+        };
+
+        let error = match attempt {
+            Ok(value) => {
+                return RetryOutcome {
+                    result: Ok(value),
+                    retries,
+                }
+            }
+            Err(e) => e,
+        };
+
+        if !error.is_retryable() || retries >= policy.max_retries {
+            return RetryOutcome {
+                result: Err(error),
+                retries,
+            };
+        }
+
+        // Hinted waits are bounded by the budget below; without one, only absurd hints are refused
+        let hint = error.retry_after();
+        if policy.budget.is_none() && hint.is_some_and(|h| h > Duration::from_millis(MAX_UNBUDGETED_HINT_MS)) {
+            if let Some(logger) = logger {
+                logger.log(
+                    LogLevel::Warning,
+                    "retry",
+                    &format!(
+                        "⏱️ {}: provider asked to wait {}ms, longer than {}ms - giving up",
+                        label,
+                        hint.unwrap_or_default().as_millis(),
+                        MAX_UNBUDGETED_HINT_MS
+                    ),
+                );
+            }
+            return RetryOutcome {
+                result: Err(error),
+                retries,
+            };
+        }
+
+        let delay = policy.backoff_delay(retries, hint);
+
+        if let Some(budget) = policy.budget {
+            if started.elapsed() + delay >= budget {
+                if let Some(logger) = logger {
+                    logger.log(
+                        LogLevel::Warning,
+                        "retry",
+                        &format!(
+                            "⏱️ {}: giving up after {} retries, next wait of {}ms would exceed {}s budget",
+                            label,
+                            retries,
+                            delay.as_millis(),
+                            budget.as_secs()
+                        ),
+                    );
+                }
+                return RetryOutcome {
+                    result: Err(error),
+                    retries,
+                };
+            }
+        }
+
+        if let Some(logger) = logger {
+            logger.log(
+                LogLevel::Warning,
+                "retry",
+                &format!(
+                    "🔁 {}: {} - retry {}/{} in {}ms",
+                    label,
+                    error,
+                    retries + 1,
+                    policy.max_retries,
+                    delay.as_millis()
+                ),
+            );
+        }
+
+        tokio::time::sleep(delay).await;
+        retries += 1;
+    }
+}
+
+/// Extract a wait hint from rate-limit response headers
+///
+/// Understands `Retry-After` (seconds or HTTP date), `retry-after-ms`,
+/// OpenAI's `x-ratelimit-reset-requests` / `x-ratelimit-reset-tokens`
+/// ("6m0s", "250ms") and OpenRouter's `x-ratelimit-reset` (epoch ms).
+pub fn retry_after_from_headers(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+        return Some(Duration::from_millis(ms.max(0.0) as u64));
+    }
+
+    if let Some(value) = header("retry-after") {
+        if let Ok(secs) = value.parse::<f64>() {
+            return Some(Duration::from_millis((secs.max(0.0) * 1000.0) as u64));
+        }
+        if let Ok(date) = chrono::DateTime::parse_from_rfc2822(value) {
+            let wait = date.timestamp_millis() - chrono::Utc::now().timestamp_millis();
+            return Some(Duration::from_millis(wait.max(0) as u64));
+        }
+    }
+
+    let openai_reset = ["x-ratelimit-reset-requests", "x-ratelimit-reset-tokens"]
+        .iter()
+        .filter_map(|name| header(name).and_then(parse_duration_str))
+        .max();
+    if openai_reset.is_some() {
+        return openai_reset;
+    }
+
+    if let Some(value) = header("x-ratelimit-reset").and_then(|v| v.parse::<i64>().ok()) {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let wait_ms = if value > 1_000_000_000_000 {
+            value - now_ms // epoch milliseconds
+        } else if value > 1_000_000_000 {
+            value * 1000 - now_ms // epoch seconds
+        } else {
+            value * 1000 // delta seconds
+        };
+        return Some(Duration::from_millis(wait_ms.max(0) as u64));
+    }
+
+    None
+}
+
+/// Extract a wait hint from an error body (Gemini puts `retryDelay: "37s"` in `error.details`)
+pub fn retry_after_from_body(body: &str) -> Option<Duration> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    json["error"]["details"]
+        .as_array()?
+        .iter()
+        .filter_map(|d| d["retryDelay"].as_str())
+        .find_map(parse_duration_str)
+}
+
+/// Parse Go-style duration strings such as "1s", "6m0s", "1h2m", "250ms", "0.5s"
+pub fn parse_duration_str(value: &str) -> Option<Duration> {
+    let mut total_ms = 0f64;
+    let mut number = String::new();
+    let mut chars = value.trim().chars().peekable();
+    let mut matched = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+
+        let n: f64 = number.parse().ok()?;
+        number.clear();
+        let factor = match c {
+            'h' => 3_600_000.0,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                1.0
+            }
+            'm' => 60_000.0,
+            's' => 1000.0,
+            _ => return None,
+        };
+        total_ms += n * factor;
+        matched = true;
+    }
+
+    if !number.is_empty() {
+        // Bare number: seconds
+        total_ms += number.parse::<f64>().ok()? * 1000.0;
+        matched = true;
+    }
+
+    matched.then(|| Duration::from_millis(total_ms as u64))
+}
+
+/// Decorator that adds the shared retry layer to any provider
+pub struct RetryingProvider {
+    inner: Arc<dyn AIProvider>,
+    policy: RetryPolicy,
+    logger: Arc<Logger>,
+}
+
+impl RetryingProvider {
+    pub fn new(inner: Arc<dyn AIProvider>, policy: RetryPolicy, logger: Arc<Logger>) -> Self {
+        Self {
+            inner,
+            policy,
+            logger,
+        }
+    }
+}
+
+#[async_trait]
+impl AIProvider for RetryingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        self.inner.provider_type()
+    }
+
+    async fn generate(
+        &self,
+        request: GenerationRequest,
+    ) -> Result<GenerationResponse, ProviderError> {
+        let label = format!("{}:{}", self.inner.name(), request.model);
+        let outcome = retry_with_backoff(&self.policy, Some(&self.logger), &label, || {
+            self.inner.generate(request.clone())
+        })
+        .await;
+
+        outcome.result.map(|mut response| {
+            response.retries += outcome.retries;
+            response
+        })
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, ProviderError> {
+        let label = format!("{}:embed", self.inner.name());
+        retry_with_backoff(&self.policy, Some(&self.logger), &label, || {
+            self.inner.embed(text)
+        })
+        .await
+        .result
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let label = format!("{}:embed", self.inner.name());
+        retry_with_backoff(&self.policy, Some(&self.logger), &label, || {
+            self.inner.embed_batch(texts)
+        })
+        .await
+        .result
+    }
+
+    fn embedding_model(&self) -> &str {
+        self.inner.embedding_model()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        self.inner.list_models().await
+    }
+
+    async fn describe_model(&self, model: &str) -> Result<ModelInfo, ProviderError> {
+        self.inner.describe_model(model).await
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth, ProviderError> {
+        self.inner.health_check().await
+    }
+
+    fn is_available(&self) -> bool {
+        self.inner.is_available()
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn max_context_length(&self) -> usize {
+        self.inner.max_context_length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            budget: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_str() {
+        assert_eq!(parse_duration_str("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_duration_str("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_duration_str("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration_str("37s"), Some(Duration::from_secs(37)));
+        assert_eq!(parse_duration_str("12"), Some(Duration::from_secs(12)));
+        assert_eq!(parse_duration_str("soon"), None);
+    }
+
+    #[test]
+    fn test_retry_after_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("7"));
+        assert_eq!(retry_after_from_headers(&headers), Some(Duration::from_secs(7)));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("1s"));
+        headers.insert("x-ratelimit-reset-tokens", HeaderValue::from_static("6m0s"));
+        assert_eq!(retry_after_from_headers(&headers), Some(Duration::from_secs(360)));
+
+        assert_eq!(retry_after_from_headers(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_retry_after_from_gemini_body() {
+        let body = r#"{"error":{"code":429,"details":[{"@type":"type.googleapis.com/google.rpc.RetryInfo","retryDelay":"37s"}]}}"#;
+        assert_eq!(retry_after_from_body(body), Some(Duration::from_secs(37)));
+        assert_eq!(retry_after_from_body("not json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let calls = AtomicU32::new(0);
+        let outcome = retry_with_backoff(&fast_policy(), None, "test", || async {
+            if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(ProviderError::RateLimitError {
+                    message: "slow down".to_string(),
+                    retry_after_ms: Some(1),
+                })
+            } else {
+                Ok("done")
+            }
+        })
+        .await;
+
+        assert_eq!(outcome.result.unwrap(), "done");
+        assert_eq!(outcome.retries, 2);
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_fails_fast() {
+        let calls = AtomicU32::new(0);
+        let outcome: RetryOutcome<()> = retry_with_backoff(&fast_policy(), None, "test", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(ProviderError::AuthenticationError("bad key".to_string()))
+        })
+        .await;
+
+        assert!(outcome.result.is_err());
+        assert_eq!(outcome.retries, 0);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_hint_longer_than_max_delay_is_honoured_within_budget() {
+        let policy = RetryPolicy {
+            budget: Some(Duration::from_secs(5)),
+            ..fast_policy()
+        };
+        let calls = AtomicU32::new(0);
+        let outcome = retry_with_backoff(&policy, None, "test", || async {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(ProviderError::RateLimitError {
+                    message: "retryDelay".to_string(),
+                    retry_after_ms: Some(30),
+                })
+            } else {
+                Ok("done")
+            }
+        })
+        .await;
+
+        assert_eq!(outcome.result.unwrap(), "done");
+        assert_eq!(outcome.retries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_budget_stops_long_waits() {
+        let policy = RetryPolicy {
+            max_delay: Duration::from_secs(60),
+            budget: Some(Duration::from_millis(50)),
+            ..fast_policy()
+        };
+        let outcome: RetryOutcome<()> = retry_with_backoff(&policy, None, "test", || async {
+            Err(ProviderError::RateLimitError {
+                message: "come back later".to_string(),
+                retry_after_ms: Some(10_000),
+            })
+        })
+        .await;
+
+        assert!(outcome.result.is_err());
+        assert_eq!(outcome.retries, 0);
+    }
+}
@@ -0,0 +1,579 @@
+use crate::providers::{
+    AIProvider, GenerationRequest, GenerationResponse, ModelInfo, ProviderError, ProviderHealth,
+    ProviderType,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::oneshot;
+
+/// Default max concurrent requests per lane when not configured
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 2;
+
+/// Scheduling class, served strictly in this order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestPriority {
+    /// Chat replies and direct questions from a human
+    Interactive,
+    /// Council sessions and deliberations
+    Council,
+    /// Topic loop and other unattended work
+    #[default]
+    Background,
+}
+
+impl RequestPriority {
+    const ALL: [RequestPriority; 3] = [Self::Interactive, Self::Council, Self::Background];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Lane key for a provider: one lane per Ollama host, one per remote API
+pub fn lane_for(provider: &str, ollama_url: &str) -> String {
+    let provider = provider.to_lowercase();
+    if provider == "ollama" {
+        format!("ollama@{}", ollama_url.trim_end_matches('/'))
+    } else {
+        provider
+    }
+}
+
+/// Queue depth and wait times for one lane (exposed via `scheduler_stats`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaneStats {
+    pub lane: String,
+    pub max_in_flight: usize,
+    pub in_flight: usize,
+    /// Waiting requests per priority class (interactive, council, background)
+    pub queued: HashMap<RequestPriority, usize>,
+    /// Requests that had to queue before running
+    pub waited: u64,
+    pub avg_wait_ms: f64,
+    pub max_wait_ms: u64,
+    pub granted: u64,
+}
+
+/// Round-robin queue across users so one busy user cannot starve the rest
+#[derive(Default)]
+struct FairQueue {
+    order: VecDeque<String>,
+    waiters: HashMap<String, VecDeque<oneshot::Sender<SchedulerPermit>>>,
+}
+
+impl FairQueue {
+    fn push(&mut self, user: &str, waiter: oneshot::Sender<SchedulerPermit>) {
+        let queue = self.waiters.entry(user.to_string()).or_default();
+        if queue.is_empty() {
+            self.order.push_back(user.to_string());
+        }
+        queue.push_back(waiter);
+    }
+
+    fn pop(&mut self) -> Option<oneshot::Sender<SchedulerPermit>> {
+        while let Some(user) = self.order.pop_front() {
+            let Some(queue) = self.waiters.get_mut(&user) else {
+                continue;
+            };
+            let waiter = queue.pop_front();
+            if queue.is_empty() {
+                self.waiters.remove(&user);
+            } else {
+                self.order.push_back(user);
+            }
+            if waiter.is_some() {
+                return waiter;
+            }
+        }
+        None
+    }
+
+    /// Live waiters (cancelled requests are skipped when popped)
+    fn len(&self) -> usize {
+        self.waiters
+            .values()
+            .flatten()
+            .filter(|waiter| !waiter.is_closed())
+            .count()
+    }
+}
+
+struct Lane {
+    max_in_flight: usize,
+    in_flight: usize,
+    queues: [FairQueue; 3],
+    waited: u64,
+    total_wait_ms: u64,
+    max_wait_ms: u64,
+    granted: u64,
+}
+
+impl Lane {
+    fn new(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight: max_in_flight.max(1),
+            in_flight: 0,
+            queues: Default::default(),
+            waited: 0,
+            total_wait_ms: 0,
+            max_wait_ms: 0,
+            granted: 0,
+        }
+    }
+
+    fn has_waiters(&self) -> bool {
+        self.queues.iter().any(|q| !q.order.is_empty())
+    }
+}
+
+/// Central scheduler in front of model servers
+///
+/// Each lane (a provider, or an Ollama host) runs at most `max_in_flight`
+/// requests. Waiting requests are served by priority class, round-robin
+/// across users within a class.
+pub struct RequestScheduler {
+    lanes: Mutex<HashMap<String, Lane>>,
+    limits: HashMap<String, usize>,
+    default_limit: usize,
+}
+
+impl RequestScheduler {
+    /// `limits` may be keyed by full lane (`ollama@http://host:11434`) or by provider (`ollama`)
+    pub fn new(limits: HashMap<String, usize>, default_limit: usize) -> Self {
+        Self {
+            lanes: Mutex::new(HashMap::new()),
+            limits,
+            default_limit: default_limit.max(1),
+        }
+    }
+
+    fn limit_for(&self, lane: &str) -> usize {
+        let provider = lane.split('@').next().unwrap_or(lane);
+        self.limits
+            .get(lane)
+            .or_else(|| self.limits.get(provider))
+            .copied()
+            .unwrap_or(self.default_limit)
+    }
+
+    /// Wait for a slot on `lane`; the slot is held until the permit is dropped
+    pub async fn acquire(
+        self: &Arc<Self>,
+        lane: &str,
+        priority: RequestPriority,
+        user: &str,
+    ) -> SchedulerPermit {
+        let enqueued = Instant::now();
+        let receiver = {
+            let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+            let state = lanes
+                .entry(lane.to_string())
+                .or_insert_with(|| Lane::new(self.limit_for(lane)));
+
+            if state.in_flight < state.max_in_flight && !state.has_waiters() {
+                state.in_flight += 1;
+                state.granted += 1;
+                return SchedulerPermit::new(self.clone(), lane);
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            state.queues[priority.index()].push(user, sender);
+            receiver
+        };
+
+        // The releasing request hands its slot over directly
+        let permit = match receiver.await {
+            Ok(permit) => permit,
+            Err(_) => SchedulerPermit::detached(self.clone(), lane),
+        };
+
+        let waited_ms = enqueued.elapsed().as_millis() as u64;
+        let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = lanes.get_mut(lane) {
+            state.waited += 1;
+            state.granted += 1;
+            state.total_wait_ms += waited_ms;
+            state.max_wait_ms = state.max_wait_ms.max(waited_ms);
+        }
+
+        permit
+    }
+
+    fn release(self: &Arc<Self>, lane: &str) {
+        loop {
+            // Pick the next waiter under the lock, but hand over outside it:
+            // a permit dropped mid-send re-enters `release`
+            let waiter = {
+                let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+                let Some(state) = lanes.get_mut(lane) else {
+                    return;
+                };
+                let next = RequestPriority::ALL
+                    .iter()
+                    .find_map(|p| state.queues[p.index()].pop());
+                match next {
+                    Some(waiter) => waiter,
+                    None => {
+                        state.in_flight = state.in_flight.saturating_sub(1);
+                        return;
+                    }
+                }
+            };
+
+            match waiter.send(SchedulerPermit::new(self.clone(), lane)) {
+                Ok(()) => return,
+                // Waiter gave up; disarm so the drop doesn't release twice
+                Err(mut permit) => permit.armed = false,
+            }
+        }
+    }
+
+    /// Snapshot of every lane seen so far
+    pub fn stats(&self) -> Vec<LaneStats> {
+        let lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats: Vec<LaneStats> = lanes
+            .iter()
+            .map(|(lane, state)| LaneStats {
+                lane: lane.clone(),
+                max_in_flight: state.max_in_flight,
+                in_flight: state.in_flight,
+                queued: RequestPriority::ALL
+                    .iter()
+                    .map(|p| (*p, state.queues[p.index()].len()))
+                    .collect(),
+                waited: state.waited,
+                avg_wait_ms: if state.waited > 0 {
+                    state.total_wait_ms as f64 / state.waited as f64
+                } else {
+                    0.0
+                },
+                max_wait_ms: state.max_wait_ms,
+                granted: state.granted,
+            })
+            .collect();
+        stats.sort_by(|a, b| a.lane.cmp(&b.lane));
+        stats
+    }
+}
+
+impl Default for RequestScheduler {
+    fn default() -> Self {
+        Self::new(HashMap::new(), DEFAULT_MAX_IN_FLIGHT)
+    }
+}
+
+/// A running slot on a lane; dropping it lets the next request in
+pub struct SchedulerPermit {
+    scheduler: Arc<RequestScheduler>,
+    lane: String,
+    armed: bool,
+}
+
+impl SchedulerPermit {
+    fn new(scheduler: Arc<RequestScheduler>, lane: &str) -> Self {
+        Self {
+            scheduler,
+            lane: lane.to_string(),
+            armed: true,
+        }
+    }
+
+    fn detached(scheduler: Arc<RequestScheduler>, lane: &str) -> Self {
+        Self {
+            scheduler,
+            lane: lane.to_string(),
+            armed: false,
+        }
+    }
+}
+
+impl Drop for SchedulerPermit {
+    fn drop(&mut self) {
+        if self.armed {
+            self.scheduler.release(&self.lane);
+        }
+    }
+}
+
+/// Where and as whom a request queues; retry loops take a permit per attempt
+///
+/// Holding a permit across a backoff sleep would keep the lane busy while
+/// nothing is running, so each attempt acquires and drops its own.
+#[derive(Clone)]
+pub struct SchedulerSlot {
+    pub scheduler: Arc<RequestScheduler>,
+    pub lane: String,
+    pub priority: RequestPriority,
+    pub user: String,
+}
+
+impl SchedulerSlot {
+    pub fn new(
+        scheduler: Arc<RequestScheduler>,
+        lane: String,
+        priority: RequestPriority,
+        user: &str,
+    ) -> Self {
+        Self {
+            scheduler,
+            lane,
+            priority,
+            user: user.to_string(),
+        }
+    }
+
+    /// Wait for a slot for one attempt
+    pub async fn acquire(&self) -> SchedulerPermit {
+        self.acquire_on(&self.lane).await
+    }
+
+    /// Wait for a slot on another lane (the host a pooled request was routed to)
+    pub async fn acquire_on(&self, lane: &str) -> SchedulerPermit {
+        self.scheduler.acquire(lane, self.priority, &self.user).await
+    }
+}
+
+/// Provider wrapper that takes a scheduler slot for every call
+pub struct ScheduledProvider {
+    inner: Arc<dyn AIProvider>,
+    scheduler: Arc<RequestScheduler>,
+    lane: String,
+    priority: RequestPriority,
+    user: String,
+}
+
+impl ScheduledProvider {
+    pub fn new(
+        inner: Arc<dyn AIProvider>,
+        scheduler: Arc<RequestScheduler>,
+        lane: String,
+        priority: RequestPriority,
+        user: String,
+    ) -> Self {
+        Self {
+            inner,
+            scheduler,
+            lane,
+            priority,
+            user,
+        }
+    }
+}
+
+#[async_trait]
+impl AIProvider for ScheduledProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        self.inner.provider_type()
+    }
+
+    async fn generate(
+        &self,
+        request: GenerationRequest,
+    ) -> Result<GenerationResponse, ProviderError> {
+        let _permit = self
+            .scheduler
+            .acquire(&self.lane, self.priority, &self.user)
+            .await;
+        self.inner.generate(request).await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, ProviderError> {
+        let _permit = self
+            .scheduler
+            .acquire(&self.lane, self.priority, &self.user)
+            .await;
+        self.inner.embed(text).await
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let _permit = self
+            .scheduler
+            .acquire(&self.lane, self.priority, &self.user)
+            .await;
+        self.inner.embed_batch(texts).await
+    }
+
+    fn embedding_model(&self) -> &str {
+        self.inner.embedding_model()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        self.inner.list_models().await
+    }
+
+    async fn describe_model(&self, model: &str) -> Result<ModelInfo, ProviderError> {
+        self.inner.describe_model(model).await
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth, ProviderError> {
+        self.inner.health_check().await
+    }
+
+    fn is_available(&self) -> bool {
+        self.inner.is_available()
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn max_context_length(&self) -> usize {
+        self.inner.max_context_length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Queue a waiter and return a handle that resolves to its tag once it runs
+    fn spawn_waiter(
+        scheduler: &Arc<RequestScheduler>,
+        priority: RequestPriority,
+        user: &'static str,
+        order: Arc<Mutex<Vec<&'static str>>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let scheduler = scheduler.clone();
+        tokio::spawn(async move {
+            let _permit = scheduler.acquire("ollama@test", priority, user).await;
+            order.lock().unwrap().push(user);
+        })
+    }
+
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    #[tokio::test]
+    async fn test_limit_is_enforced_per_lane() {
+        let scheduler = Arc::new(RequestScheduler::new(
+            HashMap::from([("ollama".to_string(), 1)]),
+            4,
+        ));
+
+        let first = scheduler.acquire("ollama@test", RequestPriority::Background, "a").await;
+        let _other_lane = scheduler.acquire("openai", RequestPriority::Background, "a").await;
+
+        let waiter = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                scheduler.acquire("ollama@test", RequestPriority::Background, "b").await;
+            })
+        };
+        settle().await;
+        assert!(!waiter.is_finished());
+
+        let lane = scheduler.stats().into_iter().find(|s| s.lane == "ollama@test").unwrap();
+        assert_eq!(lane.in_flight, 1);
+        assert_eq!(lane.queued[&RequestPriority::Background], 1);
+
+        drop(first);
+        waiter.await.unwrap();
+        let lane = scheduler.stats().into_iter().find(|s| s.lane == "ollama@test").unwrap();
+        assert_eq!(lane.in_flight, 0);
+        assert_eq!(lane.waited, 1);
+    }
+
+    #[tokio::test]
+    async fn test_priority_then_fair_across_users() {
+        let scheduler = Arc::new(RequestScheduler::new(HashMap::new(), 1));
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let running = scheduler.acquire("ollama@test", RequestPriority::Background, "topic").await;
+
+        let mut handles = vec![spawn_waiter(&scheduler, RequestPriority::Background, "topic", order.clone())];
+        settle().await;
+        handles.push(spawn_waiter(&scheduler, RequestPriority::Interactive, "alice", order.clone()));
+        settle().await;
+        handles.push(spawn_waiter(&scheduler, RequestPriority::Interactive, "alice", order.clone()));
+        settle().await;
+        handles.push(spawn_waiter(&scheduler, RequestPriority::Interactive, "bob", order.clone()));
+        settle().await;
+        handles.push(spawn_waiter(&scheduler, RequestPriority::Council, "council", order.clone()));
+        settle().await;
+
+        drop(running);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["alice", "bob", "alice", "council", "topic"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_leak_slot() {
+        let scheduler = Arc::new(RequestScheduler::new(HashMap::new(), 1));
+        let running = scheduler.acquire("lane", RequestPriority::Council, "a").await;
+
+        let abandoned = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                scheduler.acquire("lane", RequestPriority::Council, "b").await;
+            })
+        };
+        settle().await;
+        abandoned.abort();
+        let _ = abandoned.await;
+
+        drop(running);
+        let again = tokio::time::timeout(
+            Duration::from_millis(200),
+            scheduler.acquire("lane", RequestPriority::Council, "c"),
+        )
+        .await;
+        assert!(again.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_slot_is_released_between_retries() {
+        let scheduler = Arc::new(RequestScheduler::new(HashMap::new(), 1));
+        let slot = SchedulerSlot::new(scheduler.clone(), "lane".to_string(), RequestPriority::Council, "a");
+        let policy = crate::providers::RetryPolicy {
+            max_retries: 1,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_millis(200),
+            budget: None,
+        };
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+
+        let retrying = crate::providers::retry::retry_with_backoff(&policy, None, "test", || async {
+            let _permit = slot.acquire().await;
+            if attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                Err(ProviderError::NetworkError("connection reset".to_string()))
+            } else {
+                Ok(())
+            }
+        });
+        // Another request gets the lane while the first one backs off
+        let other = async {
+            settle().await;
+            tokio::time::timeout(
+                Duration::from_millis(50),
+                scheduler.acquire("lane", RequestPriority::Council, "b"),
+            )
+            .await
+            .is_ok()
+        };
+
+        let (outcome, other_ran) = tokio::join!(retrying, other);
+        assert!(outcome.result.is_ok());
+        assert_eq!(outcome.retries, 1);
+        assert!(other_ran);
+    }
+}
@@ -0,0 +1,281 @@
+// Re-embedding - Background migration of stored vectors to a new embedding model
+//
+// When the configured embedding model changes, every chunk, chat message and
+// document chunk embedded with another model is re-embedded into
+// `staged_embeddings` (migration 0003). The live tables keep their old vectors
+// until the job has covered everything, then one transaction swaps them in.
+// A job interrupted by a restart resumes where it stopped.
+
+use crate::knowledge::{Embedding, KnowledgeBank};
+use crate::vector_index::VectorSource;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+
+/// Items embedded per batch (progress is recorded after each one)
+pub const BATCH_SIZE: usize = 32;
+
+/// Sources re-embedded by a job
+pub const SOURCES: [VectorSource; 3] = [VectorSource::Chunks, VectorSource::Chat, VectorSource::Documents];
+
+/// A re-embedding job as stored in `reembed_jobs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReembedJob {
+    pub id: String,
+    pub target_model: String,
+    /// running | completed | cancelled
+    pub status: String,
+    pub total: i64,
+    pub done: i64,
+    /// Last embedding failure; the job retries with backoff
+    pub last_error: Option<String>,
+    pub started_at: i64,
+    pub updated_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+/// Something that still needs a vector from the target model
+#[derive(Debug, Clone)]
+pub struct PendingItem {
+    pub source: VectorSource,
+    pub id: String,
+    pub text: String,
+}
+
+/// (live embedding table, its id column, SQL selecting `id` and `text` of the content rows)
+fn source_tables(source: VectorSource) -> (&'static str, &'static str, &'static str) {
+    match source {
+        VectorSource::Chunks => ("embeddings", "chunk_id", "SELECT id, text FROM text_chunks"),
+        VectorSource::Chat => (
+            "chat_embeddings",
+            "message_id",
+            "SELECT id, content AS text FROM chat_logs WHERE context_cleared = 0",
+        ),
+        VectorSource::Documents => ("document_embeddings", "chunk_id", "SELECT id, text FROM document_chunks"),
+    }
+}
+
+/// Items of `source` whose live vector is from another model and not yet staged by `job_id`
+fn pending_sql(source: VectorSource, select: &str) -> String {
+    let (table, id_column, content) = source_tables(source);
+    format!(
+        "SELECT {select} FROM ({content}) c JOIN {table} e ON e.{id_column} = c.id \
+         WHERE COALESCE(e.model, '') != ? \
+         AND NOT EXISTS (SELECT 1 FROM staged_embeddings s \
+             WHERE s.job_id = ? AND s.source = '{key}' AND s.item_id = c.id)",
+        key = source.key()
+    )
+}
+
+fn job_from_row(row: &sqlx::sqlite::SqliteRow) -> ReembedJob {
+    ReembedJob {
+        id: row.get("id"),
+        target_model: row.get("target_model"),
+        status: row.get("status"),
+        total: row.get("total"),
+        done: row.get("done"),
+        last_error: row.get("last_error"),
+        started_at: row.get("started_at"),
+        updated_at: row.get("updated_at"),
+        finished_at: row.get("finished_at"),
+    }
+}
+
+/// The job currently running, if any
+pub async fn running_job(pool: &SqlitePool) -> Result<Option<ReembedJob>, String> {
+    let row = sqlx::query("SELECT * FROM reembed_jobs WHERE status = 'running' ORDER BY started_at DESC LIMIT 1")
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load re-embedding job: {}", e))?;
+    Ok(row.as_ref().map(job_from_row))
+}
+
+/// The most recently started job, whatever its status
+pub async fn latest_job(pool: &SqlitePool) -> Result<Option<ReembedJob>, String> {
+    let row = sqlx::query("SELECT * FROM reembed_jobs ORDER BY started_at DESC, rowid DESC LIMIT 1")
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load re-embedding job: {}", e))?;
+    Ok(row.as_ref().map(job_from_row))
+}
+
+/// Record a new running job towards `target_model`
+pub async fn start_job(pool: &SqlitePool, target_model: &str, total: i64) -> Result<ReembedJob, String> {
+    let now = chrono::Utc::now().timestamp();
+    let job = ReembedJob {
+        id: uuid::Uuid::new_v4().to_string(),
+        target_model: target_model.to_string(),
+        status: "running".to_string(),
+        total,
+        done: 0,
+        last_error: None,
+        started_at: now,
+        updated_at: now,
+        finished_at: None,
+    };
+
+    sqlx::query(
+        "INSERT INTO reembed_jobs (id, target_model, status, total, done, started_at, updated_at) \
+         VALUES (?, ?, ?, ?, 0, ?, ?)",
+    )
+    .bind(&job.id)
+    .bind(&job.target_model)
+    .bind(&job.status)
+    .bind(total)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to start re-embedding job: {}", e))?;
+    Ok(job)
+}
+
+/// Abandon a job (e.g. the model changed again) and drop what it staged
+pub async fn cancel_job(pool: &SqlitePool, job_id: &str) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+    sqlx::query("DELETE FROM staged_embeddings WHERE job_id = ?")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to drop staged embeddings: {}", e))?;
+    let now = chrono::Utc::now().timestamp();
+    sqlx::query("UPDATE reembed_jobs SET status = 'cancelled', updated_at = ?, finished_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(now)
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to cancel re-embedding job: {}", e))?;
+    tx.commit().await.map_err(|e| format!("Failed to commit: {}", e))
+}
+
+/// Number of items still to embed for `target_model` (all of them when `job_id` is None)
+pub async fn pending_count(pool: &SqlitePool, job_id: Option<&str>, target_model: &str) -> Result<i64, String> {
+    let mut total = 0;
+    for source in SOURCES {
+        let row = sqlx::query(&pending_sql(source, "COUNT(*) AS n"))
+            .bind(target_model)
+            .bind(job_id.unwrap_or(""))
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Failed to count pending {} embeddings: {}", source.key(), e))?;
+        total += row.get::<i64, _>("n");
+    }
+    Ok(total)
+}
+
+/// Up to `limit` items still to embed for the job
+pub async fn pending_items(pool: &SqlitePool, job: &ReembedJob, limit: usize) -> Result<Vec<PendingItem>, String> {
+    let mut items = Vec::new();
+    for source in SOURCES {
+        if items.len() >= limit {
+            break;
+        }
+        let sql = format!("{} ORDER BY c.id LIMIT ?", pending_sql(source, "c.id AS id, c.text AS text"));
+        let rows = sqlx::query(&sql)
+            .bind(&job.target_model)
+            .bind(&job.id)
+            .bind((limit - items.len()) as i64)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to load pending {} embeddings: {}", source.key(), e))?;
+        items.extend(rows.iter().map(|r| PendingItem {
+            source,
+            id: r.get("id"),
+            text: r.get("text"),
+        }));
+    }
+    Ok(items)
+}
+
+/// Keep a new vector aside until the switch-over
+pub async fn stage(pool: &SqlitePool, job_id: &str, item: &PendingItem, embedding: &Embedding) -> Result<(), String> {
+    sqlx::query(
+        "INSERT OR REPLACE INTO staged_embeddings (job_id, source, item_id, embedding, dimension, model) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(job_id)
+    .bind(item.source.key())
+    .bind(&item.id)
+    .bind(KnowledgeBank::serialize_embedding(&embedding.vector))
+    .bind(embedding.dimension as i64)
+    .bind(&embedding.model)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to stage embedding for {}: {}", item.id, e))?;
+    Ok(())
+}
+
+/// Update done/total from the staged rows; returns the refreshed job
+pub async fn record_progress(pool: &SqlitePool, job: &ReembedJob, error: Option<&str>) -> Result<ReembedJob, String> {
+    let done: i64 = sqlx::query("SELECT COUNT(*) AS n FROM staged_embeddings WHERE job_id = ?")
+        .bind(&job.id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to count staged embeddings: {}", e))?
+        .get("n");
+    // Items added or removed meanwhile move the goalposts
+    let total = done + pending_count(pool, Some(&job.id), &job.target_model).await?;
+    let now = chrono::Utc::now().timestamp();
+
+    sqlx::query("UPDATE reembed_jobs SET done = ?, total = ?, last_error = ?, updated_at = ? WHERE id = ?")
+        .bind(done)
+        .bind(total)
+        .bind(error)
+        .bind(now)
+        .bind(&job.id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record re-embedding progress: {}", e))?;
+
+    Ok(ReembedJob {
+        done,
+        total,
+        last_error: error.map(str::to_string),
+        updated_at: now,
+        ..job.clone()
+    })
+}
+
+/// Swap staged vectors into the live tables and complete the job, atomically;
+/// returns the number of vectors replaced
+///
+/// Items deleted (or chat cleared) since they were staged are skipped.
+pub async fn switch_over(pool: &SqlitePool, job: &ReembedJob) -> Result<usize, String> {
+    let mut tx = pool.begin().await.map_err(|e| format!("Failed to begin transaction: {}", e))?;
+    let mut replaced = 0;
+    for source in SOURCES {
+        let (table, id_column, _) = source_tables(source);
+        let result = sqlx::query(&format!(
+            "INSERT OR REPLACE INTO {table} ({id_column}, embedding, dimension, model) \
+             SELECT s.item_id, s.embedding, s.dimension, s.model FROM staged_embeddings s \
+             WHERE s.job_id = ? AND s.source = ? \
+             AND s.item_id IN (SELECT {id_column} FROM {table} WHERE COALESCE(model, '') != s.model)"
+        ))
+        .bind(&job.id)
+        .bind(source.key())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to switch {} to new embeddings: {}", table, e))?;
+        replaced += result.rows_affected() as usize;
+    }
+
+    sqlx::query("DELETE FROM staged_embeddings WHERE job_id = ?")
+        .bind(&job.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to drop staged embeddings: {}", e))?;
+    let now = chrono::Utc::now().timestamp();
+    sqlx::query(
+        "UPDATE reembed_jobs SET status = 'completed', done = total, last_error = NULL, updated_at = ?, finished_at = ? \
+         WHERE id = ?",
+    )
+    .bind(now)
+    .bind(now)
+    .bind(&job.id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to complete re-embedding job: {}", e))?;
+
+    tx.commit().await.map_err(|e| format!("Failed to commit: {}", e))?;
+    Ok(replaced)
+}
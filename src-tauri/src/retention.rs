@@ -0,0 +1,360 @@
+// Retention - Per-channel pruning, archival and scheduled context resets
+//
+// Policies come from `channel_retention` in `app_config.json`, keyed by channel
+// name. Messages past a policy's age or count limit are optionally written to
+// a gzipped NDJSON archive, then deleted along with their embeddings. Channels
+// with a reset interval have their RAG context cleared on that schedule (the
+// "fresh start" from SAFETY_AND_MEMORY.md). Every prune and reset is recorded
+// in `retention_events` (migration 0008).
+
+use crate::chat::{ChannelType, Message};
+use crate::config::AppConfig;
+use crate::logger::Logger;
+use crate::namespaces::NamespaceRegistry;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Archive directory when `retention_archive_dir` is unset
+pub const DEFAULT_ARCHIVE_DIR: &str = "./data/archive";
+
+/// Shortest pause between retention passes
+const MIN_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionKind {
+    /// Messages deleted for exceeding a limit
+    Prune,
+    /// Channel context cleared (messages kept, embeddings dropped)
+    Reset,
+}
+
+impl RetentionKind {
+    pub fn key(self) -> &'static str {
+        match self {
+            RetentionKind::Prune => "prune",
+            RetentionKind::Reset => "reset",
+        }
+    }
+
+    fn from_key(key: &str) -> Self {
+        match key {
+            "prune" => RetentionKind::Prune,
+            _ => RetentionKind::Reset,
+        }
+    }
+}
+
+/// What started a prune or reset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Initiator {
+    /// The background retention loop
+    Schedule,
+    /// A user command
+    Manual,
+}
+
+impl Initiator {
+    pub fn key(self) -> &'static str {
+        match self {
+            Initiator::Schedule => "schedule",
+            Initiator::Manual => "manual",
+        }
+    }
+
+    fn from_key(key: &str) -> Self {
+        match key {
+            "schedule" => Initiator::Schedule,
+            _ => Initiator::Manual,
+        }
+    }
+}
+
+/// A logged prune or reset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionEvent {
+    pub id: i64,
+    pub channel: String,
+    pub kind: RetentionKind,
+    pub initiated_by: Initiator,
+    /// Messages deleted (prune) or taken out of the RAG context (reset)
+    pub messages: i64,
+    /// Archive written before a prune
+    pub archive_path: Option<String>,
+    pub created_at: i64,
+}
+
+/// (timestamp cutoff, newest messages to keep) for `policy` at `now`; -1 keeps every message
+pub fn limits(policy: &crate::config::RetentionPolicy, now: i64) -> (i64, i64) {
+    let cutoff = policy
+        .max_age_days
+        .map(|days| now.saturating_sub((days as i64).saturating_mul(86_400)))
+        .unwrap_or(i64::MIN);
+    let keep = policy.max_messages.map(|n| n as i64).unwrap_or(-1);
+    (cutoff, keep)
+}
+
+/// A reset is due once `interval_secs` have passed since the last one (or, before the first,
+/// since the oldest message still in context). Channels with nothing in context are skipped.
+pub fn reset_due(interval_secs: i64, last_reset: Option<i64>, oldest_in_context: Option<i64>, now: i64) -> bool {
+    let Some(oldest) = oldest_in_context else {
+        return false;
+    };
+    now - last_reset.unwrap_or(oldest) >= interval_secs
+}
+
+/// Write `messages` as gzipped NDJSON to `<dir>/<channel>-<time>.jsonl.gz`
+pub fn write_archive(dir: &Path, channel: ChannelType, messages: &[Message], now: i64) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create archive dir: {}", e))?;
+    let stamp = chrono::DateTime::<chrono::Utc>::from_timestamp(now, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ");
+    let mut path = dir.join(format!("{}-{}.jsonl.gz", channel.as_str(), stamp));
+    // Two passes within a second must not overwrite each other
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("{}-{}-{}.jsonl.gz", channel.as_str(), stamp, n));
+        n += 1;
+    }
+
+    let file = std::fs::File::create(&path).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    for message in messages {
+        let line = serde_json::to_string(message).map_err(|e| format!("Failed to encode message: {}", e))?;
+        writeln!(encoder, "{}", line).map_err(|e| format!("Failed to write archive: {}", e))?;
+    }
+    encoder
+        .finish()
+        .and_then(|file| file.sync_all())
+        .map_err(|e| format!("Failed to finish archive: {}", e))?;
+    Ok(path)
+}
+
+/// Log a prune or reset
+pub async fn record(
+    pool: &SqlitePool,
+    channel: &str,
+    kind: RetentionKind,
+    initiated_by: Initiator,
+    messages: i64,
+    archive_path: Option<String>,
+) -> Result<RetentionEvent, String> {
+    let now = chrono::Utc::now().timestamp();
+    let id = sqlx::query(
+        "INSERT INTO retention_events (channel, kind, initiated_by, messages, archive_path, created_at) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(channel)
+    .bind(kind.key())
+    .bind(initiated_by.key())
+    .bind(messages)
+    .bind(&archive_path)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to log retention event: {}", e))?
+    .last_insert_rowid();
+
+    Ok(RetentionEvent {
+        id,
+        channel: channel.to_string(),
+        kind,
+        initiated_by,
+        messages,
+        archive_path,
+        created_at: now,
+    })
+}
+
+/// Time of the last reset of `channel`, scheduled or manual
+pub async fn last_reset(pool: &SqlitePool, channel: &str) -> Result<Option<i64>, String> {
+    let row = sqlx::query("SELECT MAX(created_at) AS at FROM retention_events WHERE channel = ? AND kind = 'reset'")
+        .bind(channel)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to read last reset: {}", e))?;
+    Ok(row.get("at"))
+}
+
+/// Logged events, newest first, optionally for one channel
+pub async fn history(pool: &SqlitePool, channel: Option<&str>, limit: usize) -> Result<Vec<RetentionEvent>, String> {
+    let rows = sqlx::query(
+        "SELECT id, channel, kind, initiated_by, messages, archive_path, created_at FROM retention_events \
+         WHERE (? IS NULL OR channel = ?) ORDER BY id DESC LIMIT ?",
+    )
+    .bind(channel)
+    .bind(channel)
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read retention events: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| RetentionEvent {
+            id: row.get("id"),
+            channel: row.get("channel"),
+            kind: RetentionKind::from_key(&row.get::<String, _>("kind")),
+            initiated_by: Initiator::from_key(&row.get::<String, _>("initiated_by")),
+            messages: row.get("messages"),
+            archive_path: row.get("archive_path"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/// Apply every configured policy once: prune over-limit messages, then reset channels that are due.
+/// A failing channel is logged and does not stop the others.
+pub async fn run(
+    namespaces: &NamespaceRegistry,
+    config: &AppConfig,
+    initiated_by: Initiator,
+    logger: &Logger,
+) -> Vec<RetentionEvent> {
+    let archive_dir = PathBuf::from(config.retention_archive_dir.as_deref().unwrap_or(DEFAULT_ARCHIVE_DIR));
+    let mut names: Vec<&String> = config.channel_retention.keys().collect();
+    names.sort();
+
+    let mut events = Vec::new();
+    for name in names {
+        let policy = &config.channel_retention[name];
+        let Some(channel) = ChannelType::from_str(name) else {
+            logger.warn("retention", &format!("⚠️ Unknown channel '{}' in channel_retention", name));
+            continue;
+        };
+        // Each channel's history lives in the bank of the namespace that owns it
+        let Some(kb) = namespaces.for_channel(channel) else {
+            continue;
+        };
+
+        match kb.prune_channel(channel, policy, &archive_dir, initiated_by).await {
+            Ok(Some(event)) => events.push(event),
+            Ok(None) => {}
+            Err(e) => logger.error("retention", &format!("❌ Pruning #{} failed: {}", name, e)),
+        }
+
+        let Some(hours) = policy.reset_interval_hours.filter(|h| *h > 0) else {
+            continue;
+        };
+        let reset = match kb.channel_reset_due(channel, hours as i64 * 3600).await {
+            Ok(true) => kb.clear_channel_context(channel, initiated_by).await.map(Some),
+            Ok(false) => Ok(None),
+            Err(e) => Err(e),
+        };
+        match reset {
+            Ok(Some(event)) => events.push(event),
+            Ok(None) => {}
+            Err(e) => logger.error("retention", &format!("❌ Resetting #{} failed: {}", name, e)),
+        }
+    }
+    events
+}
+
+/// Run the configured policies every `retention_interval_secs`
+pub fn start_retention_loop(app_state: Arc<AppState>) {
+    tokio::spawn(async move {
+        loop {
+            let config = app_state.get_config();
+            tokio::time::sleep(Duration::from_secs(config.retention_interval_secs.max(MIN_INTERVAL_SECS))).await;
+            // Re-read: policies may have changed while sleeping
+            let config = app_state.get_config();
+            if config.channel_retention.is_empty() {
+                continue;
+            }
+            run(&app_state.namespaces, &config, Initiator::Schedule, &app_state.logger).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::AuthorType;
+    use crate::config::RetentionPolicy;
+    use crate::knowledge::KnowledgeBank;
+    use crate::providers::LocalEmbeddingProvider;
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    fn message(id: &str, channel: ChannelType, age_days: i64) -> Message {
+        let mut message = Message::new(channel, "Skeptic".to_string(), AuthorType::AI, format!("point {}", id));
+        message.id = id.to_string();
+        message.timestamp -= chrono::Duration::days(age_days);
+        message
+    }
+
+    #[test]
+    fn test_reset_due() {
+        let hour = 3600;
+        assert!(!reset_due(hour, None, None, 10 * hour));
+        assert!(reset_due(hour, None, Some(0), hour));
+        assert!(!reset_due(hour, Some(9 * hour + 1), Some(0), 10 * hour));
+        assert!(reset_due(hour, Some(9 * hour), Some(0), 10 * hour));
+    }
+
+    #[tokio::test]
+    async fn test_prune_archives_then_deletes_and_resets_are_logged() {
+        let logger = Arc::new(Logger::new(false));
+        let embedder = Arc::new(LocalEmbeddingProvider::new(logger.clone()));
+        let kb = Arc::new(KnowledgeBank::new("sqlite::memory:", logger.clone(), embedder).await.unwrap());
+        let namespaces = NamespaceRegistry::new(Some(kb.clone()), logger.clone());
+
+        for (id, age) in [("old", 40), ("m1", 3), ("m2", 2), ("m3", 1)] {
+            kb.save_chat_message(&message(id, ChannelType::Topic, age)).await.unwrap();
+        }
+        kb.save_chat_message(&message("g1", ChannelType::General, 90)).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("retention-{}", uuid::Uuid::new_v4()));
+        let config = AppConfig {
+            channel_retention: HashMap::from([(
+                "topic".to_string(),
+                RetentionPolicy {
+                    max_age_days: Some(30),
+                    max_messages: Some(2),
+                    archive: true,
+                    reset_interval_hours: Some(24),
+                },
+            )]),
+            retention_archive_dir: Some(dir.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+
+        let events = run(&namespaces, &config, Initiator::Schedule, &logger).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].kind, events[0].messages), (RetentionKind::Prune, 2));
+        assert_eq!((events[1].kind, events[1].messages), (RetentionKind::Reset, 2));
+
+        let remaining: Vec<String> = kb
+            .get_chat_history(ChannelType::Topic, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(remaining, vec!["m2".to_string(), "m3".to_string()]);
+        assert_eq!(kb.get_chat_history(ChannelType::General, 10).await.unwrap().len(), 1);
+
+        let mut archived = String::new();
+        let file = std::fs::File::open(events[0].archive_path.as_ref().unwrap()).unwrap();
+        flate2::read::GzDecoder::new(file).read_to_string(&mut archived).unwrap();
+        let ids: Vec<String> = archived
+            .lines()
+            .map(|line| serde_json::from_str::<Message>(line).unwrap().id)
+            .collect();
+        assert_eq!(ids, vec!["old".to_string(), "m1".to_string()]);
+
+        // Just reset and nothing new in context: the next pass does nothing
+        assert!(run(&namespaces, &config, Initiator::Schedule, &logger).await.is_empty());
+        let history = kb.retention_history(Some("topic"), 10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].initiated_by, Initiator::Schedule);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
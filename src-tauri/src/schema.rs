@@ -0,0 +1,228 @@
+// Schema migrations - Versioned Knowledge Bank schema via sqlx migrate
+//
+// Migrations live in `src-tauri/migrations` and are embedded at compile time.
+// Applied versions are recorded in `_sqlx_migrations`. Never edit a migration
+// that has shipped; add a new numbered file instead.
+
+use crate::logger::{LogLevel, Logger};
+use sqlx::migrate::Migrator;
+use sqlx::{sqlite::SqlitePool, Row};
+use std::sync::Arc;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Newest schema version this build knows about
+pub fn latest_version() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+/// Highest applied schema version (0 for a fresh or pre-migration database)
+pub async fn current_version(pool: &SqlitePool) -> Result<i64, String> {
+    if !table_exists(pool, "_sqlx_migrations").await? {
+        return Ok(0);
+    }
+    let row = sqlx::query("SELECT MAX(version) AS version FROM _sqlx_migrations WHERE success = 1")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to read schema version: {}", e))?;
+    Ok(row.get::<Option<i64>, _>("version").unwrap_or(0))
+}
+
+/// Bring the database up to `latest_version()`; returns the resulting version
+///
+/// Refuses databases written by a newer build. File databases with data are
+/// copied to `<file>.v<version>-<timestamp>.bak` before anything changes.
+pub async fn migrate(pool: &SqlitePool, logger: &Arc<Logger>) -> Result<i64, String> {
+    let current = current_version(pool).await?;
+    let latest = latest_version();
+
+    if current > latest {
+        return Err(format!(
+            "Database schema version {} is newer than this build supports ({}); refusing to start. \
+             Upgrade the app or restore a backup.",
+            current, latest
+        ));
+    }
+    if current == latest {
+        return Ok(current);
+    }
+
+    let legacy = current == 0 && table_exists(pool, "deliberations").await?;
+    if current > 0 || legacy {
+        backup(pool, current, logger).await?;
+    }
+    if legacy {
+        adopt_legacy(pool, logger).await?;
+    }
+
+    MIGRATOR
+        .run(pool)
+        .await
+        .map_err(|e| format!("Schema migration failed: {}", e))?;
+
+    logger.log(
+        LogLevel::Info,
+        "knowledge",
+        &format!("📊 Schema migrated from v{} to v{}", current, latest),
+    );
+    Ok(latest)
+}
+
+/// Bring a database created by `CREATE TABLE IF NOT EXISTS` builds up to the
+/// baseline layout of migration 0001 (columns those builds added on the fly)
+async fn adopt_legacy(pool: &SqlitePool, logger: &Arc<Logger>) -> Result<(), String> {
+    logger.log(
+        LogLevel::Info,
+        "knowledge",
+        "📊 Adopting pre-migration database into versioned schema",
+    );
+
+    for table in ["embeddings", "chat_embeddings"] {
+        // Older builds were hard-wired to Ollama's nomic-embed-text
+        if ensure_column(pool, table, "model", "TEXT").await? {
+            sqlx::query(&format!(
+                "UPDATE {} SET model = 'nomic-embed-text' WHERE model IS NULL",
+                table
+            ))
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to backfill {} models: {}", table, e))?;
+        }
+    }
+    for table in ["responses", "chat_logs"] {
+        ensure_column(pool, table, "reasoning", "TEXT").await?;
+    }
+    ensure_column(pool, "chat_logs", "context_cleared", "INTEGER NOT NULL DEFAULT 0").await?;
+    Ok(())
+}
+
+/// Add a column if the table exists without it; returns whether it was added
+async fn ensure_column(pool: &SqlitePool, table: &str, column: &str, definition: &str) -> Result<bool, String> {
+    let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to inspect {}: {}", table, e))?;
+
+    // Missing tables are created by the migrations
+    if columns.is_empty() || columns.iter().any(|c| c.get::<String, _>("name") == column) {
+        return Ok(false);
+    }
+
+    sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to add {} column to {}: {}", column, table, e))?;
+    Ok(true)
+}
+
+async fn table_exists(pool: &SqlitePool, name: &str) -> Result<bool, String> {
+    Ok(sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to inspect schema: {}", e))?
+        .is_some())
+}
+
+/// Copy a file database aside before migrating it (no-op in memory)
+async fn backup(pool: &SqlitePool, version: i64, logger: &Arc<Logger>) -> Result<(), String> {
+    let file: String = sqlx::query("SELECT file FROM pragma_database_list WHERE name = 'main'")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to locate database file: {}", e))?
+        .get("file");
+    if file.is_empty() {
+        return Ok(());
+    }
+
+    let target = format!("{}.v{}-{}.bak", file, version, chrono::Utc::now().format("%Y%m%d%H%M%S"));
+    sqlx::query("VACUUM INTO ?")
+        .bind(&target)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to back up database before migrating: {}", e))?;
+
+    logger.log(
+        LogLevel::Info,
+        "knowledge",
+        &format!("💾 Backed up schema v{} database to {}", version, target),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_legacy_database_is_adopted_and_backed_up() {
+        let logger = Arc::new(Logger::new(false));
+        let db = std::env::temp_dir().join(format!("kb-legacy-{}.sqlite", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}?mode=rwc", db.to_string_lossy());
+        let pool = SqlitePool::connect(&url).await.unwrap();
+
+        // Layout of the first releases: no model / reasoning / context_cleared columns
+        for sql in [
+            "CREATE TABLE deliberations (id TEXT PRIMARY KEY, question TEXT NOT NULL, consensus TEXT, created_at INTEGER NOT NULL, completed BOOLEAN NOT NULL)",
+            "CREATE TABLE text_chunks (id TEXT PRIMARY KEY, deliberation_id TEXT NOT NULL, text TEXT NOT NULL, chunk_type TEXT NOT NULL, chunk_metadata TEXT)",
+            "CREATE TABLE embeddings (chunk_id TEXT PRIMARY KEY, embedding BLOB NOT NULL, dimension INTEGER NOT NULL)",
+            "CREATE TABLE chat_logs (id TEXT PRIMARY KEY, channel TEXT NOT NULL, author TEXT NOT NULL, author_type TEXT NOT NULL, content TEXT NOT NULL, timestamp INTEGER NOT NULL, signature TEXT, reply_to TEXT)",
+            "INSERT INTO deliberations VALUES ('d1', 'Adopt policy ZX-81?', NULL, 0, 1)",
+            "INSERT INTO text_chunks VALUES ('c1', 'd1', 'Adopt policy ZX-81?', 'text', NULL)",
+            "INSERT INTO embeddings VALUES ('c1', x'0000803f', 1)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        assert_eq!(migrate(&pool, &logger).await.unwrap(), latest_version());
+        assert_eq!(current_version(&pool).await.unwrap(), latest_version());
+        // Second start is a no-op
+        assert_eq!(migrate(&pool, &logger).await.unwrap(), latest_version());
+
+        let model: String = sqlx::query("SELECT model FROM embeddings").fetch_one(&pool).await.unwrap().get("model");
+        assert_eq!(model, "nomic-embed-text");
+        let cleared: i64 = sqlx::query("SELECT COUNT(*) AS n FROM chat_logs WHERE context_cleared = 0")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .get("n");
+        assert_eq!(cleared, 0);
+        // Rows from before the full-text migration are searchable
+        let hits = crate::lexical_index::search(&pool, crate::lexical_index::LexicalSource::Chunks, "ZX-81", 5, None)
+            .await
+            .unwrap();
+        assert_eq!(hits, vec!["c1"]);
+
+        pool.close().await;
+        let dir = db.parent().unwrap();
+        let name = db.file_name().unwrap().to_string_lossy().into_owned();
+        let backups: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with(&format!("{}.v0-", name)))
+            .collect();
+        assert_eq!(backups.len(), 1);
+
+        let _ = std::fs::remove_file(backups[0].path());
+        let _ = std::fs::remove_file(db);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_newer_database() {
+        let logger = Arc::new(Logger::new(false));
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate(&pool, &logger).await.unwrap();
+
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+             VALUES (?, 'from the future', 1, x'00', 0)",
+        )
+        .bind(latest_version() + 1)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let err = migrate(&pool, &logger).await.unwrap_err();
+        assert!(err.contains("newer than this build"));
+    }
+}
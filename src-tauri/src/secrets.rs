@@ -0,0 +1,530 @@
+// Secret store - Encrypted-at-rest provider API keys
+// ChaCha20-Poly1305 per secret, key derived with Argon2id from a passphrase or key file
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Config values starting with this prefix name an entry in the secret store
+pub const SECRET_REF_PREFIX: &str = "secret:";
+
+/// Passphrase for headless runs (web server, CI)
+pub const PASSPHRASE_ENV: &str = "COUNCIL_SECRETS_PASSPHRASE";
+
+const FILE_VERSION: u32 = 1;
+const VERIFIER_PLAINTEXT: &[u8] = b"council-of-dicks-secrets";
+
+/// Argon2id cost parameters (stored with the file so they can be raised later)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KdfParams {
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl KdfParams {
+    fn generate(m_cost: u32, t_cost: u32, p_cost: u32) -> Self {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            salt: general_purpose::STANDARD.encode(salt),
+            m_cost,
+            t_cost,
+            p_cost,
+        }
+    }
+
+    fn derive_key(&self, material: &[u8]) -> Result<[u8; 32], String> {
+        let salt = general_purpose::STANDARD
+            .decode(&self.salt)
+            .map_err(|e| format!("Corrupt secret store salt: {}", e))?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|e| format!("Invalid KDF parameters: {}", e))?;
+
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(material, &salt, &mut key)
+            .map_err(|e| format!("Key derivation failed: {}", e))?;
+        Ok(key)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Sealed {
+    fn seal(key: &[u8; 32], plaintext: &[u8]) -> Result<Self, String> {
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key))
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| "Encryption failed".to_string())?;
+        Ok(Self {
+            nonce: general_purpose::STANDARD.encode(nonce),
+            ciphertext: general_purpose::STANDARD.encode(ciphertext),
+        })
+    }
+
+    fn open(&self, key: &[u8; 32]) -> Result<Vec<u8>, String> {
+        let nonce = general_purpose::STANDARD
+            .decode(&self.nonce)
+            .map_err(|e| format!("Corrupt nonce: {}", e))?;
+        let ciphertext = general_purpose::STANDARD
+            .decode(&self.ciphertext)
+            .map_err(|e| format!("Corrupt ciphertext: {}", e))?;
+        if nonce.len() != 12 {
+            return Err("Corrupt nonce length".to_string());
+        }
+        ChaCha20Poly1305::new(Key::from_slice(key))
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| "Decryption failed (wrong passphrase or tampered file)".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SecretEntry {
+    #[serde(flatten)]
+    sealed: Sealed,
+    /// Redacted form shown in config/API responses
+    preview: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SecretFile {
+    version: u32,
+    kdf: KdfParams,
+    /// Known plaintext, used to reject a wrong passphrase on unlock
+    verifier: Sealed,
+    #[serde(default)]
+    secrets: BTreeMap<String, SecretEntry>,
+}
+
+/// Secret metadata (never the value)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub name: String,
+    pub reference: String,
+    pub preview: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretStoreStatus {
+    pub path: String,
+    pub exists: bool,
+    pub unlocked: bool,
+    pub secret_count: usize,
+}
+
+struct Unlocked {
+    key: [u8; 32],
+    file: SecretFile,
+}
+
+/// Encrypted key/value store in `config/secrets.enc.json`
+///
+/// Names and redacted previews are readable while locked; values need the
+/// passphrase (or key file) the store was created with.
+pub struct SecretStore {
+    path: PathBuf,
+    /// Argon2id (memory KiB, iterations, lanes) for newly created stores
+    kdf_costs: (u32, u32, u32),
+    unlocked: Mutex<Option<Unlocked>>,
+}
+
+/// `secret:<name>` reference for config files
+pub fn secret_ref(name: &str) -> String {
+    format!("{}{}", SECRET_REF_PREFIX, name)
+}
+
+pub fn is_secret_ref(value: &str) -> bool {
+    value.starts_with(SECRET_REF_PREFIX)
+}
+
+/// Redacted preview of a secret value ("sk-p…3xYz"); references pass through unchanged
+pub fn redact(value: &str) -> String {
+    if is_secret_ref(value) {
+        return value.to_string();
+    }
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 12 {
+        return "••••".to_string();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+/// Process-wide store at the default path
+pub fn global() -> &'static SecretStore {
+    static STORE: OnceLock<SecretStore> = OnceLock::new();
+    STORE.get_or_init(|| SecretStore::open(SecretStore::default_path()))
+}
+
+/// Write a new random key file (base64, 32 bytes) usable on any OS
+pub fn generate_key_file(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!("Key file already exists: {}", path.display()));
+    }
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, general_purpose::STANDARD.encode(key)).map_err(|e| e.to_string())?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+    }
+    Ok(())
+}
+
+impl SecretStore {
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let defaults = Params::default();
+        Self {
+            path: path.into(),
+            kdf_costs: (defaults.m_cost(), defaults.t_cost(), defaults.p_cost()),
+            unlocked: Mutex::new(None),
+        }
+    }
+
+    /// Argon2id costs used when the store file is first created
+    #[allow(dead_code)]
+    pub fn with_kdf_costs(mut self, m_cost: u32, t_cost: u32, p_cost: u32) -> Self {
+        self.kdf_costs = (m_cost, t_cost, p_cost);
+        self
+    }
+
+    /// `secrets.enc.json` next to `app_config.json`
+    pub fn default_path() -> PathBuf {
+        crate::config::AppConfig::get_config_path().with_file_name("secrets.enc.json")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.unlocked.lock().unwrap().is_some()
+    }
+
+    pub fn status(&self) -> SecretStoreStatus {
+        SecretStoreStatus {
+            path: self.path.to_string_lossy().to_string(),
+            exists: self.path.exists(),
+            unlocked: self.is_unlocked(),
+            secret_count: self.list().map(|s| s.len()).unwrap_or(0),
+        }
+    }
+
+    /// Unlock with a passphrase; creates the store if it doesn't exist yet
+    pub fn unlock_with_passphrase(&self, passphrase: &str) -> Result<(), String> {
+        if passphrase.is_empty() {
+            return Err("Passphrase cannot be empty".to_string());
+        }
+        self.unlock(passphrase.as_bytes())
+    }
+
+    /// Unlock with the contents of a key file (see `generate_key_file`)
+    pub fn unlock_with_key_file(&self, path: &Path) -> Result<(), String> {
+        let material = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read key file {}: {}", path.display(), e))?;
+        let material = material.trim();
+        if material.is_empty() {
+            return Err(format!("Key file {} is empty", path.display()));
+        }
+        self.unlock(material.as_bytes())
+    }
+
+    fn unlock(&self, material: &[u8]) -> Result<(), String> {
+        let unlocked = if self.path.exists() {
+            let file = self.read_file()?;
+            let key = file.kdf.derive_key(material)?;
+            file.verifier.open(&key)?;
+            Unlocked { key, file }
+        } else {
+            let (m, t, p) = self.kdf_costs;
+            let kdf = KdfParams::generate(m, t, p);
+            let key = kdf.derive_key(material)?;
+            let file = SecretFile {
+                version: FILE_VERSION,
+                kdf,
+                verifier: Sealed::seal(&key, VERIFIER_PLAINTEXT)?,
+                secrets: BTreeMap::new(),
+            };
+            self.write_file(&file)?;
+            Unlocked { key, file }
+        };
+
+        *self.unlocked.lock().unwrap() = Some(unlocked);
+        Ok(())
+    }
+
+    /// Unlock from `COUNCIL_SECRETS_PASSPHRASE` or the configured key file; Ok(false) if neither is set
+    pub fn unlock_at_startup(&self, key_file: Option<&str>) -> Result<bool, String> {
+        if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+            self.unlock_with_passphrase(&passphrase)?;
+            return Ok(true);
+        }
+        match key_file {
+            Some(path) => self.unlock_with_key_file(Path::new(path)).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Forget the derived key
+    pub fn lock(&self) {
+        if let Some(mut unlocked) = self.unlocked.lock().unwrap().take() {
+            unlocked.key.fill(0);
+        }
+    }
+
+    /// Store or rotate a secret; returns the `secret:<name>` reference
+    pub fn set(&self, name: &str, value: &str) -> Result<String, String> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(format!("Invalid secret name: '{}'", name));
+        }
+        if value.is_empty() {
+            return Err("Secret value cannot be empty".to_string());
+        }
+
+        let mut guard = self.unlocked.lock().unwrap();
+        let unlocked = guard.as_mut().ok_or_else(locked_error)?;
+
+        let mut file = unlocked.file.clone();
+        file.secrets.insert(
+            name.to_string(),
+            SecretEntry {
+                sealed: Sealed::seal(&unlocked.key, value.as_bytes())?,
+                preview: redact(value),
+                updated_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        self.write_file(&file)?;
+        unlocked.file = file;
+        Ok(secret_ref(name))
+    }
+
+    pub fn get(&self, name: &str) -> Result<String, String> {
+        let guard = self.unlocked.lock().unwrap();
+        let unlocked = guard.as_ref().ok_or_else(locked_error)?;
+        let entry = unlocked
+            .file
+            .secrets
+            .get(name)
+            .ok_or_else(|| format!("Secret not found: {}", name))?;
+        let bytes = entry.sealed.open(&unlocked.key)?;
+        String::from_utf8(bytes).map_err(|e| format!("Secret {} is not UTF-8: {}", name, e))
+    }
+
+    pub fn remove(&self, name: &str) -> Result<bool, String> {
+        let mut guard = self.unlocked.lock().unwrap();
+        let unlocked = guard.as_mut().ok_or_else(locked_error)?;
+
+        let mut file = unlocked.file.clone();
+        if file.secrets.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.write_file(&file)?;
+        unlocked.file = file;
+        Ok(true)
+    }
+
+    /// Names and previews (available while locked)
+    pub fn list(&self) -> Result<Vec<SecretInfo>, String> {
+        let file = match self.unlocked.lock().unwrap().as_ref() {
+            Some(unlocked) => unlocked.file.clone(),
+            None if self.path.exists() => self.read_file()?,
+            None => return Ok(Vec::new()),
+        };
+        Ok(file
+            .secrets
+            .into_iter()
+            .map(|(name, entry)| SecretInfo {
+                reference: secret_ref(&name),
+                name,
+                preview: entry.preview,
+                updated_at: entry.updated_at,
+            })
+            .collect())
+    }
+
+    /// Value for a config field: `secret:<name>` is looked up, anything else is returned as-is
+    pub fn resolve(&self, value: &str) -> Result<String, String> {
+        match value.strip_prefix(SECRET_REF_PREFIX) {
+            Some(name) => self.get(name),
+            None => Ok(value.to_string()),
+        }
+    }
+
+    /// Re-encrypt every secret under a new passphrase / key file
+    pub fn rekey(&self, material: &[u8]) -> Result<(), String> {
+        let mut guard = self.unlocked.lock().unwrap();
+        let unlocked = guard.as_mut().ok_or_else(locked_error)?;
+
+        let (m, t, p) = self.kdf_costs;
+        let kdf = KdfParams::generate(m, t, p);
+        let key = kdf.derive_key(material)?;
+
+        let mut secrets = BTreeMap::new();
+        for (name, entry) in &unlocked.file.secrets {
+            let value = entry.sealed.open(&unlocked.key)?;
+            secrets.insert(
+                name.clone(),
+                SecretEntry {
+                    sealed: Sealed::seal(&key, &value)?,
+                    preview: entry.preview.clone(),
+                    updated_at: entry.updated_at.clone(),
+                },
+            );
+        }
+
+        let file = SecretFile {
+            version: FILE_VERSION,
+            kdf,
+            verifier: Sealed::seal(&key, VERIFIER_PLAINTEXT)?,
+            secrets,
+        };
+        self.write_file(&file)?;
+        unlocked.key.fill(0);
+        *unlocked = Unlocked { key, file };
+        Ok(())
+    }
+
+    fn read_file(&self) -> Result<SecretFile, String> {
+        let content = fs::read_to_string(&self.path)
+            .map_err(|e| format!("Cannot read {}: {}", self.path.display(), e))?;
+        let file: SecretFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Corrupt secret store {}: {}", self.path.display(), e))?;
+        if file.version != FILE_VERSION {
+            return Err(format!("Unsupported secret store version {}", file.version));
+        }
+        Ok(file)
+    }
+
+    /// Write via a temp file so a crash never leaves a half-written store
+    fn write_file(&self, file: &SecretFile) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, content).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &self.path).map_err(|e| e.to_string())
+    }
+}
+
+fn locked_error() -> String {
+    "Secret store is locked (unlock with a passphrase or key file)".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> SecretStore {
+        let path = std::env::temp_dir().join(format!(
+            "cod_secrets_{}_{}.json",
+            name,
+            uuid::Uuid::new_v4()
+        ));
+        // Cheap KDF so tests stay fast
+        SecretStore::open(path).with_kdf_costs(64, 1, 1)
+    }
+
+    #[test]
+    fn test_round_trip_and_wrong_passphrase() {
+        let store = temp_store("roundtrip");
+        store.unlock_with_passphrase("correct horse").unwrap();
+        let reference = store.set("openai_api_key", "sk-test-1234567890abcdef").unwrap();
+        assert_eq!(reference, "secret:openai_api_key");
+
+        // Plaintext never hits the disk
+        let on_disk = fs::read_to_string(store.path()).unwrap();
+        assert!(!on_disk.contains("sk-test-1234567890abcdef"));
+
+        store.lock();
+        assert!(store.get("openai_api_key").is_err());
+        assert_eq!(store.list().unwrap()[0].preview, "sk-t…cdef");
+
+        let reopened = SecretStore::open(store.path());
+        assert!(reopened.unlock_with_passphrase("wrong").is_err());
+        reopened.unlock_with_passphrase("correct horse").unwrap();
+        assert_eq!(
+            reopened.resolve("secret:openai_api_key").unwrap(),
+            "sk-test-1234567890abcdef"
+        );
+        assert_eq!(reopened.resolve("plain-value").unwrap(), "plain-value");
+
+        let _ = fs::remove_file(store.path());
+    }
+
+    #[test]
+    fn test_rotate_rekey_and_key_file() {
+        let store = temp_store("rotate");
+        let key_file = store.path().with_extension("key");
+        generate_key_file(&key_file).unwrap();
+        store.unlock_with_key_file(&key_file).unwrap();
+
+        store.set("google_api_key", "AIza-old-value-0000").unwrap();
+        store.set("google_api_key", "AIza-new-value-1111").unwrap();
+        assert_eq!(store.get("google_api_key").unwrap(), "AIza-new-value-1111");
+
+        store.rekey(b"new passphrase").unwrap();
+        let reopened = SecretStore::open(store.path());
+        assert!(reopened.unlock_with_key_file(&key_file).is_err());
+        reopened.unlock_with_passphrase("new passphrase").unwrap();
+        assert_eq!(reopened.get("google_api_key").unwrap(), "AIza-new-value-1111");
+        assert!(reopened.remove("google_api_key").unwrap());
+
+        let _ = fs::remove_file(store.path());
+        let _ = fs::remove_file(key_file);
+    }
+
+    #[test]
+    fn test_config_keys_sealed_and_redacted() {
+        let store = temp_store("config");
+        store.unlock_with_passphrase("pass").unwrap();
+
+        let mut config = crate::config::AppConfig {
+            openai_api_key: Some("sk-proj-abcdefghijklmnop".to_string()),
+            ollama_password: Some("guardian-password-123".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.seal_api_keys(&store).unwrap(), 1);
+        assert_eq!(config.openai_api_key.as_deref(), Some("secret:openai_api_key"));
+        assert_eq!(store.get("openai_api_key").unwrap(), "sk-proj-abcdefghijklmnop");
+
+        // The UI gets previews and posts them back unchanged
+        let mut posted = config.redacted();
+        assert_eq!(posted.ollama_password.as_deref(), Some("guar…-123"));
+        posted.restore_redacted(&config);
+        assert_eq!(posted.ollama_password, config.ollama_password);
+        assert_eq!(posted.openai_api_key, config.openai_api_key);
+
+        let _ = fs::remove_file(store.path());
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(redact("short"), "••••");
+        assert_eq!(redact("sk-or-v1-abcdefghijkl"), "sk-o…ijkl");
+        assert_eq!(redact("secret:openai_api_key"), "secret:openai_api_key");
+    }
+}
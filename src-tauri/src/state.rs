@@ -1,20 +1,30 @@
 use crate::agents::{Agent, AgentPool};
 use crate::chat::{
-    ChannelManager, DuplicateFilter, Message as ChatMessage, RateLimiter, SpamDetector,
+    ChannelManager, Message as ChatMessage, RateLimiter, SpamDetector,
 };
 use crate::chat_bot::{ChatBot, ChatBotStatus};
-use crate::config::AppConfig;
+use crate::config::{AppConfig, LocalEmbeddingsMode};
 use crate::council::CouncilSessionManager;
 use crate::crypto::SigningIdentity;
 use crate::knowledge::KnowledgeBank;
 use crate::logger::Logger;
 use crate::mcp::McpServer;
+use crate::namespaces::{BankOpener, NamespaceRegistry};
 use crate::metrics::MetricsCollector;
 use crate::p2p_manager::P2PManager;
+use crate::providers::config::ProvidersConfig;
+use crate::ollama_models::ModelWarmer;
+use crate::providers::discovery::ModelCatalog;
+use crate::providers::scheduler::lane_for;
+use crate::providers::{
+    AIProvider, LocalEmbeddingProvider, MockProvider, OllamaPool, OllamaProvider, RequestPriority, RequestScheduler,
+    ResponseCache, ScheduledProvider,
+};
 use crate::pohv::PoHVSystem;
 use crate::reputation::ReputationManager;
 use crate::topic_manager::TopicManager;
 use crate::constitution::ConstitutionManager;
+use crate::contradiction::ContradictionDetector;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -30,8 +40,16 @@ pub struct AppState {
     pub mcp_server: Arc<McpServer>,
     pub signing_identity: Arc<SigningIdentity>,
     pub knowledge_bank: Option<Arc<KnowledgeBank>>,
+    /// Knowledge banks by namespace ("default" is `knowledge_bank`)
+    pub namespaces: Arc<NamespaceRegistry>,
+    pub response_cache: Option<Arc<ResponseCache>>,
+    pub scheduler: Arc<RequestScheduler>,
+    pub model_catalog: Arc<ModelCatalog>,
+    pub ollama_pool: Arc<OllamaPool>,
+    pub model_warmer: Arc<ModelWarmer>,
     pub channel_manager: Arc<ChannelManager>,
-    pub duplicate_filter: Option<Arc<DuplicateFilter>>,
+    /// Compares new verdicts with similar past ones (needs the knowledge bank)
+    pub contradiction_detector: Option<Arc<ContradictionDetector>>,
     pub rate_limiter: Arc<RateLimiter>,
     pub spam_detector: Arc<SpamDetector>,
     pub websocket_broadcast: Arc<broadcast::Sender<ChatMessage>>,
@@ -65,6 +83,23 @@ impl AppState {
         let logger = Arc::new(Logger::new(false));
         logger.set_debug_enabled(base_config.debug_enabled);
         logger.info("config", &format!("Loaded configuration (handle: {})", base_config.user_handle));
+
+        // Secret store: unlock if a passphrase/key file is available, then move plaintext keys into it
+        let secrets = crate::secrets::global();
+        match secrets.unlock_at_startup(base_config.secrets_key_file.as_deref()) {
+            Ok(true) => match base_config.seal_api_keys(secrets) {
+                Ok(0) => logger.info("secrets", "🔐 Secret store unlocked"),
+                Ok(moved) => {
+                    logger.success("secrets", &format!("🔐 Moved {} plaintext API key(s) into {}", moved, secrets.path().display()));
+                    if let Err(e) = base_config.save() {
+                        logger.warn("secrets", &format!("⚠️ Could not rewrite config with key references: {}", e));
+                    }
+                }
+                Err(e) => logger.warn("secrets", &format!("⚠️ Could not seal API keys: {}", e)),
+            },
+            Ok(false) => logger.debug("secrets", "Secret store locked (no passphrase or key file configured)"),
+            Err(e) => logger.warn("secrets", &format!("⚠️ Secret store not unlocked: {}", e)),
+        }
         
         // Log available providers
         let providers = base_config.available_providers();
@@ -76,42 +111,6 @@ impl AppState {
             logger.warn("storage", &format!("⚠️ Could not prepare data dir: {}", e));
         }
 
-        // Initialize knowledge bank
-        let kb_path = data_dir.join("knowledge_bank.sqlite");
-        let kb_url = format!("sqlite://{}", kb_path.to_string_lossy());
-        
-        // Build auth tuple - Ollama Guardian uses username-only (app name), password optional
-        let ollama_auth = base_config.ollama_username.as_ref().map(|u| {
-            (u.clone(), base_config.ollama_password.clone().unwrap_or_default())
-        });
-        
-        let knowledge_bank =
-            match KnowledgeBank::new(&kb_url, logger.clone(), base_config.ollama_url.clone(), ollama_auth).await
-            {
-                Ok(bank) => Some(Arc::new(bank)),
-                Err(e) => {
-                    logger.warn("knowledge", &format!("⚠️ Knowledge bank disabled: {}", e));
-                    None
-                }
-            };
-
-        let council_manager = Arc::new(CouncilSessionManager::new(knowledge_bank.clone()));
-        
-        // Load sessions from DB
-        council_manager.load_from_db().await;
-
-        let channel_manager = Arc::new(ChannelManager::new(knowledge_bank.clone()));
-        // Load chat history
-        channel_manager.load_history().await;
-
-        let mcp_server = Arc::new(McpServer::new(
-            9001,
-            council_manager.clone(),
-            logger.clone(),
-        ));
-
-        let chat_bot_status = Arc::new(Mutex::new(ChatBotStatus::default()));
-
         // Load or generate signing identity (stored in data/ directory)
         let keypair_path = data_dir.join("council_identity.key");
         let signing_identity = if keypair_path.exists() {
@@ -145,18 +144,175 @@ impl AppState {
             Arc::new(identity)
         };
 
+        // One scheduler in front of every model server (per-lane limits, priorities)
+        let scheduler = Arc::new(RequestScheduler::new(
+            base_config.provider_max_in_flight.clone(),
+            base_config.default_max_in_flight,
+        ));
+
+        // Initialize knowledge bank
+        let kb_path = data_dir.join("knowledge_bank.sqlite");
+        let kb_url = format!("sqlite://{}", kb_path.to_string_lossy());
+        
+        // Embedding provider: providers.json default, else the configured Ollama host
+        let registry = ProvidersConfig::load("providers.json")
+            .unwrap_or_default()
+            .build_registry(logger.clone());
+        let remote_embedder: Arc<dyn AIProvider> = match registry.get_embedding_provider() {
+            Ok(provider) => provider,
+            Err(_) => Arc::new(
+                // Ollama Guardian uses username-only auth (app name), password optional
+                OllamaProvider::new(
+                    base_config.ollama_url.clone(),
+                    base_config.ollama_model.clone(),
+                    logger.clone(),
+                )
+                .with_auth(base_config.ollama_username.clone(), base_config.ollama_password.clone()),
+            ),
+        };
+
+        // Queue and re-embedding work yields to chat; search queries do not
+        let embedding_lane = lane_for(remote_embedder.name(), &base_config.ollama_url);
+        let scheduled_embedder = |priority| -> Arc<dyn AIProvider> {
+            Arc::new(ScheduledProvider::new(
+                remote_embedder.clone(),
+                scheduler.clone(),
+                embedding_lane.clone(),
+                priority,
+                "knowledge".to_string(),
+            ))
+        };
+
+        let local_embedder = || -> Arc<dyn AIProvider> {
+            Arc::new(LocalEmbeddingProvider::from_optional_path(
+                base_config.local_embeddings_model_path.as_deref(),
+                logger.clone(),
+            ))
+        };
+        let (embedder, query_embedder, fallback_embedder) = match base_config.local_embeddings_mode {
+            LocalEmbeddingsMode::Off => (
+                scheduled_embedder(RequestPriority::Background),
+                Some(scheduled_embedder(RequestPriority::Interactive)),
+                None,
+            ),
+            LocalEmbeddingsMode::Fallback => (
+                scheduled_embedder(RequestPriority::Background),
+                Some(scheduled_embedder(RequestPriority::Interactive)),
+                Some(local_embedder()),
+            ),
+            LocalEmbeddingsMode::Primary => (local_embedder(), None, None),
+        };
+
+        // Namespaces open their banks with the same embedders and signing key
+        let opener = BankOpener {
+            data_dir: data_dir.clone(),
+            embedder: embedder.clone(),
+            query_embedder: query_embedder.clone(),
+            fallback_embedder: fallback_embedder.clone(),
+            signing_identity: signing_identity.clone(),
+        };
+
+        let knowledge_bank = match KnowledgeBank::new(&kb_url, logger.clone(), embedder).await {
+            Ok(bank) => {
+                let mut bank = bank.with_signing_identity(signing_identity.clone());
+                if let Some(query_embedder) = query_embedder {
+                    bank = bank.with_query_embedder(query_embedder);
+                }
+                if let Some(fallback) = fallback_embedder {
+                    bank = bank.with_fallback_embedder(fallback);
+                }
+                Some(Arc::new(bank))
+            }
+            Err(e) => {
+                logger.warn("knowledge", &format!("⚠️ Knowledge bank disabled: {}", e));
+                None
+            }
+        };
+
+        // Further namespaces only open when the default bank did
+        let mut namespaces = NamespaceRegistry::new(knowledge_bank.clone(), logger.clone());
+        if knowledge_bank.is_some() {
+            namespaces = namespaces.with_opener(opener);
+        }
+        let namespaces = Arc::new(namespaces);
+        namespaces.configure(&base_config.namespaces);
+        namespaces.open_configured().await;
+
+        let metrics = Arc::new(Mutex::new(MetricsCollector::new()));
+
+        // Optional generation cache for cheap re-asks and deterministic replays
+        let response_cache = if base_config.response_cache_enabled {
+            let cache_path = data_dir.join("response_cache.sqlite");
+            let cache_url = format!("sqlite://{}", cache_path.to_string_lossy());
+            match ResponseCache::new(&cache_url, logger.clone()).await {
+                Ok(cache) => Some(Arc::new(
+                    cache
+                        .with_default_ttl(base_config.response_cache_ttl_secs.map(std::time::Duration::from_secs))
+                        .with_metrics(metrics.clone()),
+                )),
+                Err(e) => {
+                    logger.warn("response_cache", &format!("⚠️ Response cache disabled: {}", e));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let model_catalog = Arc::new(ModelCatalog::default());
+
+        // ollama_url plus any extra ollama_hosts, polled for health and served models
+        let ollama_pool = Arc::new(OllamaPool::from_config(&base_config, logger.clone()));
+        if ollama_pool.len() > 1 {
+            logger.info(
+                "state",
+                &format!("🖧 Ollama pool: {} hosts", ollama_pool.len()),
+            );
+        }
+
+        // Pre-loads agent models ahead of topic rounds
+        let model_warmer = Arc::new(ModelWarmer::new(
+            base_config.ollama_keep_alive.clone(),
+            std::time::Duration::from_secs(base_config.model_warmup_lead_secs),
+        ));
+
+        let council_manager = Arc::new(
+            CouncilSessionManager::new(knowledge_bank.clone())
+                    .with_response_cache(response_cache.clone())
+                    .with_mock_provider(Arc::new(MockProvider::from_optional_path(
+                        base_config.mock_script_path.as_deref(),
+                        logger.clone(),
+                    )))
+                    .with_scheduler(scheduler.clone())
+                    .with_model_catalog(model_catalog.clone())
+                    .with_ollama_pool(ollama_pool.clone())
+                    .with_namespaces(namespaces.clone())
+                    .with_fixture_recording(base_config.record_fixtures_path.clone()),
+        );
+        
+        // Load sessions from DB
+        council_manager.load_from_db().await;
+
+        let channel_manager =
+            Arc::new(ChannelManager::new(knowledge_bank.clone()).with_namespaces(namespaces.clone()));
+        // Load chat history
+        channel_manager.load_history().await;
+
+        let mcp_server = Arc::new(
+            McpServer::new(9001, council_manager.clone(), logger.clone())
+                .with_namespaces(namespaces.clone()),
+        );
+
+        let chat_bot_status = Arc::new(Mutex::new(ChatBotStatus::default()));
+
         // Initialize channel manager
-        let channel_manager = Arc::new(ChannelManager::new(knowledge_bank.clone()));
+        let channel_manager =
+            Arc::new(ChannelManager::new(knowledge_bank.clone()).with_namespaces(namespaces.clone()));
         let _ = channel_manager.send_system_message(
             crate::chat::ChannelType::General,
             "🤖 Welcome to Council Of Dicks! Type /help for commands.".to_string(),
         );
 
-        // Initialize duplicate filter if KB is available
-        let duplicate_filter = knowledge_bank
-            .as_ref()
-            .map(|kb| Arc::new(DuplicateFilter::new(Arc::clone(kb))));
-
         let rate_limiter = Arc::new(RateLimiter::new());
         let spam_detector = Arc::new(SpamDetector::new());
         let (ws_tx, _ws_rx) = broadcast::channel::<ChatMessage>(100);
@@ -187,6 +343,9 @@ impl AppState {
                     model: String,
                     system_prompt: String,
                     timeout_secs: Option<u64>,
+                    temperature: Option<f32>,
+                    #[serde(default, flatten)]
+                    generation: crate::providers::GenerationParams,
                     metadata: Option<std::collections::HashMap<String, String>>,
                 }
 
@@ -206,6 +365,10 @@ impl AppState {
                             if let Some(timeout) = config.timeout_secs {
                                 agent.timeout_secs = Some(timeout);
                             }
+                            if let Some(temperature) = config.temperature {
+                                agent.temperature = temperature;
+                            }
+                            agent.generation = config.generation;
                             if let Some(metadata) = config.metadata {
                                 agent.metadata = metadata;
                             }
@@ -227,6 +390,16 @@ impl AppState {
         } else {
             logger.warn("agent", "config/agents.json not found (checked ./config/agents.json and ../config/agents.json). No agents loaded.");
         }
+        // Post-consensus check of new verdicts against similar past ones
+        let contradiction_detector = knowledge_bank.as_ref().map(|kb| {
+            Arc::new(
+                ContradictionDetector::new(kb.clone(), agent_pool.clone(), channel_manager.clone(), logger.clone())
+                    .with_scheduler(scheduler.clone())
+                    .with_ollama_pool(ollama_pool.clone())
+                    .with_namespaces(namespaces.clone()),
+            )
+        });
+
         let pohv_system = Arc::new(PoHVSystem::new());
         let topic_manager = Arc::new(TopicManager::new());
         let reputation_manager = Arc::new(ReputationManager::new(knowledge_bank.clone()));
@@ -241,19 +414,26 @@ impl AppState {
         }
 
         let p2p_port = base_config.p2p_port;
+        let health_interval = base_config.ollama_health_interval_secs.max(1);
         let bootstrap_peers = base_config.bootstrap_peers.clone();
 
         let state = Self {
             config: Arc::new(Mutex::new(base_config)),
             logger: logger.clone(),
-            metrics: Arc::new(Mutex::new(MetricsCollector::new())),
+            metrics,
             p2p_manager: Arc::new(P2PManager::new(p2p_port, bootstrap_peers)),
             council_manager,
             mcp_server,
             signing_identity,
             knowledge_bank,
+            namespaces,
+            response_cache,
+            scheduler,
+            model_catalog,
+            ollama_pool: ollama_pool.clone(),
+            model_warmer,
             channel_manager,
-            duplicate_filter,
+            contradiction_detector,
             rate_limiter,
             spam_detector,
             websocket_broadcast: Arc::new(ws_tx),
@@ -267,6 +447,16 @@ impl AppState {
         
         // Start background tasks
         crate::topic_manager::start_topic_loop(Arc::new(state.clone()));
+        ollama_pool.start_health_checks(std::time::Duration::from_secs(health_interval));
+        crate::ollama_models::start_model_warmer(Arc::new(state.clone()));
+        crate::retention::start_retention_loop(Arc::new(state.clone()));
+
+        // Drain the embedding queue, and re-embed stored vectors if the embedding
+        // model changed (resumes an interrupted job)
+        if let Some(kb) = &state.knowledge_bank {
+            kb.start_embedding_worker();
+            kb.spawn_reembedding();
+        }
 
         // Start ChatBot monitoring
         let chat_bot_state = Arc::new(state.clone());
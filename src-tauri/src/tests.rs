@@ -57,12 +57,8 @@ mod tests {
 
         // 1. Initialize in-memory DB
         let logger = Arc::new(Logger::new(true));
-        let kb = KnowledgeBank::new(
-            "sqlite::memory:",
-            logger,
-            "http://localhost:11434".to_string(),
-            None, // No auth for tests
-        )
+        let embedder = Arc::new(crate::providers::LocalEmbeddingProvider::new(logger.clone()));
+        let kb = KnowledgeBank::new("sqlite::memory:", logger, embedder)
         .await
         .expect("Failed to create KnowledgeBank");
 
@@ -76,6 +72,8 @@ mod tests {
             consensus: Some("42".to_string()),
             status: SessionStatus::ConsensusReached,
             created_at: 1234567890,
+            attachments: vec![],
+            namespace: None,
         };
 
         kb.save_session(&session).await.expect("Failed to save session");
@@ -1,3 +1,4 @@
+use crate::knowledge::SearchMode;
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
@@ -182,6 +183,9 @@ impl TopicManager {
 
         if needs_refill {
             let agents = app_state.agent_pool.list_active_agents().await;
+            // Only the council of the namespace that owns #topic takes part
+            let namespace = app_state.namespaces.namespace_of_channel(crate::chat::ChannelType::Topic);
+            let agents = app_state.namespaces.members(Some(namespace.as_str()), agents);
             let mut state = self.state.lock().unwrap();
             for agent in agents {
                 state.queue.push_back(agent.id);
@@ -226,16 +230,18 @@ impl TopicManager {
                 }
 
                 // 2. Get Knowledge Bank context if available
-                if let Some(kb) = &app_state.knowledge_bank {
-                    if let Ok(rag) = kb.build_rag_context(&topic, 3).await {
+                let mut sources = Vec::new();
+                if let Some(kb) = app_state.namespaces.for_channel(crate::chat::ChannelType::Topic) {
+                    if let Ok(rag) = kb.build_rag_context(&topic, 3, SearchMode::Hybrid).await {
                         if !rag.relevant_decisions.is_empty() {
                             context_str.push_str(&format!("\n\nRELEVANT PAST DECISIONS:\n{}", rag.context_text));
+                            sources = rag.sources;
                         }
                     }
                 }
 
                 let prompt = format!(
-                    "TOPIC DISCUSSION\n\nTopic: {}\n{}\n\nPlease provide your perspective on this topic. Keep it concise and insightful. Start your response with your opinion. If relevant, reference the past decisions provided. Respond to previous points if applicable.",
+                    "TOPIC DISCUSSION\n\nTopic: {}\n{}\n\nPlease provide your perspective on this topic. Keep it concise and insightful. Start your response with your opinion. If relevant, reference the past decisions provided by their IDs. Respond to previous points if applicable.",
                     topic, context_str
                 );
 
@@ -243,30 +249,80 @@ impl TopicManager {
                 // Use topic-specific system prompt WITHOUT TCOD framing
                 let system_prompt = crate::prompt::compose_topic_system_prompt(&agent.system_prompt);
                 
-                let provider = crate::providers::ollama::OllamaProvider::new(
-                    config.ollama_url.clone(),
-                    agent.model.clone(),
+                // Topic replies are background work: they yield to chat and council
+                let slot = crate::providers::SchedulerSlot::new(
+                    app_state.scheduler.clone(),
+                    crate::providers::scheduler::lane_for(&agent.provider, &config.ollama_url),
+                    crate::providers::RequestPriority::Background,
+                    "topic",
+                );
+                let pooled = agent.provider.eq_ignore_ascii_case("ollama");
+                let built = if pooled {
+                    // Least-loaded healthy host that serves the model, queued on that host's lane
+                    Ok(Arc::new(
+                        crate::providers::OllamaPoolProvider::new(
+                            app_state.ollama_pool.clone(),
+                            agent.model.clone(),
+                            app_state.logger.clone(),
+                        )
+                        .with_slot(slot.clone()),
+                    ) as Arc<dyn crate::providers::AIProvider>)
+                } else {
+                    crate::provider_dispatch::build_provider(
+                        &agent.provider,
+                        &agent.model,
+                        &config,
+                        app_state.logger.clone(),
+                    )
+                };
+                let base = match built {
+                    Ok(provider) => provider,
+                    Err(e) => {
+                        app_state.logger.error("topic_manager", &format!("Agent {} has no usable provider: {}", agent.name, e));
+                        return;
+                    }
+                };
+
+                // Retries are bounded by the agent's timeout, if it has one
+                let policy = agent
+                    .timeout_secs
+                    .map(crate::providers::RetryPolicy::with_budget_secs)
+                    .unwrap_or_default();
+                let scheduled: Arc<dyn crate::providers::AIProvider> = if pooled {
+                    base
+                } else {
+                    Arc::new(crate::providers::ScheduledProvider::new(
+                        base,
+                        slot.scheduler,
+                        slot.lane,
+                        slot.priority,
+                        slot.user,
+                    ))
+                };
+                let retrying: Arc<dyn crate::providers::AIProvider> = Arc::new(crate::providers::RetryingProvider::new(
+                    scheduled,
+                    policy,
                     app_state.logger.clone(),
-                ).with_auth(config.ollama_username.clone(), config.ollama_password.clone());
-                
-                use crate::providers::AIProvider;
-                // We need to use the AIProvider trait method `generate` or similar, but `ask` was my guess.
-                // Let's check `AIProvider` trait definition.
-                // Actually, let's just use `generate` which is likely the method name.
-                // Wait, I don't have the trait definition handy, but `ask` failed.
-                // Let's look at `src/providers/mod.rs` or similar if I could, but I'll guess `generate` based on `GenerationRequest`.
-                
-                let request = crate::providers::GenerationRequest {
-                    model: agent.model.clone(),
-                    prompt: prompt.clone(),
-                    system_prompt: Some(system_prompt),
-                    temperature: agent.temperature,
-                    max_tokens: None,
-                    stream: false,
+                ));
+                let provider = match &app_state.response_cache {
+                    Some(cache) => Arc::new(crate::providers::CachingProvider::new(retrying, cache.clone())),
+                    None => retrying,
                 };
 
+                let request = agent.generation_request(prompt.clone(), Some(system_prompt));
+
                 match provider.generate(request).await {
                     Ok(response) => {
+                        app_state.agent_pool.record_retries(&agent.id, response.retries).await;
+
+                        let cited = crate::citations::check(&response.text, &sources);
+                        if !cited.unresolved.is_empty() {
+                            app_state.logger.warn(
+                                "topic_manager",
+                                &format!("Agent {} cited unknown sources: {}", agent.name, cited.unresolved.join(", ")),
+                            );
+                        }
+
                         // Post to chat
                         let message_content = format!("#topic {}\n\n{}", topic, response.text);
                         
@@ -275,7 +331,9 @@ impl TopicManager {
                             agent.name.clone(),
                             crate::chat::AuthorType::AI,
                             message_content
-                        );
+                        )
+                        .with_reasoning(response.reasoning)
+                        .with_citations(cited.resolved);
 
                         let _ = app_state.channel_manager.send_message(message);
                     },
@@ -307,7 +365,7 @@ pub async fn topic_set(topic: String, interval: Option<u64>, state: tauri::State
     state.topic_manager.set_topic(topic.clone(), Some(interval_val))?;
     
     // Save to Knowledge Bank if available
-    if let Some(kb) = &state.knowledge_bank {
+    if let Some(kb) = state.namespaces.for_channel(crate::chat::ChannelType::Topic) {
         let peer_id = state.p2p_manager.status().await.peer_id.unwrap_or_else(|| "local".to_string());
         if let Err(e) = kb.add_topic(&topic, Some(&peer_id)).await {
             state.logger.warn("topic_manager", &format!("Failed to save topic to history: {}", e));
@@ -344,7 +402,7 @@ pub fn topic_get_status(state: tauri::State<AppState>) -> TopicStatus {
 
 #[tauri::command]
 pub async fn topic_history(limit: Option<i64>, state: tauri::State<'_, AppState>) -> Result<Vec<(String, i64)>, String> {
-    if let Some(kb) = &state.knowledge_bank {
+    if let Some(kb) = state.namespaces.for_channel(crate::chat::ChannelType::Topic) {
         kb.get_recent_topics(limit.unwrap_or(10)).await
     } else {
         Ok(Vec::new())
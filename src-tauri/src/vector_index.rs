@@ -0,0 +1,499 @@
+// Vector index - sqlite-vec `vec0` tables for KNN search over stored embeddings
+//
+// The BLOB tables (`embeddings`, `chat_embeddings`) stay the source of truth;
+// the `vec0` tables are a derived index, one per source and dimension, with
+// the embedding model as partition key. Without the extension, searches fall
+// back to the old brute-force scan.
+
+use crate::knowledge::{Embedding, KnowledgeBank};
+use crate::logger::{LogLevel, Logger};
+use sqlx::{sqlite::SqlitePool, Row};
+use std::sync::{Arc, Once};
+
+/// Largest `k` sqlite-vec accepts in a KNN query
+const MAX_K: usize = 4096;
+
+/// Register sqlite-vec for every SQLite connection opened afterwards
+pub fn register_extension() {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| unsafe {
+        type EntryPoint = unsafe extern "C" fn(
+            *mut libsqlite3_sys::sqlite3,
+            *mut *mut std::os::raw::c_char,
+            *const libsqlite3_sys::sqlite3_api_routines,
+        ) -> std::os::raw::c_int;
+        libsqlite3_sys::sqlite3_auto_extension(Some(std::mem::transmute::<*const (), EntryPoint>(
+            sqlite_vec::sqlite3_vec_init as *const (),
+        )));
+    });
+}
+
+/// Which embedding table a vector belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorSource {
+    /// Deliberation text chunks (`embeddings`)
+    Chunks,
+    /// Chat messages (`chat_embeddings`), scoped by channel
+    Chat,
+    /// Ingested document chunks (`document_embeddings`)
+    Documents,
+}
+
+impl VectorSource {
+    /// Stable name used when a source is stored as text (job and queue tables)
+    pub fn key(self) -> &'static str {
+        match self {
+            VectorSource::Chunks => "chunks",
+            VectorSource::Chat => "chat",
+            VectorSource::Documents => "documents",
+        }
+    }
+
+    /// Source stored under `key`
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "chunks" => Some(VectorSource::Chunks),
+            "chat" => Some(VectorSource::Chat),
+            "documents" => Some(VectorSource::Documents),
+            _ => None,
+        }
+    }
+
+    fn blob_table(self) -> &'static str {
+        match self {
+            VectorSource::Chunks => "embeddings",
+            VectorSource::Chat => "chat_embeddings",
+            VectorSource::Documents => "document_embeddings",
+        }
+    }
+
+    fn id_column(self) -> &'static str {
+        match self {
+            VectorSource::Chunks | VectorSource::Documents => "chunk_id",
+            VectorSource::Chat => "message_id",
+        }
+    }
+
+    /// SQL expression for the scope of a blob row aliased `e`
+    fn scope_sql(self) -> &'static str {
+        match self {
+            VectorSource::Chunks | VectorSource::Documents => "''",
+            VectorSource::Chat => "COALESCE((SELECT channel FROM chat_logs WHERE id = e.message_id), '')",
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            VectorSource::Chunks => "vec_chunks_",
+            VectorSource::Chat => "vec_chat_",
+            VectorSource::Documents => "vec_docs_",
+        }
+    }
+
+    /// Name of the `vec0` table holding vectors of `dimension`
+    pub fn table(self, dimension: usize) -> String {
+        format!("{}{}", self.prefix(), dimension)
+    }
+}
+
+/// KNN index over the knowledge bank's embeddings
+pub struct VectorIndex {
+    pool: SqlitePool,
+    knn: bool,
+}
+
+impl VectorIndex {
+    /// Wrap `pool`, checking whether sqlite-vec is loaded on its connections
+    pub async fn open(pool: SqlitePool, logger: &Arc<Logger>) -> Self {
+        let knn = match sqlx::query("SELECT vec_version() AS version").fetch_one(&pool).await {
+            Ok(row) => {
+                logger.log(
+                    LogLevel::Debug,
+                    "knowledge",
+                    &format!("📐 sqlite-vec {} loaded", row.get::<String, _>("version")),
+                );
+                true
+            }
+            Err(e) => {
+                logger.log(
+                    LogLevel::Warning,
+                    "knowledge",
+                    &format!("⚠️ sqlite-vec unavailable ({}), using full-scan search", e),
+                );
+                false
+            }
+        };
+        Self { pool, knn }
+    }
+
+    /// Whether searches use `vec0` KNN queries rather than a full scan
+    pub fn uses_knn(&self) -> bool {
+        self.knn
+    }
+
+    /// Create the `vec0` table for `dimension` if needed; returns its name
+    async fn ensure_table(&self, source: VectorSource, dimension: usize) -> Result<String, String> {
+        let table = source.table(dimension);
+        sqlx::query(&format!(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS {} USING vec0(\
+                item_id TEXT PRIMARY KEY, \
+                model TEXT PARTITION KEY, \
+                scope TEXT, \
+                embedding float[{}] distance_metric=cosine)",
+            table, dimension
+        ))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", table, e))?;
+        Ok(table)
+    }
+
+    /// Existing `vec0` tables for `source` (shadow tables excluded)
+    async fn tables(&self, source: VectorSource) -> Result<Vec<String>, String> {
+        let rows = sqlx::query(
+            "SELECT name FROM sqlite_master WHERE name LIKE ? AND sql LIKE 'CREATE VIRTUAL TABLE%'",
+        )
+        .bind(format!("{}%", source.prefix()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to list vector tables: {}", e))?;
+        Ok(rows.iter().map(|r| r.get("name")).collect())
+    }
+
+    /// Copy BLOB rows missing from the index (and drop stale ones); returns rows added
+    pub async fn migrate(&self, source: VectorSource) -> Result<usize, String> {
+        if !self.knn {
+            return Ok(0);
+        }
+
+        let blob_table = source.blob_table();
+        let id_column = source.id_column();
+        let dimensions = sqlx::query(&format!(
+            "SELECT dimension, COUNT(*) AS n FROM {} GROUP BY dimension",
+            blob_table
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to inspect {}: {}", blob_table, e))?;
+
+        let mut added = 0;
+        for row in dimensions {
+            let dimension = row.get::<i64, _>("dimension") as usize;
+            let expected: i64 = row.get("n");
+            let table = self.ensure_table(source, dimension).await?;
+
+            let indexed: i64 = sqlx::query(&format!("SELECT COUNT(*) AS n FROM {}", table))
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| format!("Failed to count {}: {}", table, e))?
+                .get("n");
+            if indexed == expected {
+                continue;
+            }
+
+            sqlx::query(&format!(
+                "DELETE FROM {table} WHERE item_id NOT IN \
+                    (SELECT {id_column} FROM {blob_table} WHERE dimension = ?)"
+            ))
+            .bind(dimension as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to prune {}: {}", table, e))?;
+
+            let result = sqlx::query(&format!(
+                "INSERT INTO {table} (item_id, model, scope, embedding) \
+                 SELECT e.{id_column}, COALESCE(e.model, ''), {scope}, e.embedding \
+                 FROM {blob_table} e \
+                 WHERE e.dimension = ? AND e.{id_column} NOT IN (SELECT item_id FROM {table})",
+                scope = source.scope_sql()
+            ))
+            .bind(dimension as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to migrate {} into {}: {}", blob_table, table, e))?;
+            added += result.rows_affected() as usize;
+        }
+
+        Ok(added)
+    }
+
+    /// Drop the index for `source` and rebuild it from the BLOB table
+    pub async fn rebuild(&self, source: VectorSource) -> Result<usize, String> {
+        if !self.knn {
+            return Ok(0);
+        }
+
+        for table in self.tables(source).await? {
+            sqlx::query(&format!("DROP TABLE {}", table))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| format!("Failed to drop {}: {}", table, e))?;
+        }
+        self.migrate(source).await
+    }
+
+    /// Index (or re-index) one vector
+    pub async fn upsert(
+        &self,
+        source: VectorSource,
+        id: &str,
+        scope: &str,
+        embedding: &Embedding,
+    ) -> Result<(), String> {
+        if !self.knn {
+            return Ok(());
+        }
+
+        // vec0 has no INSERT OR REPLACE, and the dimension may have changed
+        self.remove(source, id).await?;
+
+        let table = self.ensure_table(source, embedding.dimension).await?;
+        sqlx::query(&format!(
+            "INSERT INTO {} (item_id, model, scope, embedding) VALUES (?, ?, ?, ?)",
+            table
+        ))
+        .bind(id)
+        .bind(&embedding.model)
+        .bind(scope)
+        .bind(KnowledgeBank::serialize_embedding(&embedding.vector))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to index {}: {}", id, e))?;
+
+        Ok(())
+    }
+
+    /// Drop one vector from every dimension's table
+    pub async fn remove(&self, source: VectorSource, id: &str) -> Result<(), String> {
+        if !self.knn {
+            return Ok(());
+        }
+
+        for table in self.tables(source).await? {
+            sqlx::query(&format!("DELETE FROM {} WHERE item_id = ?", table))
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| format!("Failed to unindex {}: {}", id, e))?;
+        }
+        Ok(())
+    }
+
+    /// Drop every indexed vector in `scope`
+    pub async fn remove_scope(&self, source: VectorSource, scope: &str) -> Result<(), String> {
+        if !self.knn {
+            return Ok(());
+        }
+
+        for table in self.tables(source).await? {
+            sqlx::query(&format!("DELETE FROM {} WHERE scope = ?", table))
+                .bind(scope)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| format!("Failed to clear {}: {}", table, e))?;
+        }
+        Ok(())
+    }
+
+    /// Nearest `limit` items to `query` as (id, cosine similarity), best first
+    pub async fn nearest(
+        &self,
+        source: VectorSource,
+        query: &Embedding,
+        limit: usize,
+        scope: Option<&str>,
+    ) -> Result<Vec<(String, f32)>, String> {
+        if self.knn {
+            self.knn_search(source, query, limit, scope).await
+        } else {
+            self.full_scan(source, query, limit, scope).await
+        }
+    }
+
+    /// KNN query against the `vec0` table for the query's dimension
+    pub async fn knn_search(
+        &self,
+        source: VectorSource,
+        query: &Embedding,
+        limit: usize,
+        scope: Option<&str>,
+    ) -> Result<Vec<(String, f32)>, String> {
+        let table = source.table(query.dimension);
+        if !self.tables(source).await?.contains(&table) {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT item_id, distance FROM {} \
+             WHERE embedding MATCH ? AND k = ? AND model = ?{} \
+             ORDER BY distance",
+            table,
+            if scope.is_some() { " AND scope = ?" } else { "" }
+        );
+        let mut q = sqlx::query(&sql)
+            .bind(KnowledgeBank::serialize_embedding(&query.vector))
+            .bind(limit.clamp(1, MAX_K) as i64)
+            .bind(&query.model);
+        if let Some(scope) = scope {
+            q = q.bind(scope);
+        }
+
+        let rows = q
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("KNN search on {} failed: {}", table, e))?;
+
+        Ok(rows
+            .iter()
+            .map(|r| (r.get("item_id"), 1.0 - r.get::<f64, _>("distance") as f32))
+            .collect())
+    }
+
+    /// Brute-force cosine similarity over every compatible BLOB row
+    pub async fn full_scan(
+        &self,
+        source: VectorSource,
+        query: &Embedding,
+        limit: usize,
+        scope: Option<&str>,
+    ) -> Result<Vec<(String, f32)>, String> {
+        let sql = format!(
+            "SELECT e.{id} AS item_id, e.embedding FROM {table} e \
+             WHERE e.dimension = ? AND e.model = ?{filter}",
+            id = source.id_column(),
+            table = source.blob_table(),
+            filter = if scope.is_some() {
+                format!(" AND {} = ?", source.scope_sql())
+            } else {
+                String::new()
+            }
+        );
+        let mut q = sqlx::query(&sql)
+            .bind(query.dimension as i64)
+            .bind(&query.model);
+        if let Some(scope) = scope {
+            q = q.bind(scope);
+        }
+
+        let rows = q
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to fetch embeddings: {}", e))?;
+
+        let mut results: Vec<(String, f32)> = rows
+            .iter()
+            .map(|r| {
+                let vector = KnowledgeBank::deserialize_embedding(&r.get::<Vec<u8>, _>("embedding"));
+                (r.get("item_id"), KnowledgeBank::cosine_similarity(&query.vector, &vector))
+            })
+            .collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn index() -> (SqlitePool, VectorIndex) {
+        register_extension();
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE embeddings (chunk_id TEXT PRIMARY KEY, embedding BLOB NOT NULL, dimension INTEGER NOT NULL, model TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let index = VectorIndex::open(pool.clone(), &Arc::new(Logger::new(false))).await;
+        (pool, index)
+    }
+
+    fn embedding(vector: Vec<f32>, model: &str) -> Embedding {
+        Embedding { dimension: vector.len(), vector, model: model.to_string() }
+    }
+
+    #[tokio::test]
+    async fn test_knn_matches_full_scan_after_migration() {
+        let (pool, index) = index().await;
+        assert!(index.uses_knn());
+
+        let rows = [
+            ("a", vec![1.0, 0.0, 0.0], "m1"),
+            ("b", vec![0.6, 0.8, 0.0], "m1"),
+            ("c", vec![0.0, 0.0, 1.0], "m1"),
+            ("d", vec![1.0, 0.0, 0.0], "m2"),
+        ];
+        for (id, vector, model) in rows {
+            sqlx::query("INSERT INTO embeddings VALUES (?, ?, 3, ?)")
+                .bind(id)
+                .bind(KnowledgeBank::serialize_embedding(&vector))
+                .bind(model)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        assert_eq!(index.migrate(VectorSource::Chunks).await.unwrap(), 4);
+        assert_eq!(index.migrate(VectorSource::Chunks).await.unwrap(), 0);
+
+        let query = embedding(vec![0.9, 0.1, 0.0], "m1");
+        let knn = index.knn_search(VectorSource::Chunks, &query, 2, None).await.unwrap();
+        let scan = index.full_scan(VectorSource::Chunks, &query, 2, None).await.unwrap();
+        let ids: Vec<&str> = knn.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        for ((knn_id, knn_score), (scan_id, scan_score)) in knn.iter().zip(&scan) {
+            assert_eq!(knn_id, scan_id);
+            assert!((knn_score - scan_score).abs() < 1e-4);
+        }
+
+        // Re-indexing under a new dimension moves the vector between tables
+        index.upsert(VectorSource::Chunks, "a", "", &embedding(vec![1.0, 0.0], "m1")).await.unwrap();
+        let knn = index.knn_search(VectorSource::Chunks, &query, 5, None).await.unwrap();
+        assert_eq!(knn.len(), 2);
+        assert_eq!(knn[0].0, "b");
+    }
+
+    /// `cargo test --release bench_knn_vs_full_scan -- --ignored --nocapture`
+    #[tokio::test]
+    #[ignore]
+    async fn bench_knn_vs_full_scan() {
+        use rand::Rng;
+
+        const ROWS: usize = 20_000;
+        const DIMENSION: usize = 768;
+        const QUERIES: usize = 20;
+
+        let (pool, index) = index().await;
+        let mut rng = rand::thread_rng();
+        let mut random = || embedding((0..DIMENSION).map(|_| rng.gen_range(-1.0..1.0)).collect(), "bench");
+
+        let mut tx = pool.begin().await.unwrap();
+        for i in 0..ROWS {
+            sqlx::query("INSERT INTO embeddings VALUES (?, ?, ?, 'bench')")
+                .bind(i.to_string())
+                .bind(KnowledgeBank::serialize_embedding(&random().vector))
+                .bind(DIMENSION as i64)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        let started = std::time::Instant::now();
+        index.migrate(VectorSource::Chunks).await.unwrap();
+        println!("migrated {} x {} dims in {:?}", ROWS, DIMENSION, started.elapsed());
+
+        let queries: Vec<Embedding> = (0..QUERIES).map(|_| random()).collect();
+        for (name, knn) in [("full scan", false), ("vec0 knn", true)] {
+            let started = std::time::Instant::now();
+            for query in &queries {
+                let hits = if knn {
+                    index.knn_search(VectorSource::Chunks, query, 10, None).await.unwrap()
+                } else {
+                    index.full_scan(VectorSource::Chunks, query, 10, None).await.unwrap()
+                };
+                assert_eq!(hits.len(), 10);
+            }
+            println!("{:>9}: {:?} per query", name, started.elapsed() / QUERIES as u32);
+        }
+    }
+}
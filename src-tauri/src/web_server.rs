@@ -41,6 +41,15 @@ pub struct CreateAgentRequest {
 pub struct CouncilSessionRequest {
     pub question: String,
     pub agent_ids: Vec<String>,
+    /// Images for vision-capable agents (others get a text description)
+    #[serde(default)]
+    pub attachments: Vec<crate::providers::Attachment>,
+    /// Knowledge namespace (council) to deliberate in; omitted = default
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Ask the agents again instead of replaying cached answers
+    #[serde(default)]
+    pub bypass_cache: bool,
 }
 
 #[derive(Deserialize)]
@@ -50,6 +59,8 @@ pub struct SendMessageRequest {
     pub author_type: String,
     pub content: String,
     pub signature: Option<String>,
+    #[serde(default)]
+    pub attachments: Vec<crate::providers::Attachment>,
 }
 
 #[derive(Deserialize)]
@@ -65,6 +76,11 @@ pub struct TopicSetRequest {
     pub interval: u64,
 }
 
+#[derive(Deserialize)]
+pub struct NamespaceQuery {
+    pub namespace: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct TopicHistoryRequest {
     pub limit: Option<usize>,
@@ -287,8 +303,14 @@ async fn reset_agent_identity(
 }
 
 // Council endpoints
-async fn list_council_sessions(State(state): State<WebState>) -> Response {
-    let sessions = state.council_manager.list_sessions().await;
+async fn list_council_sessions(
+    State(state): State<WebState>,
+    Query(req): Query<NamespaceQuery>,
+) -> Response {
+    let sessions = match req.namespace {
+        Some(namespace) => state.council_manager.list_sessions_in(Some(namespace.as_str())).await,
+        None => state.council_manager.list_sessions().await,
+    };
     (StatusCode::OK, Json(ApiResponse::ok(CouncilSessionsListResponse { sessions }))).into_response()
 }
 
@@ -319,8 +341,11 @@ async fn create_council_session(
 
     match state
         .council_manager
-        .create_session_with_agents(
+        .create_session_with_agents_and_attachments(
             req.question,
+            req.attachments,
+            req.namespace,
+            req.bypass_cache,
             state.agent_pool.clone(),
             req.agent_ids,
             &ollama_url,
@@ -372,7 +397,8 @@ async fn send_message(
         }
     };
 
-    let mut message = Message::new(channel_type, req.author, author_type, req.content);
+    let mut message = Message::new(channel_type, req.author, author_type, req.content)
+        .with_attachments(req.attachments);
 
     if let Some(sig) = req.signature {
         message = message.with_signature(sig);
@@ -475,7 +501,7 @@ async fn get_topic_history(
 ) -> Response {
     let limit = req.limit.unwrap_or(10) as i64;
     
-    let history = if let Some(kb) = &state.app_state.knowledge_bank {
+    let history = if let Some(kb) = state.app_state.namespaces.for_channel(crate::chat::ChannelType::Topic) {
         match kb.get_recent_topics(limit).await {
             Ok(h) => h,
             Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<Vec<(String, i64)>>::err(e))).into_response(),
@@ -110,6 +110,20 @@ export interface ChatMessage {
   signature?: string;
   reply_to?: string;
   reactions: Reaction[];
+  /** Model's <think> trace, split from content (debugging only) */
+  reasoning?: string;
+  /** Past verdicts / documents from the RAG context that the reply cited */
+  citations?: Citation[];
+}
+
+export interface Citation {
+  /** ID shown to the agent, e.g. "V-3f2a9c1d" or "DOC-5b0e41aa" */
+  id: string;
+  kind: "verdict" | "document";
+  /** Deliberation or document id */
+  record_id: string;
+  title: string;
+  location?: string;
 }
 
 export interface DuplicateCheckResult {
@@ -210,25 +224,30 @@ export interface CouncilSession {
   consensus: string | null;
   status: SessionStatus;
   created_at: number;
+  /** Knowledge namespace (council); absent for the default one */
+  namespace?: string;
 }
 
 // Council session commands
-export async function councilCreateSession(question: string): Promise<string> {
+export async function councilCreateSession(question: string, namespace?: string): Promise<string> {
   return await apiCall(
     "council_create_session",
     "POST /api/council/create",
-    { question, agent_ids: [] }
+    { question, agent_ids: [], namespace }
   );
 }
 
 export async function councilCreateSessionWithAgents(
   question: string,
-  agentIds: string[]
+  agentIds: string[],
+  namespace?: string,
+  /** Ask the agents again instead of replaying cached answers */
+  bypassCache: boolean = false
 ): Promise<string> {
   return await apiCall(
     "council_create_session_with_agents",
     "POST /api/council/create",
-    { question, agent_ids: agentIds }
+    { question, agent_ids: agentIds, namespace, bypass_cache: bypassCache }
   );
 }
 
@@ -240,10 +259,12 @@ export async function councilGetSession(sessionId: string): Promise<CouncilSessi
   );
 }
 
-export async function councilListSessions(): Promise<{sessions: CouncilSession[]}> {
+/** All sessions, or only those of `namespace` */
+export async function councilListSessions(namespace?: string): Promise<{sessions: CouncilSession[]}> {
   return await apiCall(
     "council_list_sessions",
-    "GET /api/council/sessions"
+    "GET /api/council/sessions",
+    namespace ? { namespace } : undefined
   );
 }
 
@@ -415,9 +436,10 @@ export async function chatGetMessageCount(
 }
 
 export async function chatCheckDuplicate(
-  question: string
+  question: string,
+  namespace?: string
 ): Promise<DuplicateCheckResult> {
-  return await tauriInvoke('chat_check_duplicate', { question });
+  return await tauriInvoke('chat_check_duplicate', { question, namespace });
 }
 
 // Rate limiting types
@@ -727,11 +749,243 @@ export interface SearchResult {
   question: string;
   relevance_score: number;
   text_snippet: string;
+  /** Present when the hit is an ingested document (deliberation_id is then the document id) */
+  document?: { source_path: string; heading: string | null };
+  /** Namespace the hit came from (cross-namespace searches only) */
+  namespace?: string;
+}
+
+/** A separate council with its own knowledge bank */
+export interface NamespaceInfo {
+  name: string;
+  description: string | null;
+  agents: string[];
+  channels: string[];
+  /** Whether its knowledge bank is open */
+  open: boolean;
+}
+
+export interface DocumentInfo {
+  id: string;
+  source_path: string;
+  title: string;
+  format: string;
+  content_hash: string;
+  chunk_count: number;
+  ingested_at: number;
+}
+
+export interface IngestReport {
+  ingested: string[];
+  unchanged: string[];
+  failed: [string, string][];
+  chunks: number;
+}
+
+export interface ExportReport {
+  path: string;
+  deliberations: number;
+  chunks: number;
+  embeddings: number;
+  reputations: number;
+}
+
+export interface ImportReport {
+  /** Fingerprint of the key that signed the bundle */
+  signer: string;
+  /** Unix time the exporting node wrote the bundle */
+  exported_at: number;
+  deliberations: number;
+  chunks: number;
+  embeddings: number;
+  reputations: number;
+  /** Records skipped because their ID already existed */
+  duplicates: number;
+  queued_for_embedding: number;
+}
+
+export interface EmbeddingQueueStatus {
+  /** Items still awaiting an embedding */
+  pending: number;
+  /** Of those, items that failed at least once and are waiting to retry */
+  retrying: number;
+  last_error: string | null;
+  next_attempt_at: number | null;
+}
+
+export interface ReembedJob {
+  id: string;
+  target_model: string;
+  status: "running" | "completed" | "cancelled";
+  total: number;
+  done: number;
+  last_error: string | null;
+  started_at: number;
+  updated_at: number;
+  finished_at: number | null;
+}
+
+export type VerdictRelation = "consistent" | "refining" | "contradicting";
+
+/** Judged relation between a newer verdict and an earlier, similar one */
+export interface VerdictLink {
+  deliberation_id: string;
+  related_id: string;
+  relation: VerdictRelation;
+  reason: string;
+  /** Cosine similarity of the two questions */
+  similarity: number;
+  /** Name of the judging agent */
+  judge: string;
+  created_at: number;
+}
+
+/** A logged retention prune or context reset */
+export interface RetentionEvent {
+  id: number;
+  channel: ChannelType;
+  kind: "prune" | "reset";
+  initiated_by: "schedule" | "manual";
+  /** Messages deleted (prune) or taken out of the RAG context (reset) */
+  messages: number;
+  archive_path: string | null;
+  created_at: number;
 }
 
+export interface LedgerEntry {
+  seq: number;
+  deliberation_id: string;
+  question: string;
+  verdict: string;
+  recorded_at: number;
+  prev_hash: string;
+  payload_hash: string;
+  entry_hash: string;
+}
+
+/** Ledger head signed by a node key */
+export interface LedgerCheckpoint {
+  seq: number;
+  head_hash: string;
+  signed_at: number;
+  public_key: string;
+  signature: string;
+}
+
+export interface InclusionProof {
+  entry: LedgerEntry;
+  /** Payload hashes of the entries between `entry` and the checkpoint */
+  following: string[];
+  checkpoint: LedgerCheckpoint;
+}
+
+export interface LedgerVerification {
+  valid: boolean;
+  entries: number;
+  checkpoints: number;
+  head_hash: string | null;
+  problems: string[];
+}
+
+/** semantic = embeddings only, lexical = full-text (BM25) only, hybrid = both fused */
+export type SearchMode = "semantic" | "lexical" | "hybrid";
+
 // Knowledge Bank Commands
-export async function kbSearch(query: string, limit: number = 10): Promise<SearchResult[]> {
-  return await apiCall<SearchResult[]>("kb_search", "GET /api/knowledge/search", { query, limit });
+/** Search one namespace (default when omitted), or every namespace with `crossNamespace` */
+export async function kbSearch(
+  query: string,
+  limit: number = 10,
+  mode: SearchMode = "hybrid",
+  namespace?: string,
+  crossNamespace: boolean = false
+): Promise<SearchResult[]> {
+  return await apiCall<SearchResult[]>("kb_search", "GET /api/knowledge/search", {
+    query,
+    limit,
+    mode,
+    ...(namespace ? { namespace } : {}),
+    ...(crossNamespace ? { crossNamespace } : {}),
+  });
+}
+
+export async function namespaceList(): Promise<NamespaceInfo[]> {
+  return await apiCall<NamespaceInfo[]>("namespace_list", "GET /api/namespaces");
+}
+
+/** Open a namespace's knowledge bank, creating it on first use */
+export async function namespaceCreate(name: string): Promise<NamespaceInfo[]> {
+  return await apiCall<NamespaceInfo[]>("namespace_create", "POST /api/namespaces", { name });
+}
+
+/** Ingest a markdown/text/HTML/PDF file or a whole directory (server-side path) */
+export async function kbIngest(path: string, namespace?: string): Promise<IngestReport> {
+  return await apiCall<IngestReport>("kb_ingest", "POST /api/knowledge/ingest", { path, namespace });
+}
+
+export async function kbListDocuments(namespace?: string): Promise<DocumentInfo[]> {
+  return await apiCall<DocumentInfo[]>("kb_list_documents", "GET /api/knowledge/documents", { namespace });
+}
+
+export async function kbRemoveDocument(id: string, namespace?: string): Promise<boolean> {
+  return await apiCall<boolean>("kb_remove_document", "POST /api/knowledge/documents/remove", { id, namespace });
+}
+
+/** Write a signed .tar.gz bundle of the knowledge bank (server-side path) */
+export async function kbExport(path: string, namespace?: string): Promise<ExportReport> {
+  return await apiCall<ExportReport>("kb_export", "POST /api/knowledge/export", { path, namespace });
+}
+
+/** Verify and import a bundle; `trustedKey` pins the signer (public key or fingerprint) */
+export async function kbImport(path: string, trustedKey?: string, reembed: boolean = true, namespace?: string): Promise<ImportReport> {
+  return await apiCall<ImportReport>("kb_import", "POST /api/knowledge/import", { path, trustedKey, reembed, namespace });
+}
+
+/** Consistent / refining / contradicting links from or to a deliberation's verdict */
+export async function kbVerdictLinks(deliberationId: string, namespace?: string): Promise<VerdictLink[]> {
+  return await apiCall<VerdictLink[]>("kb_verdict_links", "GET /api/knowledge/verdict-links", { deliberationId, namespace });
+}
+
+/** Clear a channel's RAG context now (messages stay visible) */
+export async function kbClearChannelContext(channel: ChannelType): Promise<RetentionEvent> {
+  return await apiCall<RetentionEvent>("kb_clear_channel_context", "POST /api/knowledge/clear-context", { channel });
+}
+
+/** Apply the `channel_retention` policies immediately */
+export async function retentionRun(): Promise<RetentionEvent[]> {
+  return await apiCall<RetentionEvent[]>("retention_run", "POST /api/retention/run");
+}
+
+export async function retentionHistory(channel?: ChannelType, limit: number = 50, namespace?: string): Promise<RetentionEvent[]> {
+  return await apiCall<RetentionEvent[]>("retention_history", "GET /api/retention/history", { channel, limit, namespace });
+}
+
+/** Check the verdict ledger; `trustedKey` pins the checkpoint signer (public key or fingerprint) */
+export async function ledgerVerify(trustedKey?: string, namespace?: string): Promise<LedgerVerification> {
+  return await apiCall<LedgerVerification>("ledger_verify", "GET /api/ledger/verify", { trustedKey, namespace });
+}
+
+/** Inclusion proof for a deliberation's verdict (checkable offline with `council-ledger check`) */
+export async function ledgerProve(deliberationId: string, namespace?: string): Promise<InclusionProof> {
+  return await apiCall<InclusionProof>("ledger_prove", "GET /api/ledger/proof", { deliberationId, namespace });
+}
+
+/** Sign the current ledger head (null if it is already checkpointed) */
+export async function ledgerCheckpoint(namespace?: string): Promise<LedgerCheckpoint | null> {
+  return await apiCall<LedgerCheckpoint | null>("ledger_checkpoint", "POST /api/ledger/checkpoint", { namespace });
+}
+
+export async function kbEmbeddingQueueStatus(): Promise<EmbeddingQueueStatus> {
+  return await apiCall<EmbeddingQueueStatus>("kb_embedding_queue_status", "GET /api/knowledge/embedding-queue");
+}
+
+/** Latest re-embedding job (null if the embedding model never changed) */
+export async function kbReembedStatus(): Promise<ReembedJob | null> {
+  return await apiCall<ReembedJob | null>("kb_reembed_status", "GET /api/knowledge/reembed");
+}
+
+/** Re-embed vectors made with another model than the configured one, in the background */
+export async function kbReembedStart(): Promise<ReembedJob | null> {
+  return await apiCall<ReembedJob | null>("kb_reembed_start", "POST /api/knowledge/reembed");
 }
 
 // Topic Control Types